@@ -254,6 +254,126 @@ fn proof_list_extend(b: &mut Bencher<'_>, len: usize) {
     );
 }
 
+fn proof_list_extend_from_slice(b: &mut Bencher<'_>, len: usize) {
+    let data = generate_random_values(len);
+    let db = BenchDB::default();
+    b.iter_with_setup(
+        || db.fork(),
+        |fork| {
+            let mut table = fork.get_proof_list(NAME);
+            assert!(table.is_empty());
+            table.extend_from_slice(&data);
+        },
+    );
+}
+
+fn proof_list_iter_hashes(b: &mut Bencher<'_>, len: usize) {
+    let mut rng: StdRng = SeedableRng::from_seed(SEED);
+    let db = BenchDB::default();
+    let fork = db.fork();
+    fork.get_proof_list(NAME)
+        .extend((0..len).map(|_| Hash::new(rng.gen())));
+    db.merge_sync(fork.into_patch()).unwrap();
+
+    b.iter_with_setup(
+        || db.snapshot(),
+        |snapshot| {
+            let index = snapshot.get_proof_list::<_, Hash>(NAME);
+            for hash in &index {
+                black_box(hash);
+            }
+        },
+    );
+}
+
+fn proof_list_get_sequential(b: &mut Bencher<'_>, len: usize) {
+    let mut rng: StdRng = SeedableRng::from_seed(SEED);
+    let db = BenchDB::default();
+    let fork = db.fork();
+    fork.get_proof_list(NAME)
+        .extend((0..len).map(|_| Hash::new(rng.gen())));
+    db.merge_sync(fork.into_patch()).unwrap();
+
+    b.iter_with_setup(
+        || db.snapshot(),
+        |snapshot| {
+            let index = snapshot.get_proof_list::<_, Hash>(NAME);
+            for i in 0..len as u64 {
+                black_box(index.get(i));
+            }
+        },
+    );
+}
+
+/// Fills a list with values, 10% of which start with a zero byte.
+fn fill_tagged_proof_list(db: &BenchDB, len: usize) {
+    const VALUE_SIZE: usize = 256;
+
+    let mut rng: StdRng = SeedableRng::from_seed(SEED);
+    let fork = db.fork();
+    fork.get_proof_list(NAME).extend((0..len).map(|i| {
+        let mut value = vec![0_u8; VALUE_SIZE];
+        rng.fill_bytes(&mut value);
+        value[0] = (i % 10) as u8;
+        value
+    }));
+    db.merge_sync(fork.into_patch()).unwrap();
+}
+
+fn proof_list_iter_filter(b: &mut Bencher<'_>, len: usize) {
+    let db = BenchDB::default();
+    fill_tagged_proof_list(&db, len);
+
+    b.iter_with_setup(
+        || db.snapshot(),
+        |snapshot| {
+            let index = snapshot.get_proof_list::<_, Vec<u8>>(NAME);
+            for value in index.iter().filter(|value| value[0] == 0) {
+                black_box(value);
+            }
+        },
+    );
+}
+
+fn proof_list_iter_filter_raw(b: &mut Bencher<'_>, len: usize) {
+    let db = BenchDB::default();
+    fill_tagged_proof_list(&db, len);
+
+    b.iter_with_setup(
+        || db.snapshot(),
+        |snapshot| {
+            let index = snapshot.get_proof_list::<_, Vec<u8>>(NAME);
+            for value in index.iter_filter_raw(|bytes| bytes[0] == 0) {
+                black_box(value);
+            }
+        },
+    );
+}
+
+fn proof_list_iter_large_values(b: &mut Bencher<'_>, len: usize) {
+    const VALUE_SIZE: usize = 4_096;
+
+    let mut rng: StdRng = SeedableRng::from_seed(SEED);
+    let db = BenchDB::default();
+    let fork = db.fork();
+    fork.get_proof_list(NAME).extend((0..len).map(|_| {
+        let mut value = vec![0_u8; VALUE_SIZE];
+        rng.fill_bytes(&mut value);
+        value
+    }));
+    db.merge_sync(fork.into_patch()).unwrap();
+
+    b.iter_with_setup(
+        || db.snapshot(),
+        |snapshot| {
+            let index = snapshot.get_proof_list::<_, Vec<u8>>(NAME);
+            for value in &index {
+                black_box(value);
+            }
+        },
+    );
+}
+
 fn proof_map_insert_without_merge(b: &mut Bencher<'_>, len: usize) {
     let db = BenchDB::default();
     let data = generate_random_kv(len);
@@ -457,6 +577,24 @@ pub fn bench_storage(c: &mut Criterion) {
     // ProofListIndex
     bench_fn(c, "storage/proof_list/append", proof_list_append);
     bench_fn(c, "storage/proof_list/extend", proof_list_extend);
+    bench_fn(
+        c,
+        "storage/proof_list/extend_from_slice",
+        proof_list_extend_from_slice,
+    );
+    bench_fn(c, "storage/proof_list/get", proof_list_get_sequential);
+    bench_fn(c, "storage/proof_list/iter", proof_list_iter_hashes);
+    bench_fn(
+        c,
+        "storage/proof_list/iter_large_values",
+        proof_list_iter_large_values,
+    );
+    bench_fn(c, "storage/proof_list/iter_filter", proof_list_iter_filter);
+    bench_fn(
+        c,
+        "storage/proof_list/iter_filter_raw",
+        proof_list_iter_filter_raw,
+    );
     bench_fn(
         c,
         "storage/proof_list/proofs/build",
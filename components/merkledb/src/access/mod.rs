@@ -283,6 +283,11 @@ pub enum AccessErrorKind {
     #[error("Invalid tombstone location. Tombstones can only be created in migrations")]
     InvalidTombstone,
 
+    /// `ProofListIndex` with a non-default hasher is accessed outside of a group. Such a list
+    /// would be aggregated into the state hash, which always uses the default hasher.
+    #[error("Lists with a non-default hasher can only be created in groups")]
+    AggregatedCustomHasher,
+
     /// Custom error.
     #[error("{0}")]
     Custom(#[source] anyhow::Error),
@@ -25,7 +25,8 @@ use std::{fmt, iter::Peekable, mem, path::Path, sync::Arc};
 
 use crate::{
     db::{check_database, Change},
-    Database, DbOptions, Iter, Iterator, Patch, ResolvedAddress, Snapshot,
+    views::ViewWithMetadata,
+    Database, DbOptions, IndexAddress, Iter, Iterator, Patch, ResolvedAddress, Snapshot,
 };
 
 /// Size of a byte representation of an index ID, which is used to prefix index keys
@@ -109,6 +110,27 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Compacts the storage space occupied by the index with the specified address,
+    /// reclaiming space from removed entries. The index contents are not changed.
+    /// See [`RocksDB` docs] for details.
+    ///
+    /// This is a maintenance operation, which is performed synchronously and may block
+    /// for a considerable amount of time. Compaction does not influence the database state,
+    /// so it is not available via `Fork`s and should be triggered by node administration
+    /// tools rather than by service logic. If the index does not exist, the method
+    /// does nothing.
+    ///
+    /// [`RocksDB` docs]: https://github.com/facebook/rocksdb/wiki/Manual-Compaction
+    pub fn compact_index<I: Into<IndexAddress>>(&self, addr: I) {
+        let addr = addr.into();
+        let snapshot = self.snapshot();
+        let metadata = ViewWithMetadata::get_metadata_unchecked(&snapshot, &addr);
+        if let Some(metadata) = metadata {
+            let resolved = ResolvedAddress::new(addr.name(), Some(metadata.identifier()));
+            self.compact_prefix(&resolved);
+        }
+    }
+
     fn cf_exists(&self, cf_name: &str) -> bool {
         self.get_lock_guard().cf_handle(cf_name).is_some()
     }
@@ -202,6 +224,22 @@ impl RocksDB {
             .map_err(Into::into)
     }
 
+    /// Compacts the storage space occupied by the keys with the specified prefix.
+    fn compact_prefix(&self, resolved: &ResolvedAddress) {
+        let db_reader = self.get_lock_guard();
+        let cf = match db_reader.cf_handle(&resolved.name) {
+            Some(cf) => cf,
+            None => return,
+        };
+
+        if let Some(id_bytes) = resolved.id_to_bytes() {
+            let next_bytes = next_id_bytes(id_bytes);
+            db_reader.compact_range_cf(cf, Some(id_bytes), Some(next_bytes));
+        } else {
+            db_reader.compact_range_cf::<&[u8], &[u8]>(cf, None, None);
+        }
+    }
+
     /// Removes all keys with the specified prefix from a column family.
     fn clear_prefix(
         &self,
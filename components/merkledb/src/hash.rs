@@ -45,7 +45,7 @@ const EMPTY_MAP_HASH: [u8; HASH_SIZE] = [
 /// from the pre-image attack. See more information [here][rfc6962].
 ///
 /// [rfc6962]: https://tools.ietf.org/html/rfc6962#section-2.1
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
 #[non_exhaustive]
 pub enum HashTag {
@@ -111,6 +111,15 @@ impl HashTag {
             .hash()
     }
 
+    /// Verifies that a Merkelized list with the specified Merkle root has `len` elements,
+    /// given the trusted hash of the list.
+    ///
+    /// The length and the Merkle root can be obtained from the list using
+    /// `ProofListIndex::length_proof()`.
+    pub fn verify_length(len: u64, merkle_root: Hash, expected_list_hash: Hash) -> bool {
+        Self::hash_list_node(len, merkle_root) == expected_list_hash
+    }
+
     /// Obtains hash of an empty Merkelized list.
     ///
     /// ```text
@@ -125,6 +134,30 @@ impl HashTag {
         Self::hash_list_node(values.len() as u64, root_hash(values))
     }
 
+    /// Computes the hash for a Merkelized list given hashes of its elements, i.e.,
+    /// `HashTag::hash_leaf(value.to_bytes())` for each list element.
+    ///
+    /// The result is the same as for `hash_list` applied to the elements themselves. This is
+    /// useful, e.g., to compare test vectors with other implementations of Merkelized lists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{BinaryValue, HashTag};
+    /// let values = [1_u32, 2, 3];
+    /// let leaf_hashes: Vec<_> = values
+    ///     .iter()
+    ///     .map(|value| HashTag::hash_leaf(&value.to_bytes()))
+    ///     .collect();
+    /// assert_eq!(HashTag::list_hash_from_leaves(&leaf_hashes), HashTag::hash_list(&values));
+    /// ```
+    pub fn list_hash_from_leaves(leaf_hashes: &[Hash]) -> Hash {
+        Self::hash_list_node(
+            leaf_hashes.len() as u64,
+            root_hash_from_leaves(leaf_hashes.to_vec()),
+        )
+    }
+
     /// Obtains hash of a Merkelized map. `root` is the recursively defined
     /// hash of the root node of the binary Patricia Merkle tree corresponding to the map.
     ///
@@ -193,14 +226,19 @@ impl HashTag {
 ///
 /// If `hashes` are empty then `Hash::zero()` value is returned.
 pub fn root_hash<V: BinaryValue + ?Sized>(hashes: &[V]) -> Hash {
-    if hashes.is_empty() {
-        return Hash::zero();
-    }
-
-    let mut hashes: Vec<Hash> = hashes
+    let hashes: Vec<Hash> = hashes
         .iter()
         .map(|h| HashTag::hash_leaf(&h.to_bytes()))
         .collect();
+    root_hash_from_leaves(hashes)
+}
+
+/// Computes a Merkle root hash for the given leaf hashes. The `hashes` are used as a buffer
+/// for the hashes on the upper levels of the tree.
+fn root_hash_from_leaves(mut hashes: Vec<Hash>) -> Hash {
+    if hashes.is_empty() {
+        return Hash::zero();
+    }
 
     let mut end = hashes.len();
     let mut index = 0;
@@ -264,8 +302,15 @@ impl ObjectHash for [u8] {
 #[non_exhaustive]
 pub enum ValidationError<E: StdError + 'static> {
     /// The hash of the proof is not equal to the trusted root hash.
-    #[error("hash of the proof is not equal to the trusted hash of the index")]
-    UnmatchedRootHash,
+    #[error(
+        "hash of the proof ({actual}) is not equal to the trusted hash of the index ({expected})"
+    )]
+    UnmatchedRootHash {
+        /// Trusted hash of the index.
+        expected: Hash,
+        /// Hash restored from the proof.
+        actual: Hash,
+    },
 
     /// The proof is malformed.
     #[error("Malformed proof: {0}")]
@@ -292,6 +337,48 @@ mod tests {
         assert_eq!(empty_list_hash, HashTag::empty_list_hash());
     }
 
+    #[test]
+    fn list_hash_vectors() {
+        // Leaves are hashes of single-byte values `0, 1, 2, ...`.
+        let vectors = [
+            (
+                0,
+                "c6c0aa07f27493d2f2e5cff56c890a353a20086d6c25ec825128e12ae752b2d9",
+            ),
+            (
+                1,
+                "ef5f464056079ebafefc9f2ddcc9405475ba464ccd1d2234c41de669429e03bc",
+            ),
+            (
+                2,
+                "68db9a2835ddb88e18165a5c9d58f8c5f8821d46e73b8b5705f449941b1aac17",
+            ),
+            (
+                3,
+                "c347761aa694d26becc4a4ae773688362ce5b623fbff94ac138d71eb9c66fc85",
+            ),
+            (
+                5,
+                "50ea5f50aa16fc08ecba5e4794529882bb0458e84eec8eda330d57eff96fe297",
+            ),
+        ];
+
+        for &(len, expected_hash) in &vectors {
+            let values: Vec<u8> = (0..len).collect();
+            let leaf_hashes: Vec<_> = values
+                .iter()
+                .map(|value| HashTag::hash_leaf(&[*value]))
+                .collect();
+            let list_hash = HashTag::list_hash_from_leaves(&leaf_hashes);
+            assert_eq!(list_hash.to_hex(), expected_hash);
+            assert_eq!(list_hash, HashTag::hash_list(&values));
+        }
+        assert_eq!(
+            HashTag::list_hash_from_leaves(&[]),
+            HashTag::empty_list_hash()
+        );
+    }
+
     #[test]
     fn empty_map_hash() {
         let tag = 3;
@@ -16,8 +16,9 @@ use std::marker::PhantomData;
 
 use crate::{
     access::{Access, AccessError, FromAccess},
+    proof_list::{FamilyListProof, Hasher, ProofListIndex},
     views::{AsReadonly, GroupKeys, IndexAddress},
-    BinaryKey,
+    BinaryKey, BinaryValue,
 };
 
 // cspell:ignore foob
@@ -124,6 +125,57 @@ where
     }
 }
 
+impl<T, K, V, H> Group<T, K, ProofListIndex<T::Base, V, H>>
+where
+    T: Access,
+    K: BinaryKey + ?Sized,
+    V: BinaryValue,
+    H: Hasher + 'static,
+{
+    /// Returns the proof of existence for the list element at the specified position
+    /// in the list corresponding to `key`. The proof is tagged with the identifier
+    /// of the list within the group.
+    ///
+    /// See [`ProofListIndex::get_proof`] for details on the returned proof.
+    ///
+    /// # Panics
+    ///
+    /// If the index is present and has a wrong type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{
+    /// #     access::CopyAccessExt, Database, Group, ObjectHash, ProofListIndex, TemporaryDB,
+    /// # };
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let group: Group<_, str, ProofListIndex<_, u64>> = fork.get_group("group");
+    /// group.get("alice").extend(vec![1, 2, 3]);
+    ///
+    /// let family_proof = group.get_proof_in_family("alice", 2);
+    /// assert_eq!(family_proof.id, b"alice");
+    /// // The verifier uses the `id` to find out the trusted hash of the list.
+    /// let list_hash = group.get("alice").object_hash();
+    /// family_proof.proof.check_against_hash(list_hash).unwrap();
+    /// ```
+    ///
+    /// [`ProofListIndex::get_proof`]: ../proof_list/struct.ProofListIndex.html#method.get_proof
+    pub fn get_proof_in_family(&self, key: &K, index: u64) -> FamilyListProof<V, H> {
+        let addr = self.prefix.clone().append_key(key);
+        let id = addr
+            .id_in_group()
+            .expect("Address of a group member always has a key")
+            .to_vec();
+        let list = ProofListIndex::<_, V, H>::from_access(self.access.clone(), addr)
+            .unwrap_or_else(|e| panic!("MerkleDB error: {}", e));
+        FamilyListProof {
+            id,
+            proof: list.get_proof(index),
+        }
+    }
+}
+
 impl<T, K, I> Group<T, K, I>
 where
     T: Access,
@@ -158,8 +210,9 @@ mod tests {
     use crate::{
         access::{AccessExt, CopyAccessExt, Prefixed, RawAccessMut},
         migration::{Migration, Scratchpad},
-        Database, ProofListIndex, TemporaryDB,
+        Database, ObjectHash, ProofListIndex, TemporaryDB, ValidationError,
     };
+    use assert_matches::assert_matches;
 
     #[test]
     fn group() {
@@ -193,6 +246,38 @@ mod tests {
         // group.get(&3).push("quux".to_owned());
     }
 
+    #[test]
+    fn family_proofs() {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        let group: Group<_, str, ProofListIndex<_, u32>> = fork.get_group("group");
+        group.get("alice").extend(vec![1, 2, 3]);
+        group.get("bob").extend(vec![4, 5]);
+        let alice_hash = group.get("alice").object_hash();
+        let bob_hash = group.get("bob").object_hash();
+
+        let alice_proof = group.get_proof_in_family("alice", 1);
+        assert_eq!(alice_proof.id, b"alice");
+        let checked_proof = alice_proof.proof.check_against_hash(alice_hash).unwrap();
+        assert_eq!(checked_proof.entries(), [(1, 2)]);
+        let bob_proof = group.get_proof_in_family("bob", 1);
+        assert_eq!(bob_proof.id, b"bob");
+        let checked_proof = bob_proof.proof.check_against_hash(bob_hash).unwrap();
+        assert_eq!(checked_proof.entries(), [(1, 5)]);
+
+        // Proofs do not validate against the hash of another family member.
+        let err = alice_proof.proof.check_against_hash(bob_hash).unwrap_err();
+        assert_matches!(err, ValidationError::UnmatchedRootHash { .. });
+        let err = bob_proof.proof.check_against_hash(alice_hash).unwrap_err();
+        assert_matches!(err, ValidationError::UnmatchedRootHash { .. });
+
+        // The identifier of a member in a nested group includes the outer key.
+        let outer: Group<_, u8, Group<_, str, ProofListIndex<_, u32>>> = fork.get_group("outer");
+        outer.get(&1).get("alice").push(1);
+        let proof = outer.get(&1).get_proof_in_family("alice", 0);
+        assert_eq!(proof.id, b"\x01alice");
+    }
+
     fn prepare_key_iter<A>(fork: &A)
     where
         A: Access,
@@ -131,6 +131,22 @@ pub struct Values<'a, V> {
     base_iter: Iter<'a, (), V>,
 }
 
+impl<V> Values<'_, V>
+where
+    V: BinaryValue,
+{
+    /// Returns the next value whose raw bytes satisfy `predicate`. Values not satisfying
+    /// the predicate are skipped without deserializing them.
+    pub(crate) fn next_matching<F>(&mut self, predicate: F) -> Option<V>
+    where
+        F: FnMut(&[u8]) -> bool,
+    {
+        self.base_iter
+            .next_matching(predicate)
+            .map(|(_, value)| value)
+    }
+}
+
 impl<V> Iterator for Values<'_, V>
 where
     V: BinaryValue,
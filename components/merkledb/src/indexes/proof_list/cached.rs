@@ -0,0 +1,160 @@
+// Copyright 2020 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum_crypto::Hash;
+
+use std::{cell::RefCell, collections::VecDeque, fmt};
+
+use super::{Hasher, ListProof, ProofListIndex};
+use crate::{views::RawAccess, BinaryValue, HashTag};
+
+/// Wrapper around `ProofListIndex` caching recently built proofs for single list elements.
+///
+/// The cache is useful if proofs for the same elements are requested repeatedly, e.g.,
+/// by an explorer serving proofs for recent list elements. Cached proofs are associated
+/// with the list hash they were built for, and the cache is invalidated whenever the hash
+/// of the list changes. Once the cache is full, the least recently used proofs are evicted.
+///
+/// The list can be modified via [`list_mut`]; subsequent proofs will reflect the changes.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{
+///     access::CopyAccessExt, proof_list::CachedProofList, TemporaryDB, Database,
+/// };
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut list = CachedProofList::new(fork.get_proof_list("name"), 16);
+/// list.list_mut().extend(vec![1_u32, 2, 3]);
+///
+/// let proof = list.get_proof(1);
+/// assert_eq!(proof, list.list().get_proof(1));
+/// // The second proof is retrieved from the cache.
+/// let cached_proof = list.get_proof(1);
+/// assert_eq!(proof, cached_proof);
+/// ```
+///
+/// [`list_mut`]: #method.list_mut
+pub struct CachedProofList<T: RawAccess, V, H: Hasher = HashTag> {
+    list: ProofListIndex<T, V, H>,
+    cache: RefCell<ProofCache<V, H>>,
+}
+
+/// LRU cache of proofs for a specific list hash.
+struct ProofCache<V, H> {
+    capacity: usize,
+    list_hash: Option<Hash>,
+    // Proofs ordered from the least recently used to the most recently used one.
+    proofs: VecDeque<(u64, ListProof<V, H>)>,
+}
+
+impl<V: Clone, H> ProofCache<V, H> {
+    fn get(&mut self, list_hash: Hash, index: u64) -> Option<ListProof<V, H>> {
+        if self.list_hash != Some(list_hash) {
+            self.list_hash = Some(list_hash);
+            self.proofs.clear();
+            return None;
+        }
+
+        let position = self.proofs.iter().position(|(i, _)| *i == index)?;
+        let entry = self.proofs.remove(position)?;
+        let proof = entry.1.clone();
+        self.proofs.push_back(entry);
+        Some(proof)
+    }
+
+    fn insert(&mut self, index: u64, proof: ListProof<V, H>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.proofs.len() == self.capacity {
+            self.proofs.pop_front();
+        }
+        self.proofs.push_back((index, proof));
+    }
+}
+
+impl<T, V, H> CachedProofList<T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue + Clone,
+    H: Hasher,
+{
+    /// Wraps the list, caching at most `capacity` proofs.
+    pub fn new(list: ProofListIndex<T, V, H>, capacity: usize) -> Self {
+        Self {
+            list,
+            cache: RefCell::new(ProofCache {
+                capacity,
+                list_hash: None,
+                proofs: VecDeque::with_capacity(capacity),
+            }),
+        }
+    }
+
+    /// Returns a reference to the wrapped list.
+    pub fn list(&self) -> &ProofListIndex<T, V, H> {
+        &self.list
+    }
+
+    /// Returns a mutable reference to the wrapped list.
+    pub fn list_mut(&mut self) -> &mut ProofListIndex<T, V, H> {
+        &mut self.list
+    }
+
+    /// Returns the wrapped list, dropping the cache.
+    pub fn into_inner(self) -> ProofListIndex<T, V, H> {
+        self.list
+    }
+
+    /// Returns the number of cached proofs.
+    ///
+    /// Proofs built for an outdated list hash are counted until the next call
+    /// to [`get_proof`](#method.get_proof).
+    pub fn cached_len(&self) -> usize {
+        self.cache.borrow().proofs.len()
+    }
+
+    /// Returns a proof of existence for the list element at the specified position,
+    /// or a proof of absence if the list doesn't contain an element with the specified `index`.
+    ///
+    /// The proof is retrieved from the cache if it was built for the current list hash;
+    /// otherwise, the proof is built using [`ProofListIndex::get_proof`] and is cached.
+    ///
+    /// [`ProofListIndex::get_proof`]: struct.ProofListIndex.html#method.get_proof
+    pub fn get_proof(&self, index: u64) -> ListProof<V, H> {
+        let list_hash = self.list.list_hash();
+        let mut cache = self.cache.borrow_mut();
+        if let Some(proof) = cache.get(list_hash, index) {
+            return proof;
+        }
+
+        let proof = self.list.get_proof(index);
+        cache.insert(index, proof.clone());
+        proof
+    }
+}
+
+impl<T: RawAccess, V, H: Hasher> fmt::Debug for CachedProofList<T, V, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cache = self.cache.borrow();
+        formatter
+            .debug_struct("CachedProofList")
+            .field("capacity", &cache.capacity)
+            .field("cached_len", &cache.proofs.len())
+            .finish()
+    }
+}
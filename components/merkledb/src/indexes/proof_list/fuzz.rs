@@ -0,0 +1,68 @@
+// Copyright 2020 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Entry points for fuzzing and property testing of `ListProof`s.
+
+use std::ops::Range;
+
+use super::ProofListIndex;
+use crate::{access::CopyAccessExt, BinaryValue, Database, TemporaryDB};
+
+/// Builds a list from `elements`, creates a proof for `range` and checks that the proof
+/// validates against the list hash and contains the expected elements. Then, a byte in one
+/// of the proof hashes is flipped, and it is checked that the tampered proof does not validate.
+///
+/// Returns `true` if all checks pass. If `range` is illegal, checks that the proof cannot
+/// be built.
+pub fn fuzz_proof_roundtrip<V>(elements: Vec<V>, range: Range<u64>) -> bool
+where
+    V: BinaryValue + Clone + PartialEq,
+{
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list: ProofListIndex<_, V> = fork.get_proof_list("fuzz");
+    list.extend(elements.iter().cloned());
+    let (list_hash, list_len) = (list.list_hash(), list.len());
+
+    let mut proof = match list.try_get_range_proof(range.clone()) {
+        Ok(proof) => proof,
+        Err(_) => return range.end < range.start,
+    };
+
+    let expected_indexes = range.start..range.end.min(list_len);
+    let expected_len = expected_indexes.end.saturating_sub(expected_indexes.start);
+    let is_valid = match proof.validate(list_hash, list_len) {
+        Ok(entries) => {
+            entries.len() as u64 == expected_len
+                && entries
+                    .iter()
+                    .zip(expected_indexes)
+                    .all(|(&(index, value), i)| {
+                        index == i && elements.get(i as usize) == Some(value)
+                    })
+        }
+        Err(_) => false,
+    };
+    if !is_valid {
+        return false;
+    }
+
+    // Use the list hash to choose the tampered hash and byte pseudo-randomly.
+    let seed = list_hash.as_bytes();
+    if proof.tamper_hash(usize::from(seed[0]), usize::from(seed[1])) {
+        proof.validate(list_hash, list_len).is_err()
+    } else {
+        true
+    }
+}
@@ -0,0 +1,395 @@
+// Copyright 2020 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum_crypto::Hash;
+use serde_derive::{Deserialize, Serialize};
+
+use std::{fmt, marker::PhantomData};
+
+use super::{height_of_len, key::MAX_INDEX};
+use crate::{BinaryValue, HashTag};
+
+/// Hash functions used to build the Merkle tree of a [`ProofListIndex`] and to verify
+/// [`ListProof`]s.
+///
+/// The default implementation is [`HashTag`], which uses SHA-256 with domain separation
+/// prefixes for leaves, branches and the list itself. Other implementations may be used
+/// to interoperate with systems using different hash functions; the structure of the Merkle
+/// tree is the same regardless of the hasher.
+///
+/// The hasher is not recorded in the database or in serialized proofs. Thus, a list must
+/// always be accessed with the same hasher, and proofs must be verified with the hasher
+/// of the list they were created for; proofs do not validate under a different hasher.
+///
+/// Lists outside of groups are aggregated into the state hash of the database, which is
+/// always computed with the default hasher. Hence, a list with another hasher can only be
+/// created in a [`Group`]; accessing it at other addresses fails with
+/// [`AccessErrorKind::AggregatedCustomHasher`].
+///
+/// # Examples
+///
+/// ```
+/// # use exonum_crypto::{Hash, HashStream};
+/// # use exonum_merkledb::{
+/// #     access::FromAccess, proof_list::Hasher, Database, ListProof, ObjectHash, ProofListIndex,
+/// #     TemporaryDB,
+/// # };
+/// /// Hasher using custom domain separation prefixes.
+/// struct CustomHasher;
+///
+/// impl Hasher for CustomHasher {
+///     fn hash_leaf(value: &[u8]) -> Hash {
+///         HashStream::new().update(b"leaf").update(value).hash()
+///     }
+///
+///     fn hash_node(left_hash: &Hash, right_hash: &Hash) -> Hash {
+///         HashStream::new()
+///             .update(b"node")
+///             .update(left_hash.as_ref())
+///             .update(right_hash.as_ref())
+///             .hash()
+///     }
+///
+///     fn hash_single_node(hash: &Hash) -> Hash {
+///         HashStream::new().update(b"node").update(hash.as_ref()).hash()
+///     }
+///
+///     fn hash_list_node(len: u64, root: Hash) -> Hash {
+///         HashStream::new()
+///             .update(b"list")
+///             .update(&len.to_le_bytes())
+///             .update(root.as_ref())
+///             .hash()
+///     }
+/// }
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut list =
+///     ProofListIndex::<_, u32, CustomHasher>::from_access(&fork, ("lists", &1_u8).into())
+///         .unwrap();
+/// list.extend(vec![1, 2, 3]);
+/// let proof: ListProof<u32, CustomHasher> = list.get_proof(1);
+/// proof.check_against_hash(list.object_hash()).unwrap();
+/// ```
+///
+/// [`ProofListIndex`]: struct.ProofListIndex.html
+/// [`ListProof`]: struct.ListProof.html
+/// [`HashTag`]: ../../enum.HashTag.html
+/// [`Group`]: ../struct.Group.html
+/// [`AccessErrorKind::AggregatedCustomHasher`]: ../../access/enum.AccessErrorKind.html#variant.AggregatedCustomHasher
+pub trait Hasher {
+    /// Obtains a hashed value of a leaf in a Merkle tree.
+    fn hash_leaf(value: &[u8]) -> Hash;
+
+    /// Obtains a hashed value of a branch in a Merkle tree.
+    fn hash_node(left_hash: &Hash, right_hash: &Hash) -> Hash;
+
+    /// Obtains a hashed value of a Merkle tree branch with one child.
+    fn hash_single_node(hash: &Hash) -> Hash;
+
+    /// Obtains hash of a Merkelized list. `len` is the length of the list, and `root` is
+    /// the hash of the root node of the Merkle tree corresponding to the list.
+    fn hash_list_node(len: u64, root: Hash) -> Hash;
+}
+
+impl Hasher for HashTag {
+    fn hash_leaf(value: &[u8]) -> Hash {
+        Self::hash_leaf(value)
+    }
+
+    fn hash_node(left_hash: &Hash, right_hash: &Hash) -> Hash {
+        Self::hash_node(left_hash, right_hash)
+    }
+
+    fn hash_single_node(hash: &Hash) -> Hash {
+        Self::hash_single_node(hash)
+    }
+
+    fn hash_list_node(len: u64, root: Hash) -> Hash {
+        Self::hash_list_node(len, root)
+    }
+}
+
+/// Incremental hasher computing the hash of a Merkelized list as its elements are appended.
+///
+/// The hasher is fed with leaf hashes (or values) in order, and yields the hash the list
+/// would have with the appended elements, i.e., the same hash as `ProofListIndex::list_hash()`.
+/// Both appending an element and computing the list hash take `O(log n)` time, where `n`
+/// is the number of appended elements; the hasher does not access the database.
+///
+/// The hasher stores the hashes of complete subtrees to the left of the last element
+/// (at most one per tree height), and the hash of the last element.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{
+///     access::CopyAccessExt, proof_list::ListHasher, TemporaryDB, Database, HashTag,
+/// };
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut list = fork.get_proof_list("name");
+/// list.extend(vec![1_u32, 2]);
+///
+/// let mut hasher = list.list_hasher();
+/// for value in 3_u32..10 {
+///     list.push(value);
+///     hasher.append_value(&value);
+///     assert_eq!(hasher.list_hash(), list.list_hash());
+/// }
+///
+/// let mut hasher = ListHasher::<HashTag>::new();
+/// for value in 1_u32..10 {
+///     hasher.append_value(&value);
+/// }
+/// assert_eq!(hasher.list_hash(), list.list_hash());
+/// ```
+pub struct ListHasher<H = HashTag> {
+    len: u64,
+    // Hashes of the complete subtrees covering elements `0..len - 1`. The subtree on height
+    // `i + 1` is present iff the `i`th bit of `len - 1` is set.
+    subtrees: Vec<Option<Hash>>,
+    // Hash of the last element.
+    last: Option<Hash>,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: Hasher> ListHasher<H> {
+    /// Creates a hasher for an empty list.
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            subtrees: vec![],
+            last: None,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Creates a hasher from the state of an existing list.
+    pub(super) fn from_parts(len: u64, subtrees: Vec<Option<Hash>>, last: Option<Hash>) -> Self {
+        Self {
+            len,
+            subtrees,
+            last,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Returns the number of appended elements.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Checks whether no elements were appended to the hasher.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a leaf hash of the next list element, i.e., `H::hash_leaf(value.to_bytes())`.
+    pub fn append_hash(&mut self, leaf_hash: Hash) {
+        if let Some(mut carry) = self.last.replace(leaf_hash) {
+            // Merge the previous element into complete subtrees, similar to
+            // incrementing a binary counter.
+            let mut height = 0;
+            while let Some(Some(left_hash)) = self.subtrees.get(height) {
+                carry = H::hash_node(left_hash, &carry);
+                self.subtrees[height] = None;
+                height += 1;
+            }
+            if height == self.subtrees.len() {
+                self.subtrees.push(Some(carry));
+            } else {
+                self.subtrees[height] = Some(carry);
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Appends the next list element.
+    pub fn append_value<V: BinaryValue>(&mut self, value: &V) {
+        self.append_hash(H::hash_leaf(&value.to_bytes()));
+    }
+
+    /// Returns the root hash of the Merkle tree for the appended elements.
+    pub fn merkle_root(&self) -> Hash {
+        let mut hash = match self.last {
+            Some(hash) => hash,
+            None => return Hash::zero(),
+        };
+
+        // Compute hashes on the rightmost path of the tree bottom-up.
+        for height in 0..usize::from(height_of_len(self.len) - 1) {
+            hash = match self.subtrees.get(height) {
+                Some(Some(left_hash)) => H::hash_node(left_hash, &hash),
+                _ => H::hash_single_node(&hash),
+            };
+        }
+        hash
+    }
+
+    /// Returns the hash of the list consisting of the appended elements.
+    pub fn list_hash(&self) -> Hash {
+        H::hash_list_node(self.len, self.merkle_root())
+    }
+
+    /// Returns the checkpoint for the list consisting of the appended elements.
+    pub fn checkpoint(&self) -> ListCheckpoint {
+        let frontier = self
+            .subtrees
+            .iter()
+            .filter_map(|hash| *hash)
+            .chain(self.last)
+            .collect();
+        ListCheckpoint {
+            len: self.len,
+            list_hash: self.list_hash(),
+            frontier,
+        }
+    }
+
+    /// Restores the hasher from the `checkpoint`, so that elements appended to the hasher
+    /// are appended to the list described by the checkpoint.
+    ///
+    /// Returns `None` if the checkpoint is inconsistent, i.e., if the number of hashes
+    /// in its frontier does not correspond to the list length, or the list hash restored
+    /// from the frontier differs from the one in the checkpoint.
+    pub fn from_checkpoint(checkpoint: &ListCheckpoint) -> Option<Self> {
+        let hasher = match checkpoint.len.checked_sub(1) {
+            None if checkpoint.frontier.is_empty() => Self::new(),
+            Some(last_index) if last_index <= MAX_INDEX => {
+                let mut frontier = checkpoint.frontier.iter().copied();
+                let subtree_heights = height_of_len(checkpoint.len) - 1;
+                let mut subtrees = Vec::with_capacity(usize::from(subtree_heights));
+                for height in 0..subtree_heights {
+                    subtrees.push(if (last_index >> height) & 1 == 1 {
+                        Some(frontier.next()?)
+                    } else {
+                        None
+                    });
+                }
+                let last = frontier.next()?;
+                if frontier.next().is_some() {
+                    return None;
+                }
+                Self::from_parts(checkpoint.len, subtrees, Some(last))
+            }
+            _ => return None,
+        };
+
+        if hasher.list_hash() == checkpoint.list_hash {
+            Some(hasher)
+        } else {
+            None
+        }
+    }
+}
+
+/// Compact verifiable summary of the state of a `ProofListIndex`.
+///
+/// Besides the length and the hash of the list, the checkpoint contains the hashes necessary
+/// to compute the list hash after appending elements to the list, namely, hashes of complete
+/// subtrees of the Merkle tree to the left of the last element (ordered by increasing height)
+/// followed by the leaf hash of the last element. Thus, the checkpoint size is `O(log n)`,
+/// where `n` is the list length.
+///
+/// Checkpoints are created by [`ProofListIndex::checkpoint`]. Given a trusted checkpoint,
+/// the next checkpoint after appending elements to the list can be verified with
+/// [`verify_checkpoint_chain`] without accessing other list elements.
+///
+/// [`ProofListIndex::checkpoint`]: struct.ProofListIndex.html#method.checkpoint
+/// [`verify_checkpoint_chain`]: fn.verify_checkpoint_chain.html
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListCheckpoint {
+    /// Length of the list.
+    pub len: u64,
+    /// Hash of the list, i.e., its `object_hash()`.
+    pub list_hash: Hash,
+    /// Hashes necessary to append elements to the list.
+    pub frontier: Vec<Hash>,
+}
+
+/// Verifies that the `next` checkpoint follows from the `prev` checkpoint by appending
+/// exactly the `appended` elements to the list. The lists are assumed to use the default
+/// hasher ([`HashTag`]); use [`ListHasher::from_checkpoint`] for other hashers.
+///
+/// The hashes in the frontier of `prev` are authenticated by its list hash, so it suffices
+/// to trust `prev.list_hash` (e.g., if it was obtained from the previous verification step).
+/// The hashes are computed in memory and do not require access to the list.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{
+///     access::CopyAccessExt, proof_list::verify_checkpoint_chain, TemporaryDB, Database,
+/// };
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut list = fork.get_proof_list("name");
+/// list.extend(vec![1_u32, 2, 3]);
+/// let prev = list.checkpoint();
+///
+/// list.extend(vec![4, 5]);
+/// let next = list.checkpoint();
+/// assert!(verify_checkpoint_chain(&prev, &next, &[4_u32, 5]));
+/// assert!(!verify_checkpoint_chain(&prev, &next, &[4_u32, 6]));
+/// ```
+///
+/// [`HashTag`]: ../enum.HashTag.html
+/// [`ListHasher::from_checkpoint`]: struct.ListHasher.html#method.from_checkpoint
+pub fn verify_checkpoint_chain<V: BinaryValue>(
+    prev: &ListCheckpoint,
+    next: &ListCheckpoint,
+    appended: &[V],
+) -> bool {
+    let mut hasher = match ListHasher::<HashTag>::from_checkpoint(prev) {
+        Some(hasher) => hasher,
+        None => return false,
+    };
+    for value in appended {
+        hasher.append_value(value);
+    }
+    hasher.checkpoint() == *next
+}
+
+impl<H: Hasher> Default for ListHasher<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Implemented manually to avoid the `H: Clone` bound.
+impl<H> Clone for ListHasher<H> {
+    fn clone(&self) -> Self {
+        Self {
+            len: self.len,
+            subtrees: self.subtrees.clone(),
+            last: self.last,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+impl<H> fmt::Debug for ListHasher<H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ListHasher")
+            .field("len", &self.len)
+            .field("subtrees", &self.subtrees)
+            .field("last", &self.last)
+            .finish()
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright 2020 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{cmp, collections::VecDeque, fmt};
+
+use super::{Hasher, ProofListIndex};
+use crate::{
+    indexes::iter::{IndexIterator, Values},
+    views::RawAccess,
+    BinaryValue, HashTag,
+};
+
+/// Iterator over the values of a `ProofListIndex`.
+///
+/// This structure is returned by the [`iter`], [`iter_from`] and [`iter_range`] methods
+/// of `ProofListIndex`.
+/// Unlike the generic [`Values`] iterator, it can be iterated from both ends. Forward
+/// iteration is backed by the database iterator, while backward iteration retrieves
+/// list elements one by one starting from the end of the list, since database iterators
+/// cannot move backwards. Thus, each element returned by `next_back` costs a random read
+/// from the database.
+///
+/// Since the list length is known in advance, the iterator implements `ExactSizeIterator`.
+///
+/// Values are deserialized directly from the bytes borrowed from the database, so
+/// fixed-size values (e.g., `Hash`es) are decoded without intermediate allocations.
+///
+/// [`iter`]: struct.ProofListIndex.html#method.iter
+/// [`iter_from`]: struct.ProofListIndex.html#method.iter_from
+/// [`iter_range`]: struct.ProofListIndex.html#method.iter_range
+/// [`Values`]: ../struct.Values.html
+pub struct ProofListIndexIter<'a, T: RawAccess, V, H: Hasher = HashTag> {
+    index: &'a ProofListIndex<T, V, H>,
+    // The database iterator is created lazily, so that it is not allocated if the iterator
+    // is only consumed from the back.
+    forward_iter: Option<Values<'a, V>>,
+    // Index of the next element returned from the front.
+    start: u64,
+    // Index following the next element returned from the back.
+    end: u64,
+}
+
+impl<'a, T, V, H> ProofListIndexIter<'a, T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+    /// Creates an iterator over elements with indexes in `[from, to)`. The range is clamped
+    /// to the list bounds; an empty iterator is created if `to <= from`.
+    pub(super) fn new(index: &'a ProofListIndex<T, V, H>, from: u64, to: u64) -> Self {
+        let end = cmp::min(to, index.len());
+        Self {
+            index,
+            forward_iter: None,
+            start: cmp::min(from, end),
+            end,
+        }
+    }
+
+    /// Repositions the iterator so that the next element returned from the front has
+    /// the specified index in the list.
+    ///
+    /// Unlike skipping elements with `next`, this method does not read the skipped elements
+    /// from the database; the database iterator is recreated starting from `index` instead.
+    /// If `index` precedes the current iterator position, the position is not changed;
+    /// if `index` is beyond the end of the iterated range, the iterator becomes empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(0_u32..10);
+    ///
+    /// let mut iter = index.iter().skip_to(5);
+    /// assert_eq!(iter.next(), Some(5));
+    /// assert_eq!(iter.len(), 4);
+    /// ```
+    pub fn skip_to(mut self, index: u64) -> Self {
+        let new_start = cmp::min(index, self.end);
+        if new_start > self.start {
+            self.start = new_start;
+            self.forward_iter = None;
+        }
+        self
+    }
+}
+
+impl<T: RawAccess, V, H: Hasher> fmt::Debug for ProofListIndexIter<'_, T, V, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ProofListIndexIter")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+impl<T, V, H> Iterator for ProofListIndexIter<'_, T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+    type Item = V;
+
+    fn next(&mut self) -> Option<V> {
+        if self.start >= self.end {
+            return None;
+        }
+
+        let (index, start) = (self.index, self.start);
+        let value = self
+            .forward_iter
+            .get_or_insert_with(|| index.index_iter(Some(&start)).skip_keys())
+            .next();
+        debug_assert!(value.is_some(), "List element {} is missing", start);
+        self.start += 1;
+        value
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.start) as usize;
+        (len, Some(len))
+    }
+
+    fn count(self) -> usize {
+        // The number of remaining elements is known, so there is no need to read them.
+        self.len()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<V> {
+        let new_start = self.start.saturating_add(n as u64);
+        if new_start >= self.end {
+            self.start = self.end;
+            return None;
+        }
+
+        // Seek directly to the requested element; the database iterator will be recreated
+        // starting from it.
+        if n > 0 {
+            self.start = new_start;
+            self.forward_iter = None;
+        }
+        self.next()
+    }
+}
+
+impl<T, V, H> DoubleEndedIterator for ProofListIndexIter<'_, T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+    fn next_back(&mut self) -> Option<V> {
+        if self.start >= self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        let value = self.index.get(self.end);
+        debug_assert!(value.is_some(), "List element {} is missing", self.end);
+        value
+    }
+}
+
+impl<T, V, H> ExactSizeIterator for ProofListIndexIter<'_, T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+}
+
+/// Owning iterator over the values of a `ProofListIndex`.
+///
+/// This structure is returned by the `into_iter` method of `ProofListIndex`. Since the iterator
+/// owns the index, it cannot hold a database iterator borrowing from the index; instead,
+/// values are read from the index in chunks when iterating from the front, and one by one
+/// when iterating from the back.
+pub struct ProofListIndexIntoIter<T: RawAccess, V, H: Hasher = HashTag> {
+    index: ProofListIndex<T, V, H>,
+    // Values read from the index, but not yet returned from the front. The values have
+    // indexes `start - buffer.len()..start`.
+    buffer: VecDeque<V>,
+    // Index of the next element to be read into `buffer`.
+    start: u64,
+    // Index following the next element returned from the back.
+    end: u64,
+}
+
+impl<T, V, H> ProofListIndexIntoIter<T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+    /// Number of values read from the index at once.
+    const CHUNK_SIZE: u64 = 64;
+
+    pub(super) fn new(index: ProofListIndex<T, V, H>) -> Self {
+        let end = index.len();
+        Self {
+            index,
+            buffer: VecDeque::new(),
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<T: RawAccess, V, H: Hasher> fmt::Debug for ProofListIndexIntoIter<T, V, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ProofListIndexIntoIter")
+            .field("buffered", &self.buffer.len())
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+impl<T, V, H> Iterator for ProofListIndexIntoIter<T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+    type Item = V;
+
+    fn next(&mut self) -> Option<V> {
+        if self.buffer.is_empty() && self.start < self.end {
+            let chunk_end = cmp::min(self.start + Self::CHUNK_SIZE, self.end);
+            self.buffer
+                .extend(self.index.iter_range(self.start..chunk_end));
+            debug_assert_eq!(self.buffer.len() as u64, chunk_end - self.start);
+            self.start = chunk_end;
+        }
+        self.buffer.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.buffer.len() + (self.end - self.start) as usize;
+        (len, Some(len))
+    }
+}
+
+impl<T, V, H> DoubleEndedIterator for ProofListIndexIntoIter<T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+    fn next_back(&mut self) -> Option<V> {
+        if self.start >= self.end {
+            return self.buffer.pop_back();
+        }
+
+        self.end -= 1;
+        let value = self.index.get(self.end);
+        debug_assert!(value.is_some(), "List element {} is missing", self.end);
+        value
+    }
+}
+
+impl<T, V, H> ExactSizeIterator for ProofListIndexIntoIter<T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+}
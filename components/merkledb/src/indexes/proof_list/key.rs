@@ -21,6 +21,31 @@ use crate::BinaryKey;
 pub const HEIGHT_SHIFT: u64 = 56;
 pub const MAX_INDEX: u64 = 0xFF_FFFF_FFFF_FFFF; // 2_u64.pow(56) - 1
 
+/// Position of a node in the Merkle tree of a `ProofListIndex`.
+///
+/// The tree is addressed by the `height` of the node and its `index` on this height.
+/// Height 0 corresponds to the list values, height 1 to the hashes of the values,
+/// and each following height contains hashes of pairs of nodes on the previous height.
+/// The root of the tree for a list of length `len` has height [`height_of_len(len)`]
+/// and index 0. `ListProof`s contain hashes keyed by `ProofListKey`s with height 1 or greater.
+///
+/// The key is a read-only value; the tree is only modified by the `ProofListIndex` methods.
+///
+/// # Examples
+///
+/// ```
+/// # use exonum_merkledb::proof_list::ProofListKey;
+/// let key = ProofListKey::new(3, 1);
+/// // The node covers 4 leaves starting from the leaf with index 4.
+/// assert_eq!(key.first_left_leaf_index(), 4);
+/// assert_eq!(key.first_right_leaf_index(), 6);
+/// assert_eq!(key.left(), ProofListKey::new(2, 2));
+/// assert_eq!(key.right(), ProofListKey::new(2, 3));
+/// assert_eq!(key.parent(), ProofListKey::new(4, 0));
+/// assert_eq!(key.as_left(), ProofListKey::new(3, 0));
+/// ```
+///
+/// [`height_of_len(len)`]: fn.height_of_len.html
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProofListKey {
     index: u64,
@@ -28,15 +53,24 @@ pub struct ProofListKey {
 }
 
 impl ProofListKey {
+    /// Creates a key with the specified height and index on this height.
+    ///
+    /// The height should not exceed 56 and the index should not exceed `2 ** 56 - 1`;
+    /// this is checked only if debug assertions are on. Use [`is_valid`] to check keys
+    /// obtained from untrusted sources.
+    ///
+    /// [`is_valid`]: #method.is_valid
     pub fn new(height: u8, index: u64) -> Self {
         debug_assert!(u64::from(height) <= HEIGHT_SHIFT && index <= MAX_INDEX);
         Self { height, index }
     }
 
+    /// Returns the height of the node.
     pub fn height(&self) -> u8 {
         self.height
     }
 
+    /// Returns the index of the node on its height.
     pub fn index(&self) -> u64 {
         self.index
     }
@@ -47,22 +81,46 @@ impl ProofListKey {
         u64::from(self.height) <= HEIGHT_SHIFT && self.index <= MAX_INDEX
     }
 
-    pub fn leaf(index: u64) -> Self {
+    pub(crate) fn leaf(index: u64) -> Self {
         Self::new(0, index)
     }
 
-    pub fn as_db_key(&self) -> u64 {
+    pub(crate) fn as_db_key(&self) -> u64 {
         (u64::from(self.height) << HEIGHT_SHIFT) + self.index
     }
 
-    pub fn from_db_key(key: u64) -> Self {
+    pub(crate) fn from_db_key(key: u64) -> Self {
         Self::new((key >> HEIGHT_SHIFT) as u8, key & MAX_INDEX)
     }
 
+    /// Returns the key of the parent node.
     pub fn parent(&self) -> Self {
         Self::new(self.height + 1, self.index >> 1)
     }
 
+    /// Returns the key of the left child of the node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node height is less than 2. (Nodes on height 1 are hashes of list values
+    /// with the same index, and list values on height 0 have no children.)
+    pub fn left(&self) -> Self {
+        assert!(self.height > 1, "Node has no children in the Merkle tree");
+        Self::new(self.height - 1, self.index << 1)
+    }
+
+    /// Returns the key of the right child of the node. The child may be absent in the tree
+    /// if the tree is not balanced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node height is less than 2.
+    pub fn right(&self) -> Self {
+        assert!(self.height > 1, "Node has no children in the Merkle tree");
+        Self::new(self.height - 1, (self.index << 1) + 1)
+    }
+
+    /// Returns the index of the first list element covered by the node.
     pub fn first_left_leaf_index(&self) -> u64 {
         if self.height < 2 {
             self.index
@@ -71,14 +129,27 @@ impl ProofListKey {
         }
     }
 
+    /// Returns the index of the first list element covered by the right child of the node.
+    /// For nodes with height less than 2, this is the index of the node itself.
+    pub fn first_right_leaf_index(&self) -> u64 {
+        if self.height < 2 {
+            self.index
+        } else {
+            ((self.index << 1) + 1) << (self.height - 2)
+        }
+    }
+
+    /// Checks if the node is the left child of its parent.
     pub fn is_left(&self) -> bool {
         self.index.trailing_zeros() >= 1
     }
 
+    /// Returns the key of the left node in the pair of siblings containing this node.
     pub fn as_left(&self) -> Self {
         Self::new(self.height, self.index & !1)
     }
 
+    /// Returns the key of the right node in the pair of siblings containing this node.
     pub fn as_right(&self) -> Self {
         Self::new(self.height, self.index | 1)
     }
@@ -116,6 +187,27 @@ impl Ord for ProofListKey {
     }
 }
 
+#[test]
+fn proof_list_key_children() {
+    let key = ProofListKey::new(1, 5);
+    assert_eq!(key.first_left_leaf_index(), 5);
+    assert_eq!(key.first_right_leaf_index(), 5);
+
+    for &(height, index) in &[(2, 0), (2, 3), (4, 5), (10, 17)] {
+        let key = ProofListKey::new(height, index);
+        assert_eq!(key.left().parent(), key);
+        assert_eq!(key.right().parent(), key);
+        assert_eq!(
+            key.left().first_left_leaf_index(),
+            key.first_left_leaf_index()
+        );
+        assert_eq!(
+            key.right().first_left_leaf_index(),
+            key.first_right_leaf_index()
+        );
+    }
+}
+
 #[test]
 fn proof_list_key_ord() {
     assert!(ProofListKey::new(0, 1000) < ProofListKey::new(0, 1001));
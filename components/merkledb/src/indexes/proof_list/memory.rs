@@ -0,0 +1,197 @@
+// Copyright 2020 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum_crypto::Hash;
+
+use std::{fmt, marker::PhantomData, ops::RangeBounds};
+
+use super::{
+    key::ProofListKey,
+    proof_builder::{BuildProof, MerkleTree},
+    Hasher, ListProof, ProofListError,
+};
+use crate::{BinaryValue, HashTag, ObjectHash};
+
+/// In-memory version of a `ProofListIndex`, which computes the list hash and proofs
+/// without accessing the database.
+///
+/// The Merkle tree is built when the list is created and has the same structure as
+/// the tree of a `ProofListIndex` with the same elements. Thus, the list hash and proofs
+/// are equal to those produced by the database-backed list. This allows clients to
+/// independently compute the expected list hash from the elements obtained, e.g.,
+/// over the network, or to serve proofs for such elements.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{
+///     access::CopyAccessExt, proof_list::MemoryProofList, TemporaryDB, Database, ObjectHash,
+/// };
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut index = fork.get_proof_list("name");
+/// index.extend(vec![1_u32, 2, 3, 4, 5]);
+///
+/// let list = MemoryProofList::new(vec![1_u32, 2, 3, 4, 5]);
+/// assert_eq!(list.object_hash(), index.object_hash());
+/// assert_eq!(list.get_range_proof(1..3), index.get_range_proof(1..3));
+/// ```
+pub struct MemoryProofList<V, H: Hasher = HashTag> {
+    values: Vec<V>,
+    // Hashes of the tree nodes; `layers[i]` contains hashes at height `i + 1`.
+    layers: Vec<Vec<Hash>>,
+    _hasher: PhantomData<H>,
+}
+
+impl<V: fmt::Debug, H: Hasher> fmt::Debug for MemoryProofList<V, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("MemoryProofList")
+            .field("values", &self.values)
+            .field("merkle_root", &self.merkle_root())
+            .finish()
+    }
+}
+
+impl<V, H> MemoryProofList<V, H>
+where
+    V: BinaryValue + Clone,
+    H: Hasher,
+{
+    /// Creates a list with the specified elements and builds its Merkle tree.
+    pub fn new(values: Vec<V>) -> Self {
+        let mut layers = vec![];
+        let mut layer: Vec<_> = values
+            .iter()
+            .map(|value| H::hash_leaf(&value.to_bytes()))
+            .collect();
+
+        while layer.len() > 1 {
+            let next_layer = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => H::hash_node(left, right),
+                    [single] => H::hash_single_node(single),
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(layer);
+            layer = next_layer;
+        }
+        if !layer.is_empty() {
+            layers.push(layer);
+        }
+
+        Self {
+            values,
+            layers,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in the list.
+    pub fn len(&self) -> u64 {
+        self.values.len() as u64
+    }
+
+    /// Checks whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the elements of the list.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Returns the root hash of the Merkle tree, or `Hash::zero()` if the list is empty.
+    pub fn merkle_root(&self) -> Hash {
+        self.layers
+            .last()
+            .map_or_else(Hash::zero, |root_layer| root_layer[0])
+    }
+
+    /// Returns the hash of the list, which is equal to `object_hash()` of a `ProofListIndex`
+    /// with the same elements.
+    pub fn list_hash(&self) -> Hash {
+        H::hash_list_node(self.len(), self.merkle_root())
+    }
+
+    /// Returns a proof of existence for the list element at the specified position,
+    /// or a proof of absence if the list doesn't contain an element with the specified `index`.
+    pub fn get_proof(&self, index: u64) -> ListProof<V, H> {
+        self.create_proof(index)
+    }
+
+    /// Returns the proof of existence for the list elements in the specified range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range bounds are illegal.
+    pub fn get_range_proof<R: RangeBounds<u64>>(&self, range: R) -> ListProof<V, H> {
+        self.try_get_range_proof(range)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Returns the proof of existence for the list elements in the specified range.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the range bounds are illegal.
+    pub fn try_get_range_proof<R: RangeBounds<u64>>(
+        &self,
+        range: R,
+    ) -> Result<ListProof<V, H>, ProofListError> {
+        self.create_range_proof(range)
+    }
+
+    /// Returns the proof of existence for the list elements with the specified indexes.
+    pub fn get_multiproof(&self, indexes: impl IntoIterator<Item = u64>) -> ListProof<V, H> {
+        self.create_multiproof(indexes)
+    }
+}
+
+impl<V, H> MerkleTree<V> for MemoryProofList<V, H>
+where
+    V: BinaryValue + Clone,
+    H: Hasher,
+{
+    fn len(&self) -> u64 {
+        self.len()
+    }
+
+    fn node(&self, position: ProofListKey) -> Hash {
+        let layer = &self.layers[usize::from(position.height()) - 1];
+        layer[position.index() as usize]
+    }
+
+    fn values<'s>(&'s self, start_index: u64) -> Box<dyn Iterator<Item = V> + 's> {
+        Box::new(self.values.iter().skip(start_index as usize).cloned())
+    }
+
+    fn merkle_root(&self) -> Hash {
+        self.merkle_root()
+    }
+}
+
+impl<V, H> ObjectHash for MemoryProofList<V, H>
+where
+    V: BinaryValue + Clone,
+    H: Hasher,
+{
+    fn object_hash(&self) -> Hash {
+        self.list_hash()
+    }
+}
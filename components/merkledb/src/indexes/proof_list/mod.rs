@@ -14,36 +14,129 @@
 
 //! An implementation of a Merkelized version of an array list (Merkle tree).
 
-pub use self::proof::{CheckedListProof, ListProof, ListProofError, ValidationError};
+pub use self::{
+    cached::CachedProofList,
+    hasher::{verify_checkpoint_chain, Hasher, ListCheckpoint, ListHasher},
+    iter::{ProofListIndexIntoIter, ProofListIndexIter},
+    key::ProofListKey,
+    memory::MemoryProofList,
+    proof::{CheckedListProof, FamilyListProof, ListProof, ListProofError, ValidationError},
+};
 
 use exonum_crypto::Hash;
+use thiserror::Error;
 
-use std::{cmp, iter, marker::PhantomData, ops::RangeBounds};
+use std::{
+    any::TypeId,
+    cmp, iter,
+    marker::PhantomData,
+    ops::{Bound, Range, RangeBounds},
+};
 
 use self::{
-    key::{ProofListKey, MAX_INDEX},
+    key::MAX_INDEX,
     proof::HashedEntry,
-    proof_builder::{BuildProof, MerkleTree},
+    proof_builder::{range_proof_keys, resolve_range, BuildProof, MerkleTree},
 };
 use crate::{
-    access::{Access, AccessError, FromAccess},
+    access::{Access, AccessError, AccessErrorKind, FromAccess},
     hash::HashTag,
-    indexes::iter::{Entries, IndexIterator, Values},
+    indexes::iter::{Entries, IndexIterator},
     views::{IndexState, IndexType, RawAccess, RawAccessMut, View, ViewWithMetadata},
-    BinaryValue, IndexAddress, ObjectHash,
+    BinaryValue, Fork, IndexAddress, ObjectHash,
 };
 
+mod cached;
+#[cfg(any(test, feature = "fuzz"))]
+#[doc(hidden)]
+pub mod fuzz;
+mod hasher;
+mod iter;
 mod key;
+mod memory;
 mod proof;
 mod proof_builder;
+mod stream;
 #[cfg(test)]
 mod tests;
 
-fn tree_height_by_length(len: u64) -> u8 {
-    if len == 0 {
-        0
-    } else {
-        len.next_power_of_two().trailing_zeros() as u8 + 1
+/// Minimum number of sibling pairs on a tree level for which hashing is parallelized.
+#[cfg(feature = "rayon")]
+const MIN_PARALLEL_PAIRS: u64 = 1_024;
+
+/// Maximum number of sibling pairs buffered in memory when hashing a tree level in parallel.
+#[cfg(feature = "rayon")]
+const PARALLEL_CHUNK_PAIRS: usize = 16_384;
+
+/// Returns the height of the Merkle tree built based on a list with the specified length.
+///
+/// The height of the empty list is 0; otherwise, the height is computed as `ceil(log2(len)) + 1`.
+/// Thus, a list with a single element has height 1 (the tree consists of a single leaf),
+/// and the height increases by 1 each time the list length exceeds a power of two.
+///
+/// The function is defined for all `u64` values, including lengths exceeding `2^63`
+/// (for which `len.next_power_of_two()` would overflow); the maximum height is 65.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::proof_list::height_of_len;
+///
+/// assert_eq!(height_of_len(0), 0);
+/// assert_eq!(height_of_len(1), 1);
+/// assert_eq!(height_of_len(2), 2);
+/// assert_eq!(height_of_len(3), 3);
+/// assert_eq!(height_of_len(4), 3);
+/// assert_eq!(height_of_len(5), 4);
+/// assert_eq!(height_of_len(u64::max_value()), 65);
+/// ```
+pub fn height_of_len(len: u64) -> u8 {
+    match len.checked_sub(1) {
+        None => 0,
+        // The number of significant bits in `len - 1` equals `ceil(log2(len))`.
+        Some(last_index) => (64 - last_index.leading_zeros()) as u8 + 1,
+    }
+}
+
+/// Returns keys of the Merkle tree branches, which are included into the proof of existence
+/// for elements in `range` of a list with the specified length. The keys are ordered in the same
+/// way as in the proof.
+///
+/// The keys are computed without accessing the database, so this function may be used, e.g.,
+/// to prefetch branch hashes from a remote store before building the proof with
+/// [`ProofListIndex::get_range_proof`]. The range is treated in the same way as in
+/// `get_range_proof`: if the range does not intersect with the list, the proof consists of
+/// the root of the tree (or has no hashes if the list is empty).
+///
+/// # Panics
+///
+/// Panics if `range.end < range.start`.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::proof_list::{proof_branch_keys, ProofListKey};
+///
+/// let keys = proof_branch_keys(10, 3..6);
+/// assert_eq!(
+///     keys,
+///     vec![
+///         ProofListKey::new(1, 2),
+///         ProofListKey::new(2, 0),
+///         ProofListKey::new(2, 3),
+///         ProofListKey::new(4, 1),
+///     ]
+/// );
+/// // A proof for a range beyond the list length contains the root of the tree.
+/// assert_eq!(proof_branch_keys(10, 20..30), vec![ProofListKey::new(5, 0)]);
+/// ```
+///
+/// [`ProofListIndex::get_range_proof`]: struct.ProofListIndex.html#method.get_range_proof
+pub fn proof_branch_keys(len: u64, range: Range<u64>) -> Vec<ProofListKey> {
+    match resolve_range(range, len).unwrap_or_else(|err| panic!("{}", err)) {
+        Some((from, inclusive_to)) => range_proof_keys(len, from, inclusive_to),
+        None if len == 0 => vec![],
+        None => vec![ProofListKey::new(height_of_len(len), 0)],
     }
 }
 
@@ -52,6 +145,10 @@ fn tree_height_by_length(len: u64) -> u8 {
 /// `ProofListIndex` implements a Merkle tree, storing elements as leaves and using `u64` as
 /// an index. `ProofListIndex` requires that elements implement the [`BinaryValue`] trait.
 ///
+/// The hash functions used to build the Merkle tree are specified by the [`Hasher`] type
+/// parameter, which defaults to SHA-256 with [`HashTag`] domain separation. The hasher is not
+/// recorded in the database, so the list must always be accessed with the same hasher.
+///
 /// # Safety
 ///
 /// A `ProofListIndex` may contain at most `2 ** 56` elements (which is approximately `7.2e16`),
@@ -68,24 +165,52 @@ fn tree_height_by_length(len: u64) -> u8 {
 /// index values; it is unnecessary to check on the calling side whether the index exceeds
 /// `2 ** 56 - 1` .
 ///
+/// # Length and root caching
+///
+/// The list length is loaded together with the index metadata when the index is created,
+/// and the Merkle root is cached after the first read. Both values are updated by the mutating
+/// methods of the index. The cached values cannot diverge from the stored ones: a [`Fork`]
+/// allows only one mutable instance of an index with a specific address at a time and panics
+/// if the index is opened again while the first instance is alive. To access the list
+/// from several places within a transaction, pass the same instance or reopen the index
+/// after the previous instance is dropped.
+///
+/// # Index type checks
+///
+/// The type of an index is recorded in the database when the index is created. Opening
+/// an index of another type (e.g., a `MapIndex`) at the same address as a `ProofListIndex`
+/// fails with [`AccessErrorKind::WrongIndexType`], which contains both expected and actual
+/// index types; methods of the access extension traits (such as `get_proof_list`) panic
+/// with the corresponding error message.
+///
 /// [`BinaryValue`]: ../../trait.BinaryValue.html
+/// [`AccessErrorKind::WrongIndexType`]: ../../access/enum.AccessErrorKind.html#variant.WrongIndexType
+/// [`Hasher`]: trait.Hasher.html
+/// [`HashTag`]: ../../enum.HashTag.html
 /// [`ListIndex`]: ../struct.ListIndex.html
 /// [`extend`]: #method.extend
 /// [`push`]: #method.push
 /// [`get`]: #method.get
 /// [`iter_from`]: #method.iter_from
 /// [`get_proof`]: #method.get_proof
+/// [`Fork`]: ../../struct.Fork.html
 #[derive(Debug)]
-pub struct ProofListIndex<T: RawAccess, V> {
+pub struct ProofListIndex<T: RawAccess, V, H: Hasher = HashTag> {
     base: View<T>,
     state: IndexState<T, u64>,
+    // Cached Merkle root of the list. The cache is only filled by methods mutating the list
+    // (which recompute the root anyway), so that read-only views stay `Sync`. The root key
+    // depends on the list length, so the cache is reset by all methods changing the length.
+    root_cache: Option<Hash>,
     _v: PhantomData<V>,
+    _hasher: PhantomData<H>,
 }
 
-impl<T, V> MerkleTree<V> for ProofListIndex<T, V>
+impl<T, V, H> MerkleTree<V> for ProofListIndex<T, V, H>
 where
     T: RawAccess,
     V: BinaryValue,
+    H: Hasher,
 {
     fn len(&self) -> u64 {
         self.len()
@@ -96,7 +221,7 @@ where
     }
 
     fn merkle_root(&self) -> Hash {
-        self.get_branch(self.root_key()).unwrap_or_default()
+        self.merkle_root()
     }
 
     fn values<'s>(&'s self, start_index: u64) -> Box<dyn Iterator<Item = V> + 's> {
@@ -104,28 +229,40 @@ where
     }
 }
 
-impl<T, V> FromAccess<T> for ProofListIndex<T::Base, V>
+impl<T, V, H> FromAccess<T> for ProofListIndex<T::Base, V, H>
 where
     T: Access,
     V: BinaryValue,
+    H: Hasher + 'static,
 {
     fn from_access(access: T, addr: IndexAddress) -> Result<Self, AccessError> {
+        // The state aggregator always hashes lists with the default hasher, so lists
+        // with other hashers are only allowed in groups, which are not aggregated.
+        if TypeId::of::<H>() != TypeId::of::<HashTag>() && addr.id_in_group().is_none() {
+            return Err(AccessError {
+                addr,
+                kind: AccessErrorKind::AggregatedCustomHasher,
+            });
+        }
         let view = access.get_or_create_view(addr, IndexType::ProofList)?;
         Ok(Self::new(view))
     }
 }
 
-impl<T, V> ProofListIndex<T, V>
+impl<T, V, H> ProofListIndex<T, V, H>
 where
     T: RawAccess,
     V: BinaryValue,
+    H: Hasher,
 {
     pub(crate) fn new(view: ViewWithMetadata<T>) -> Self {
         let (base, state) = view.into_parts();
         Self {
             base,
             state,
+            root_cache: None,
             _v: PhantomData,
+            _hasher: PhantomData,
         }
     }
 
@@ -146,6 +283,16 @@ where
         self.base.get(&key).unwrap()
     }
 
+    /// Returns the hash of the tree branch with the specified key, taking into account
+    /// that branches above the tree root are not present.
+    fn get_existing_branch(&self, key: ProofListKey) -> Option<Hash> {
+        if key.height() > self.height() {
+            None
+        } else {
+            self.get_branch(key)
+        }
+    }
+
     fn root_key(&self) -> ProofListKey {
         ProofListKey::new(self.height(), 0)
     }
@@ -153,6 +300,14 @@ where
     /// Returns the element at the indicated position or `None` if the indicated position
     /// is out of bounds.
     ///
+    /// # Notes
+    ///
+    /// `ProofListIndex` intentionally does not implement [`std::ops::Index`]. List elements
+    /// are deserialized from the database on each access, so there is no storage to return
+    /// a reference to; caching deserialized elements inside the index would lead to
+    /// unbounded memory consumption. Use `get(index).expect(..)` if a missing element should
+    /// lead to a panic.
+    ///
     /// # Examples
     ///
     /// ```
@@ -166,6 +321,8 @@ where
     /// index.push(10);
     /// assert_eq!(Some(10), index.get(0));
     /// ```
+    ///
+    /// [`std::ops::Index`]: https://doc.rust-lang.org/std/ops/trait.Index.html
     pub fn get(&self, index: u64) -> Option<V> {
         if index > MAX_INDEX {
             return None;
@@ -173,6 +330,52 @@ where
         self.base.get(&ProofListKey::leaf(index))
     }
 
+    /// Returns the element at the indicated position or the default value if the indicated
+    /// position is out of bounds.
+    ///
+    /// Together with [`resize`], this method allows to use the list as a Merkelized vector
+    /// in which unset elements are logically equal to the default value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.push(1_u32);
+    /// assert_eq!(index.get_or_default(0), 1);
+    /// assert_eq!(index.get_or_default(10), 0);
+    /// ```
+    ///
+    /// [`resize`]: #method.resize
+    pub fn get_or_default(&self, index: u64) -> V
+    where
+        V: Default,
+    {
+        self.get(index).unwrap_or_default()
+    }
+
+    /// Returns the first element of the proof list or `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// assert_eq!(None, index.first());
+    ///
+    /// index.extend(vec![1, 2]);
+    /// assert_eq!(Some(1), index.first());
+    /// ```
+    pub fn first(&self) -> Option<V> {
+        self.get(0)
+    }
+
     /// Returns the last element of the proof list or `None` if it is empty.
     ///
     /// # Examples
@@ -233,10 +436,50 @@ where
         self.state.get().unwrap_or_default()
     }
 
+    /// Checks whether the list contains an element with the specified `index`,
+    /// i.e., whether `index < self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// assert!(!index.contains_index(0));
+    ///
+    /// index.push(1);
+    /// assert!(index.contains_index(0));
+    /// assert!(!index.contains_index(1));
+    /// ```
+    pub fn contains_index(&self, index: u64) -> bool {
+        index < self.len()
+    }
+
+    /// Returns the range of valid indexes in the list, i.e., `0..self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// assert!(index.valid_range().is_empty());
+    ///
+    /// index.extend(vec![1, 2, 3]);
+    /// assert_eq!(index.valid_range(), 0..3);
+    /// ```
+    pub fn valid_range(&self) -> Range<u64> {
+        0..self.len()
+    }
+
     /// Returns the height of the Merkle tree built based on the list.
     ///
     /// The height of the empty list is 0; otherwise, the height is computed as `ceil(log2(l)) + 1`,
-    /// where `l` is the list length.
+    /// where `l` is the list length. See [`height_of_len`] for details.
     ///
     /// # Examples
     ///
@@ -252,192 +495,1358 @@ where
     /// index.push(1);
     /// assert_eq!(2, index.height());
     /// ```
+    ///
+    /// [`height_of_len`]: fn.height_of_len.html
     pub fn height(&self) -> u8 {
-        tree_height_by_length(self.len())
+        height_of_len(self.len())
     }
 
-    /// Returns a proof of existence for the list element at the specified position.
+    /// Returns the hash of the list, which commits to the list length and all list elements.
     ///
-    /// Returns a proof of absence if the list doesn't contain an element with the specified `index`.
+    /// The list hash is the same as the [`object_hash`] of the list; see the
+    /// [`ObjectHash` implementation](#impl-ObjectHash) for the specification.
     ///
     /// # Examples
     ///
     /// ```
-    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, HashTag, ObjectHash};
     ///
     /// let db = TemporaryDB::new();
     /// let fork = db.fork();
     /// let mut index = fork.get_proof_list("name");
-    ///
-    /// index.push(1);
-    /// let proof = index.get_proof(0);
-    /// let proof_of_absence = index.get_proof(1);
+    /// assert_eq!(index.list_hash(), HashTag::empty_list_hash());
+    /// index.push(1_u32);
+    /// assert_eq!(index.list_hash(), index.object_hash());
     /// ```
-    pub fn get_proof(&self, index: u64) -> ListProof<V> {
-        self.create_proof(index)
+    ///
+    /// [`object_hash`]: ../trait.ObjectHash.html#tymethod.object_hash
+    pub fn list_hash(&self) -> Hash {
+        self.head().1
     }
 
-    /// Returns the proof of existence for the list elements in the specified range.
-    ///
-    /// Returns a proof of absence for a range of values, if either or both its bounds
-    /// exceed the list state.
+    /// Returns the fingerprint of the element type stored in the index metadata, or `None`
+    /// if the fingerprint was never set. See [`ensure_element_fingerprint`] for details.
     ///
-    /// # Panics
+    /// [`ensure_element_fingerprint`]: #method.ensure_element_fingerprint
+    pub fn element_fingerprint(&self) -> Option<u64> {
+        self.state.fingerprint()
+    }
+
+    /// Returns the list length together with the [`list_hash`] of the list.
     ///
-    /// Panics if the range bounds are illegal.
+    /// Both values are derived from a single read of the list length: the list hash is computed
+    /// from the length and the Merkle root, and the key of the root is determined by the length
+    /// as well. Thus, the returned values always correspond to the same state of the list,
+    /// which is not guaranteed if `len()` and `list_hash()` are called separately with
+    /// the list mutated in between.
     ///
     /// # Examples
     ///
     /// ```
-    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ObjectHash};
     ///
     /// let db = TemporaryDB::new();
     /// let fork = db.fork();
     /// let mut index = fork.get_proof_list("name");
-    /// index.extend(vec![1, 2, 3, 4, 5]);
-    ///
-    /// let range_proof = index.get_range_proof(1..3);
-    /// assert!(range_proof.indexes_unchecked().eq(vec![1, 2]));
-    /// // This proof will contain only 4 elements with indexes 1..5.
-    /// let intersection_proof = index.get_range_proof(1..10);
-    /// assert!(intersection_proof.indexes_unchecked().eq(1..5));
-    /// // This proof does not contain any elements at all.
-    /// let empty_proof = index.get_range_proof(100..10000);
-    /// assert!(empty_proof.entries_unchecked().is_empty());
+    /// index.extend(vec![1_u32, 2, 3]);
+    /// assert_eq!(index.head(), (3, index.object_hash()));
     /// ```
-    pub fn get_range_proof<R: RangeBounds<u64>>(&self, range: R) -> ListProof<V> {
-        self.create_range_proof(range)
+    ///
+    /// [`list_hash`]: #method.list_hash
+    pub fn head(&self) -> (u64, Hash) {
+        let len = self.len();
+        let root = if len == 0 {
+            Hash::zero()
+        } else if let Some(root) = self.root_cache {
+            root
+        } else {
+            self.base
+                .get(&ProofListKey::new(height_of_len(len), 0))
+                .unwrap_or_default()
+        };
+        (len, H::hash_list_node(len, root))
     }
 
-    /// Returns an iterator over the list values.
+    /// Returns the root hash of the Merkle tree built based on the list, or `Hash::zero()`
+    /// for an empty list.
+    ///
+    /// Unlike the [`list_hash`], the Merkle root does not commit to the list length.
+    /// The list hash is derived from the root as
+    ///
+    /// ```text
+    /// list_hash = H::hash_list_node(len, merkle_root)
+    /// ```
+    ///
+    /// which is `sha256( HashTag::ListNode || u64_LE(len) || merkle_root )` with the default
+    /// hasher. The raw root may be used to implement custom schemes committing to the list length.
     ///
     /// # Examples
     ///
     /// ```
-    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, HashTag, ObjectHash};
+    /// use exonum_crypto::Hash;
     ///
     /// let db = TemporaryDB::new();
     /// let fork = db.fork();
-    /// let index = fork.get_proof_list::<_, u8>("name");
+    /// let mut index = fork.get_proof_list("name");
+    /// assert_eq!(index.merkle_root(), Hash::zero());
     ///
-    /// for val in index.iter() {
-    ///     println!("{}", val);
-    /// }
+    /// index.extend(vec![1_u32, 2, 3]);
+    /// let list_hash = HashTag::hash_list_node(3, index.merkle_root());
+    /// assert_eq!(list_hash, index.object_hash());
     /// ```
-    pub fn iter(&self) -> Values<'_, V> {
-        self.index_iter(None).skip_keys()
+    ///
+    /// [`list_hash`]: #method.list_hash
+    pub fn merkle_root(&self) -> Hash {
+        if let Some(root) = self.root_cache {
+            return root;
+        }
+        self.get_branch(self.root_key()).unwrap_or_default()
     }
 
-    /// Returns an iterator over the list values starting from the specified position.
+    /// Returns an incremental hasher initialized with the current state of the list.
+    ///
+    /// The hasher can be used to compute the list hash after appending elements without
+    /// accessing the database. Initializing the hasher requires reading `O(log n)` hashes
+    /// from the database, where `n` is the list length. See [`ListHasher`] for an example.
+    ///
+    /// [`ListHasher`]: struct.ListHasher.html
+    pub fn list_hasher(&self) -> ListHasher<H> {
+        let len = self.len();
+        let last_index = match len.checked_sub(1) {
+            Some(index) => index,
+            None => return ListHasher::new(),
+        };
+
+        // Complete subtrees covering elements `0..last_index` are the left siblings
+        // of the nodes on the rightmost path of the tree.
+        let subtrees = (1..self.height())
+            .map(|height| {
+                let index = last_index >> (height - 1);
+                if index % 2 == 1 {
+                    Some(self.get_branch_unchecked(ProofListKey::new(height, index - 1)))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let last = self.get_branch_unchecked(ProofListKey::new(1, last_index));
+        ListHasher::from_parts(len, subtrees, Some(last))
+    }
+
+    /// Returns a checkpoint of the list, which contains its length, hash and `O(log n)` hashes
+    /// necessary to verify appending elements to the list. See [`verify_checkpoint_chain`]
+    /// for an example.
+    ///
+    /// [`verify_checkpoint_chain`]: fn.verify_checkpoint_chain.html
+    pub fn checkpoint(&self) -> ListCheckpoint {
+        self.list_hasher().checkpoint()
+    }
+
+    /// Returns the minimal proof of the list length, which consists of the length
+    /// and the [Merkle root] of the list.
+    ///
+    /// Since the list hash commits to the list length and the Merkle root, the proof can be
+    /// verified against the trusted list hash with [`HashTag::verify_length`] without revealing
+    /// any list elements.
     ///
     /// # Examples
     ///
     /// ```
-    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, HashTag, ObjectHash};
     ///
     /// let db = TemporaryDB::new();
     /// let fork = db.fork();
-    /// let index = fork.get_proof_list::<_, u8>("name");
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2, 3]);
     ///
-    /// for val in index.iter_from(1) {
-    ///     println!("{}", val);
-    /// }
+    /// let (len, merkle_root) = index.length_proof();
+    /// assert_eq!(len, 3);
+    /// assert!(HashTag::verify_length(len, merkle_root, index.object_hash()));
+    /// assert!(!HashTag::verify_length(4, merkle_root, index.object_hash()));
     /// ```
-    pub fn iter_from(&self, from: u64) -> Values<'_, V> {
-        self.index_iter(Some(&from)).skip_keys()
-    }
-}
-
-impl<T, V> ProofListIndex<T, V>
-where
-    T: RawAccessMut,
-    V: BinaryValue,
-{
-    fn set_len(&mut self, len: u64) {
-        self.state.set(len)
+    ///
+    /// [Merkle root]: #method.merkle_root
+    /// [`HashTag::verify_length`]: ../../enum.HashTag.html#method.verify_length
+    pub fn length_proof(&self) -> (u64, Hash) {
+        (self.len(), self.merkle_root())
     }
 
-    /// Updates levels of the tree with heights `2..` after the values in the range
-    /// `[first_index, last_index]` were updated.
+    /// Checks internal consistency of the index.
     ///
-    /// # Invariants
+    /// The Merkle tree is walked bottom-up: the hash of each leaf is recomputed from
+    /// the stored list element, and the hash of each branch is recomputed from its children.
+    /// Recomputed hashes are compared to the stored ones; finally, the cached [`merkle_root`]
+    /// and [`list_hash`] are checked against the recomputed root of the tree.
     ///
-    /// - `self.len()` / `self.height()` is assumed to be correctly set.
-    /// - Value hashes (i.e., tree branches on level 1) are assumed to be updated.
-    fn update_range(&mut self, mut first_index: u64, mut last_index: u64) {
-        // Index of the last element on the current `height` of the tree.
-        let mut last_index_on_height = self.len() - 1;
-
-        for height in 1..self.height() {
-            // Check consistency of the index range.
-            debug_assert!(first_index <= last_index);
-            // Check consistency with the level length.
-            debug_assert!(last_index <= last_index_on_height);
-
-            // Calculate the start and stop indexes to process at the current `height`.
-
-            // The start index is always even, since during hashing we hash together
-            // an element with an even index and the following element (if it exists). Thus, we may
-            // need to decrease `first_index` by 1 to get the actual starting index if
-            // `first_index` is odd. The code below does just that; it is equivalent to
-            //
-            //    let mut index = first_index - (first_index % 2);
-            //
-            // ...just a bit faster.
-            let mut index = first_index & !1;
-
-            // To get the stop index, we may need to increase `last_index` to get an odd value,
-            // but we need to keep in mind the resulting index may be not in the tree.
-            // `last_index | 1` is equivalent to
-            //
-            //    last_index + (1 - last_index % 2)
-            //
-            // ...just a bit faster.
-            let stop_index = cmp::min(last_index | 1, last_index_on_height);
+    /// The check reads the entire tree, so it is intended for debugging and testing only.
+    /// Outside of unit tests, the method is available with the `integrity-check` crate feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first detected inconsistency.
+    ///
+    /// [`merkle_root`]: #method.merkle_root
+    /// [`list_hash`]: #method.list_hash
+    #[cfg(any(test, feature = "integrity-check"))]
+    pub fn verify_integrity(&self) -> Result<(), IntegrityError> {
+        let len = self.len();
+        if len == 0 {
+            return if self.merkle_root() == Hash::zero() {
+                Ok(())
+            } else {
+                Err(IntegrityError::RootMismatch)
+            };
+        }
 
-            while index < stop_index {
-                let key = ProofListKey::new(height, index);
-                let branch_hash = HashTag::hash_node(
-                    &self.get_branch_unchecked(key),
-                    &self.get_branch_unchecked(key.as_right()),
-                );
-                self.base.put(&key.parent(), branch_hash);
-                index += 2;
+        for index in 0..len {
+            let key = ProofListKey::new(1, index);
+            let value = self.base.get::<_, V>(&ProofListKey::leaf(index)).ok_or(
+                IntegrityError::MissingEntry {
+                    key: ProofListKey::leaf(index),
+                },
+            )?;
+            let stored_hash = self
+                .base
+                .get::<_, Hash>(&key)
+                .ok_or(IntegrityError::MissingEntry { key })?;
+            if stored_hash != H::hash_leaf(&value.to_bytes()) {
+                return Err(IntegrityError::HashMismatch { key });
             }
+        }
 
-            if stop_index % 2 == 0 {
-                let key = ProofListKey::new(height, stop_index);
-                let branch_hash = HashTag::hash_single_node(&self.get_branch_unchecked(key));
-                self.base.put(&key.parent(), branch_hash);
+        let last_index = len - 1;
+        for height in 1..self.height() {
+            let last_index_on_height = last_index >> (height - 1);
+            for parent_index in 0..=(last_index_on_height / 2) {
+                let left_key = ProofListKey::new(height, parent_index * 2);
+                let left_hash = self.get_branch_unchecked(left_key);
+                let hash = if left_key.index() < last_index_on_height {
+                    H::hash_node(&left_hash, &self.get_branch_unchecked(left_key.as_right()))
+                } else {
+                    H::hash_single_node(&left_hash)
+                };
+
+                let key = left_key.parent();
+                let stored_hash = self
+                    .base
+                    .get::<_, Hash>(&key)
+                    .ok_or(IntegrityError::MissingEntry { key })?;
+                if stored_hash != hash {
+                    return Err(IntegrityError::HashMismatch { key });
+                }
             }
-
-            first_index /= 2;
-            last_index /= 2;
-            last_index_on_height /= 2;
         }
 
-        debug_assert_eq!(first_index, 0);
-        debug_assert_eq!(last_index, 0);
-        debug_assert_eq!(last_index_on_height, 0);
+        let root = self.get_branch_unchecked(self.root_key());
+        if self.merkle_root() != root || self.list_hash() != H::hash_list_node(len, root) {
+            return Err(IntegrityError::RootMismatch);
+        }
+        Ok(())
     }
 
-    /// Removes the extra elements in the tree on heights `1..` and updates elements
-    /// where it is necessary.
+    /// Returns the hash the list had when it consisted of the first `len` elements,
+    /// i.e., the [`list_hash`] of the list truncated to `len` elements. The list
+    /// is not modified.
     ///
-    /// # Invariants
+    /// The Merkle tree of the prefix shares all branches with the tree of the entire list,
+    /// except for the branches on the rightmost path of the prefix tree. Thus, only these
+    /// branches are recomputed, which requires reading `O(log(len))` hashes from the database.
+    /// If `len` is greater or equal than the list length, the current list hash is returned.
     ///
-    /// - List length is assumed to be updated.
-    fn remove_range(&mut self, mut old_last_index: u64, old_height: u8) {
-        let new_length = self.len();
-        // New last index of the element on a certain height. The height of the tree
-        // may decrease after removing elements; we encode this case as `last_index == None`.
-        let mut last_index = Some(new_length - 1);
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, HashTag, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2]);
+    /// let old_hash = index.list_hash();
+    /// index.extend(vec![3, 4, 5]);
+    ///
+    /// assert_eq!(index.prefix_hash(2), old_hash);
+    /// assert_eq!(index.prefix_hash(0), HashTag::empty_list_hash());
+    /// assert_eq!(index.prefix_hash(10), index.list_hash());
+    /// ```
+    ///
+    /// [`list_hash`]: #method.list_hash
+    pub fn prefix_hash(&self, len: u64) -> Hash {
+        if len >= self.len() {
+            return self.list_hash();
+        }
+        if len == 0 {
+            return H::hash_list_node(0, Hash::zero());
+        }
 
-        // Have we started updating hashes in the tree? Up to a certain height, we may just
-        // remove hashes from the tree. However, once the last element on the tree level is even
-        // and has its right neighbor removed, the hashes of the rightmost elements on each
-        // following height must be updated.
-        let mut started_updating_hashes = false;
+        // Compute the hashes on the rightmost path of the prefix tree bottom-up.
+        // `index` is the index of the node on the path at the current `height`.
+        let mut index = len - 1;
+        let mut hash = self.get_branch_unchecked(ProofListKey::new(1, index));
+        for height in 1..height_of_len(len) {
+            hash = if index % 2 == 1 {
+                // The left sibling is a complete subtree, so its hash is the same
+                // as in the tree of the entire list.
+                let left_key = ProofListKey::new(height, index - 1);
+                H::hash_node(&self.get_branch_unchecked(left_key), &hash)
+            } else {
+                // The node is the last one on its height, so it has no right sibling.
+                H::hash_single_node(&hash)
+            };
+            index /= 2;
+        }
+        H::hash_list_node(len, hash)
+    }
+
+    /// Checks whether this list is a prefix of the `other` list, i.e., whether `other`
+    /// starts with all elements of this list in the same order. An empty list is a prefix
+    /// of any list; each list is a prefix of itself.
+    ///
+    /// The check compares the [`list_hash`] of this list with the [`prefix_hash`]
+    /// of the `other` list and does not compare elements. Thus, it requires reading
+    /// `O(log n)` hashes from the database, where `n` is the length of this list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut local = fork.get_proof_list("local");
+    /// local.extend(vec![1_u32, 2]);
+    /// let mut remote = fork.get_proof_list("remote");
+    /// remote.extend(vec![1_u32, 2, 3]);
+    ///
+    /// assert!(local.is_prefix_of(&remote));
+    /// assert!(!remote.is_prefix_of(&local));
+    /// local.push(4);
+    /// assert!(!local.is_prefix_of(&remote));
+    /// ```
+    ///
+    /// [`list_hash`]: #method.list_hash
+    /// [`prefix_hash`]: #method.prefix_hash
+    pub fn is_prefix_of<U: RawAccess>(&self, other: &ProofListIndex<U, V, H>) -> bool {
+        let len = self.len();
+        len <= other.len() && other.prefix_hash(len) == self.list_hash()
+    }
+
+    /// Binary searches the list with a comparator function.
+    ///
+    /// The comparator function should return an order code that indicates whether
+    /// its argument is `Less`, `Equal` or `Greater` than the desired target. The semantics
+    /// of the method is the same as for [`slice::binary_search_by`]: if the value is found,
+    /// `Ok` is returned with the index of the matching element; if there are multiple matches,
+    /// any one of them can be returned. If the value is not found, `Err` is returned
+    /// with the index where a matching element could be inserted while maintaining
+    /// the sorted order.
+    ///
+    /// The search takes `O(log n)` element lookups, where `n` is the list length.
+    /// The caller is responsible for the list being sorted according to the comparator;
+    /// if it is not, the returned result is unspecified and meaningless.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 3, 5, 7]);
+    ///
+    /// assert_eq!(index.binary_search_by(|probe| probe.cmp(&5)), Ok(2));
+    /// assert_eq!(index.binary_search_by(|probe| probe.cmp(&4)), Err(2));
+    /// assert_eq!(index.binary_search_by(|probe| probe.cmp(&10)), Err(4));
+    /// ```
+    ///
+    /// [`slice::binary_search_by`]: https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<u64, u64>
+    where
+        F: FnMut(&V) -> cmp::Ordering,
+    {
+        let (mut left, mut right) = (0, self.len());
+        while left < right {
+            let middle = left + (right - left) / 2;
+            let value = self.get(middle).expect("List element is missing");
+            match f(&value) {
+                cmp::Ordering::Less => left = middle + 1,
+                cmp::Ordering::Greater => right = middle,
+                cmp::Ordering::Equal => return Ok(middle),
+            }
+        }
+        Err(left)
+    }
+
+    /// Checks whether this list has the same contents as `other` by comparing their lengths
+    /// and `object_hash()`es.
+    ///
+    /// This is a hash comparison rather than a deep comparison of list elements; thus, it takes
+    /// constant time regardless of list lengths. The result is correct under the assumption
+    /// that the hash function is collision-resistant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// let mut other = fork.get_proof_list("other");
+    /// index.extend(vec![1_u8, 2, 3]);
+    /// other.extend(vec![1_u8, 2]);
+    /// assert!(!index.structurally_eq(&other));
+    /// other.push(3);
+    /// assert!(index.structurally_eq(&other));
+    /// ```
+    pub fn structurally_eq<U: RawAccess>(&self, other: &ProofListIndex<U, V, H>) -> bool {
+        self.len() == other.len() && self.object_hash() == other.object_hash()
+    }
+
+    /// Returns indexes of elements that differ between this list and `other`, in the increasing
+    /// order.
+    ///
+    /// The Merkle trees of both lists are traversed top-down, and subtrees with equal hashes
+    /// are skipped. Thus, the method reads `O(k * log(n))` tree branches for `k` differing
+    /// elements in lists of length `n`, rather than comparing all elements. If the lists have
+    /// different lengths, the indexes present in only one of the lists are reported as
+    /// differing as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// let mut other = fork.get_proof_list("other");
+    /// index.extend(vec![1_u8, 2, 3, 4, 5]);
+    /// other.extend(vec![1_u8, 0, 3, 4, 5, 6, 7]);
+    /// assert_eq!(index.diff(&other), vec![1, 5, 6]);
+    /// ```
+    pub fn diff<U: RawAccess>(&self, other: &ProofListIndex<U, V, H>) -> Vec<u64> {
+        let common_len = cmp::min(self.len(), other.len());
+        let max_len = cmp::max(self.len(), other.len());
+        let mut differing_indexes = vec![];
+
+        if common_len > 0 {
+            let max_height = cmp::max(self.height(), other.height());
+            let mut keys = vec![ProofListKey::new(max_height, 0)];
+            while let Some(key) = keys.pop() {
+                // Elements outside of the common part of the lists are reported separately.
+                if key.first_left_leaf_index() >= common_len {
+                    continue;
+                }
+
+                let hash = self.get_existing_branch(key);
+                if hash.is_some() && hash == other.get_existing_branch(key) {
+                    continue;
+                }
+
+                if key.height() == 1 {
+                    differing_indexes.push(key.index());
+                } else {
+                    // The right child is pushed first, so that indexes are visited in order.
+                    keys.push(key.right());
+                    keys.push(key.left());
+                }
+            }
+        }
+
+        differing_indexes.extend(common_len..max_len);
+        differing_indexes
+    }
+
+    /// Returns a proof of existence for the last list element, or a proof of absence
+    /// if the list is empty.
+    ///
+    /// This is a specialization of `get_proof(len - 1)` for a common case of proving
+    /// the most recently appended element. The proof is built by walking the rightmost path
+    /// of the Merkle tree, on which there are no right siblings; thus, only the left siblings
+    /// on the path are read from the database.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2, 3]);
+    ///
+    /// let proof = index.last_proof();
+    /// assert_eq!(proof, index.get_proof(2));
+    /// let checked_proof = proof.check_against_hash(index.object_hash()).unwrap();
+    /// assert_eq!(*checked_proof.entries(), [(2, 3)]);
+    /// ```
+    pub fn last_proof(&self) -> ListProof<V, H> {
+        let len = self.len();
+        let last_index = match len.checked_sub(1) {
+            Some(index) => index,
+            None => return ListProof::empty(self.merkle_root(), len),
+        };
+
+        let value = self.get(last_index).expect("Last list element is missing");
+        let mut proof = ListProof::new(iter::once((last_index, value)), len);
+        // Index of the node on the rightmost path of the tree at the current `height`.
+        let mut index = last_index;
+        for height in 1..self.height() {
+            // The node may only have a left sibling, which exists iff the node index is odd.
+            if index % 2 == 1 {
+                let key = ProofListKey::new(height, index - 1);
+                proof.push_hash(height, index - 1, self.get_branch_unchecked(key));
+            }
+            index /= 2;
+        }
+        proof
+    }
+
+    /// Returns a proof of existence for the list element at the specified position.
+    ///
+    /// Returns a proof of absence if the list doesn't contain an element with the specified `index`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    ///
+    /// index.push(1);
+    /// let proof = index.get_proof(0);
+    /// let proof_of_absence = index.get_proof(1);
+    /// ```
+    pub fn get_proof(&self, index: u64) -> ListProof<V, H> {
+        self.create_proof(index)
+    }
+
+    /// Returns a proof of existence for the list element at the specified position.
+    ///
+    /// Unlike [`get_proof`], this method does not return a proof of absence; if the list
+    /// doesn't contain an element with the specified `index`, an error is returned instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `index` is equal or greater than the current state of the proof list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{
+    ///     access::CopyAccessExt, proof_list::ProofListError, TemporaryDB, Database,
+    /// };
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    ///
+    /// index.push(1);
+    /// let proof = index.get_proof_checked(0).unwrap();
+    /// assert_eq!(proof.entries_unchecked(), [(0, 1)]);
+    /// let err = index.get_proof_checked(1).unwrap_err();
+    /// assert_eq!(err, ProofListError::IndexOutOfBounds { index: 1, len: 1 });
+    /// ```
+    ///
+    /// [`get_proof`]: #method.get_proof
+    pub fn get_proof_checked(&self, index: u64) -> Result<ListProof<V, H>, ProofListError> {
+        let len = self.len();
+        if index >= len {
+            return Err(ProofListError::IndexOutOfBounds { index, len });
+        }
+        Ok(self.create_proof(index))
+    }
+
+    /// Finds the first list element satisfying `predicate` and returns its index together
+    /// with the proof of existence for the element, or `None` if no element matches.
+    ///
+    /// Finding the element requires scanning the list from the start, so it takes `O(n)`
+    /// time, where `n` is the list length. The proof itself contains `O(log n)` hashes.
+    /// Note that the proof only attests to the element value; it does not prove that
+    /// the preceding elements do not satisfy `predicate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![10_u64, 50, 200, 300]);
+    ///
+    /// let (position, proof) = index.get_first_matching_proof(|&amount| amount > 100).unwrap();
+    /// assert_eq!(position, 2);
+    /// let checked_proof = proof.check_against_hash(index.object_hash()).unwrap();
+    /// assert_eq!(*checked_proof.entries(), [(2, 200)]);
+    /// assert!(index.get_first_matching_proof(|&amount| amount > 1_000).is_none());
+    /// ```
+    pub fn get_first_matching_proof<F>(&self, mut predicate: F) -> Option<(u64, ListProof<V, H>)>
+    where
+        F: FnMut(&V) -> bool,
+    {
+        let index = (0..)
+            .zip(self.iter())
+            .find(|(_, value)| predicate(value))
+            .map(|(index, _)| index)?;
+        Some((index, self.create_proof(index)))
+    }
+
+    /// Returns a proof that the list element at the specified position exists and differs
+    /// from `not_value`.
+    ///
+    /// The returned proof is a usual proof of existence for the element; the verifier
+    /// should check the proof and confirm that the revealed value differs from `not_value`.
+    /// This method is useful for dispute resolution, when it is necessary to prove that
+    /// a list element is not equal to the claimed value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `index` is equal or greater than the current state of the proof list,
+    /// or if the element at `index` is equal to `not_value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{
+    ///     access::CopyAccessExt, proof_list::ProofListError, TemporaryDB, Database, ObjectHash,
+    /// };
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2, 3]);
+    ///
+    /// let proof = index.get_inequality_proof(1, &5).unwrap();
+    /// // Verifier side.
+    /// let checked_proof = proof.check_against_hash(index.object_hash()).unwrap();
+    /// let (proven_index, proven_value) = checked_proof.entries()[0];
+    /// assert_eq!(proven_index, 1);
+    /// assert_ne!(proven_value, 5);
+    ///
+    /// let err = index.get_inequality_proof(1, &2).unwrap_err();
+    /// assert_eq!(err, ProofListError::EqualValue { index: 1 });
+    /// ```
+    pub fn get_inequality_proof(
+        &self,
+        index: u64,
+        not_value: &V,
+    ) -> Result<ListProof<V, H>, ProofListError>
+    where
+        V: PartialEq,
+    {
+        let len = self.len();
+        match self.get(index) {
+            None => Err(ProofListError::IndexOutOfBounds { index, len }),
+            Some(ref value) if value == not_value => Err(ProofListError::EqualValue { index }),
+            Some(_) => Ok(self.create_proof(index)),
+        }
+    }
+
+    /// Returns the proof of existence for the list elements in the specified range.
+    ///
+    /// Returns a proof of absence for a range of values, if either or both its bounds
+    /// exceed the list state.
+    ///
+    /// The range boundaries are handled as follows:
+    ///
+    /// - If the range is empty (e.g., `3..3`) or starts at or beyond the list length
+    ///   (e.g., `len..len + 1` or `1_000_000..`), a proof of absence is returned.
+    /// - If the range intersects with the list (e.g., `len - 1..len + 5`), a proof
+    ///   for the intersection is returned.
+    /// - If the range end is less than its start (e.g., `5..3`), the range is illegal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range bounds are illegal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1, 2, 3, 4, 5]);
+    ///
+    /// let range_proof = index.get_range_proof(1..3);
+    /// assert!(range_proof.indexes_unchecked().eq(vec![1, 2]));
+    /// // This proof will contain only 4 elements with indexes 1..5.
+    /// let intersection_proof = index.get_range_proof(1..10);
+    /// assert!(intersection_proof.indexes_unchecked().eq(1..5));
+    /// // This proof does not contain any elements at all.
+    /// let empty_proof = index.get_range_proof(100..10000);
+    /// assert!(empty_proof.entries_unchecked().is_empty());
+    /// ```
+    pub fn get_range_proof<R: RangeBounds<u64>>(&self, range: R) -> ListProof<V, H> {
+        self.try_get_range_proof(range)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Returns the proof of existence for the list elements in the specified range together
+    /// with the list length.
+    ///
+    /// The returned length is the length the proof was built for, so it is always consistent
+    /// with the proof; it is the same as `proof.list_len()`. See [`get_range_proof`]
+    /// for the details on handling range boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range bounds are illegal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1, 2, 3, 4, 5]);
+    ///
+    /// let (proof, len) = index.get_range_proof_with_len(1..3);
+    /// assert_eq!(len, 5);
+    /// assert!(proof.indexes_unchecked().eq(vec![1, 2]));
+    /// ```
+    ///
+    /// [`get_range_proof`]: #method.get_range_proof
+    pub fn get_range_proof_with_len<R: RangeBounds<u64>>(
+        &self,
+        range: R,
+    ) -> (ListProof<V, H>, u64) {
+        let proof = self.get_range_proof(range);
+        let len = proof.list_len();
+        (proof, len)
+    }
+
+    /// Returns the proof of existence for the list elements in the specified range.
+    ///
+    /// Unlike [`get_range_proof`], this method returns an error instead of panicking
+    /// if the range bounds are illegal. This is useful if the range is obtained
+    /// from an untrusted source, such as an HTTP request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range start is greater than the range end. See
+    /// [`get_range_proof`] for the details on handling range boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{
+    ///     access::CopyAccessExt, proof_list::ProofListError, TemporaryDB, Database,
+    /// };
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1, 2, 3, 4, 5]);
+    ///
+    /// let range_proof = index.try_get_range_proof(1..3).unwrap();
+    /// assert!(range_proof.indexes_unchecked().eq(vec![1, 2]));
+    /// let err = index.try_get_range_proof(3..1).unwrap_err();
+    /// assert_eq!(err, ProofListError::IllegalRange { start: 3, end: 1 });
+    /// ```
+    ///
+    /// [`get_range_proof`]: #method.get_range_proof
+    pub fn try_get_range_proof<R: RangeBounds<u64>>(
+        &self,
+        range: R,
+    ) -> Result<ListProof<V, H>, ProofListError> {
+        self.create_range_proof(range)
+    }
+
+    /// Returns the proof of existence for all list elements.
+    ///
+    /// The proof is equivalent to `get_range_proof(..)`, but it is built without traversing
+    /// the Merkle tree: since all leaves are present in the proof, it does not need
+    /// any branch hashes. For an empty list, a proof of absence is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2, 3]);
+    ///
+    /// let proof = index.get_full_proof();
+    /// let checked_proof = proof.check_against_hash(index.object_hash()).unwrap();
+    /// assert_eq!(*checked_proof.entries(), [(0, 1), (1, 2), (2, 3)]);
+    /// ```
+    pub fn get_full_proof(&self) -> ListProof<V, H> {
+        if self.is_empty() {
+            ListProof::empty(self.merkle_root(), 0)
+        } else {
+            ListProof::new(self.iter_with_indexes(), self.len())
+        }
+    }
+
+    /// Returns the proof of existence for the list elements with the specified indexes.
+    ///
+    /// Unlike [`get_range_proof`], indexes do not need to be contiguous; the hashes shared
+    /// by the proved elements are included into the proof only once. Indexes may be unsorted
+    /// and may contain duplicates.
+    ///
+    /// Returns a proof of absence if the indexes are empty or if any of them is not less than
+    /// the list length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(0_u32..10);
+    ///
+    /// let proof = index.get_multiproof(vec![7, 1, 3, 7]);
+    /// let checked_proof = proof.check_against_hash(index.object_hash()).unwrap();
+    /// assert_eq!(*checked_proof.entries(), [(1, 1), (3, 3), (7, 7)]);
+    /// // This proof does not contain any elements at all.
+    /// let absence_proof = index.get_multiproof(vec![1, 100]);
+    /// assert!(absence_proof.entries_unchecked().is_empty());
+    /// ```
+    ///
+    /// [`get_range_proof`]: #method.get_range_proof
+    pub fn get_multiproof(&self, indexes: impl IntoIterator<Item = u64>) -> ListProof<V, H> {
+        self.create_multiproof(indexes)
+    }
+
+    /// Returns the proof of existence for the list elements with the specified indexes,
+    /// provided that the proof contains at most `max_proof_nodes` elements and hashes in total.
+    ///
+    /// This method works like [`get_multiproof`], but it protects against building
+    /// excessively large proofs, e.g., in API servers handling requests with arbitrary indexes.
+    /// The proof size is checked incrementally during construction, so that building
+    /// the proof is aborted as soon as the limit is exceeded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProofListError::ProofTooLarge` if the proof would contain more than
+    /// `max_proof_nodes` elements and hashes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(0_u32..10);
+    ///
+    /// let proof = index.get_multiproof_limited(vec![1, 3], 10).unwrap();
+    /// let checked_proof = proof.check_against_hash(index.object_hash()).unwrap();
+    /// assert_eq!(*checked_proof.entries(), [(1, 1), (3, 3)]);
+    /// // A proof for all list elements does not fit into the limit.
+    /// assert!(index.get_multiproof_limited(0..10, 5).is_err());
+    /// ```
+    ///
+    /// [`get_multiproof`]: #method.get_multiproof
+    pub fn get_multiproof_limited(
+        &self,
+        indexes: impl IntoIterator<Item = u64>,
+        max_proof_nodes: usize,
+    ) -> Result<ListProof<V, H>, ProofListError> {
+        self.create_multiproof_limited(indexes, max_proof_nodes)
+    }
+
+    /// Returns an iterator over the list values.
+    ///
+    /// The iterator is double-ended, i.e., the list can be iterated in the reverse order
+    /// with `iter().rev()`. Database iterators only move forward, so each element returned
+    /// from the back is retrieved with a separate random read, which is slower than
+    /// the sequential scan used for forward iteration.
+    ///
+    /// The iterator yields owned values, since [`BinaryValue`] deserializes values into owned
+    /// instances. Values are decoded from the bytes borrowed from the database; thus,
+    /// the bytes are copied at most once (e.g., for `Vec<u8>` values), and no intermediate
+    /// buffers are allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let index = fork.get_proof_list::<_, u8>("name");
+    ///
+    /// for val in index.iter() {
+    ///     println!("{}", val);
+    /// }
+    /// ```
+    ///
+    /// Iterating in the reverse order:
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    /// # let db = TemporaryDB::new();
+    /// # let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u8, 2, 3]);
+    /// assert_eq!(index.iter().rev().collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// ```
+    ///
+    /// [`BinaryValue`]: ../../trait.BinaryValue.html
+    pub fn iter(&self) -> ProofListIndexIter<'_, T, V, H> {
+        ProofListIndexIter::new(self, 0, u64::max_value())
+    }
+
+    /// Returns an iterator over the list values. This is an alias of [`iter`],
+    /// which mirrors the naming of map indexes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    /// # let db = TemporaryDB::new();
+    /// # let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u8, 2, 3]);
+    /// assert_eq!(index.values().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    ///
+    /// [`iter`]: #method.iter
+    pub fn values(&self) -> ProofListIndexIter<'_, T, V, H> {
+        self.iter()
+    }
+
+    /// Returns an iterator over the list indices, i.e., `0..len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    /// # let db = TemporaryDB::new();
+    /// # let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u8, 2, 3]);
+    /// for (i, value) in index.indices().zip(index.values()) {
+    ///     assert_eq!(index.get(i), Some(value));
+    /// }
+    /// ```
+    pub fn indices(&self) -> impl Iterator<Item = u64> {
+        0..self.len()
+    }
+
+    /// Returns an iterator over the list values starting from the specified position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let index = fork.get_proof_list::<_, u8>("name");
+    ///
+    /// for val in index.iter_from(1) {
+    ///     println!("{}", val);
+    /// }
+    /// ```
+    pub fn iter_from(&self, from: u64) -> ProofListIndexIter<'_, T, V, H> {
+        ProofListIndexIter::new(self, from, u64::max_value())
+    }
+
+    /// Returns an iterator over the list values starting from the specified position,
+    /// checking that the position does not exceed the list length.
+    ///
+    /// Unlike [`iter_from`], which yields no values if `from` is out of bounds, this method
+    /// allows to distinguish an empty tail of the list (`from == len`) from an invalid
+    /// starting position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `from` is greater than the list length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{
+    ///     access::CopyAccessExt, proof_list::ProofListError, TemporaryDB, Database,
+    /// };
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u8, 2, 3]);
+    ///
+    /// assert!(index.try_iter_from(1).unwrap().eq(vec![2, 3]));
+    /// assert_eq!(index.try_iter_from(3).unwrap().count(), 0);
+    /// let err = index.try_iter_from(4).unwrap_err();
+    /// assert_eq!(err, ProofListError::IndexOutOfBounds { index: 4, len: 3 });
+    /// ```
+    ///
+    /// [`iter_from`]: #method.iter_from
+    pub fn try_iter_from(
+        &self,
+        from: u64,
+    ) -> Result<ProofListIndexIter<'_, T, V, H>, ProofListError> {
+        let len = self.len();
+        if from > len {
+            return Err(ProofListError::IndexOutOfBounds { index: from, len });
+        }
+        Ok(ProofListIndexIter::new(self, from, len))
+    }
+
+    /// Returns an iterator over the list values with indexes in the specified range.
+    ///
+    /// Unlike `iter_from(from).take(len)`, the range end is specified as a `u64` index,
+    /// and both inclusive and exclusive bounds are supported. The range is clamped to
+    /// the list bounds; if the range is empty (e.g., `3..3` or `5..3`), the iterator
+    /// yields no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u8, 2, 3, 4, 5]);
+    ///
+    /// assert_eq!(index.iter_range(1..3).collect::<Vec<_>>(), vec![2, 3]);
+    /// assert_eq!(index.iter_range(1..=3).collect::<Vec<_>>(), vec![2, 3, 4]);
+    /// assert_eq!(index.iter_range(3..100).collect::<Vec<_>>(), vec![4, 5]);
+    /// assert_eq!(index.iter_range(..2).rev().collect::<Vec<_>>(), vec![2, 1]);
+    /// ```
+    pub fn iter_range<R: RangeBounds<u64>>(&self, range: R) -> ProofListIndexIter<'_, T, V, H> {
+        let from = match range.start_bound() {
+            Bound::Included(&from) => from,
+            Bound::Excluded(&from) => from.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let to = match range.end_bound() {
+            Bound::Included(&to) => to.saturating_add(1),
+            Bound::Excluded(&to) => to,
+            Bound::Unbounded => u64::max_value(),
+        };
+        ProofListIndexIter::new(self, from, to)
+    }
+
+    /// Collects all list values into a vector.
+    ///
+    /// # Notes
+    ///
+    /// All list values are loaded into memory, so this method should only be used
+    /// for lists of a moderate size (e.g., in tests). Use [`iter`] to process large lists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u8, 2, 3]);
+    /// assert_eq!(index.to_vec(), vec![1, 2, 3]);
+    /// ```
+    ///
+    /// [`iter`]: #method.iter
+    pub fn to_vec(&self) -> Vec<V> {
+        let mut values = Vec::with_capacity(self.len() as usize);
+        values.extend(self.iter());
+        values
+    }
+
+    /// Returns an iterator over the list values together with their indexes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![10_u8, 20, 30]);
+    ///
+    /// let items: Vec<_> = index.iter_with_indexes().collect();
+    /// assert_eq!(items, vec![(0, 10), (1, 20), (2, 30)]);
+    /// ```
+    pub fn iter_with_indexes(&self) -> Entries<'_, u64, V> {
+        self.index_iter(None)
+    }
+
+    /// Returns an iterator over the list values together with their indexes starting
+    /// from the specified position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![10_u8, 20, 30]);
+    ///
+    /// let items: Vec<_> = index.iter_with_indexes_from(1).collect();
+    /// assert_eq!(items, vec![(1, 20), (2, 30)]);
+    /// ```
+    pub fn iter_with_indexes_from(&self, from: u64) -> Entries<'_, u64, V> {
+        self.index_iter(Some(&from))
+    }
+
+    /// Returns an iterator over the list values whose serialized form satisfies `filter`.
+    ///
+    /// `filter` is applied to the raw bytes of each element as stored in the database
+    /// (i.e., the output of `BinaryValue::to_bytes`), and only the elements passing
+    /// the filter are deserialized. This can significantly speed up scans of large lists
+    /// if most elements are rejected by a cheap check, e.g., of a discriminant byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![vec![1_u8, 2], vec![0, 3], vec![1, 4]]);
+    ///
+    /// let values: Vec<Vec<u8>> = index.iter_filter_raw(|bytes| bytes[0] == 1).collect();
+    /// assert_eq!(values, vec![vec![1, 2], vec![1, 4]]);
+    /// ```
+    pub fn iter_filter_raw<'a, F>(&'a self, filter: F) -> impl Iterator<Item = V> + 'a
+    where
+        F: Fn(&[u8]) -> bool + 'a,
+    {
+        let mut values = self.index_iter(None).skip_keys();
+        iter::from_fn(move || values.next_matching(&filter))
+    }
+
+    /// Returns the index of the first list element satisfying `predicate`, or `None` if there
+    /// is no such element.
+    ///
+    /// # Notes
+    ///
+    /// The list is searched linearly, so this method takes `O(n)` database reads and
+    /// deserializations, where `n` is the list length. It should only be used for lists
+    /// of a moderate size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![10_u8, 20, 30]);
+    ///
+    /// assert_eq!(index.position(|&value| value > 15), Some(1));
+    /// assert_eq!(index.position(|&value| value > 30), None);
+    /// ```
+    pub fn position<F>(&self, mut predicate: F) -> Option<u64>
+    where
+        F: FnMut(&V) -> bool,
+    {
+        self.iter_with_indexes()
+            .find(|(_, value)| predicate(value))
+            .map(|(index, _)| index)
+    }
+}
+
+impl<T, V, H> ProofListIndex<T, V, H>
+where
+    T: RawAccessMut,
+    V: BinaryValue,
+    H: Hasher,
+{
+    fn set_len(&mut self, len: u64) {
+        self.invalidate_root();
+        self.state.set(len)
+    }
+
+    fn invalidate_root(&mut self) {
+        self.root_cache = None;
+    }
+
+    /// Updates levels of the tree with heights `2..` after the values in the range
+    /// `[first_index, last_index]` were updated.
+    ///
+    /// # Invariants
+    ///
+    /// - `self.len()` / `self.height()` is assumed to be correctly set.
+    /// - Value hashes (i.e., tree branches on level 1) are assumed to be updated.
+    fn update_range(&mut self, first_index: u64, last_index: u64) {
+        self.update_range_from_height(1, first_index, last_index);
+    }
+
+    /// Same as `update_range`, but starts updating the tree from the specified height.
+    /// `first_index` and `last_index` are the indexes of updated elements on this height.
+    ///
+    /// # Invariants
+    ///
+    /// - `self.len()` / `self.height()` is assumed to be correctly set.
+    /// - Tree branches on `start_height` are assumed to be updated.
+    fn update_range_from_height(
+        &mut self,
+        start_height: u8,
+        mut first_index: u64,
+        mut last_index: u64,
+    ) {
+        // Index of the last element on the current `height` of the tree.
+        let mut last_index_on_height = (self.len() - 1) >> (start_height - 1);
+        // The last branch written by the loop below is the root of the tree.
+        let mut last_branch_hash = None;
+
+        for height in start_height..self.height() {
+            // Check consistency of the index range.
+            debug_assert!(first_index <= last_index);
+            // Check consistency with the level length.
+            debug_assert!(last_index <= last_index_on_height);
+
+            // Calculate the start and stop indexes to process at the current `height`.
+
+            // The start index is always even, since during hashing we hash together
+            // an element with an even index and the following element (if it exists). Thus, we may
+            // need to decrease `first_index` by 1 to get the actual starting index if
+            // `first_index` is odd. The code below does just that; it is equivalent to
+            //
+            //    let mut index = first_index - (first_index % 2);
+            //
+            // ...just a bit faster.
+            let mut index = first_index & !1;
+
+            // To get the stop index, we may need to increase `last_index` to get an odd value,
+            // but we need to keep in mind the resulting index may be not in the tree.
+            // `last_index | 1` is equivalent to
+            //
+            //    last_index + (1 - last_index % 2)
+            //
+            // ...just a bit faster.
+            let stop_index = cmp::min(last_index | 1, last_index_on_height);
+
+            #[cfg(feature = "rayon")]
+            {
+                if (stop_index + 1 - index) / 2 >= MIN_PARALLEL_PAIRS {
+                    last_branch_hash = self.update_pairs_in_parallel(height, index, stop_index);
+                    // Skip all pairs; the index of the left branch of a pair is always even.
+                    index = (stop_index + 1) & !1;
+                }
+            }
+
+            while index < stop_index {
+                let key = ProofListKey::new(height, index);
+                let branch_hash = H::hash_node(
+                    &self.get_branch_unchecked(key),
+                    &self.get_branch_unchecked(key.as_right()),
+                );
+                self.base.put(&key.parent(), branch_hash);
+                last_branch_hash = Some(branch_hash);
+                index += 2;
+            }
+
+            if stop_index % 2 == 0 {
+                let key = ProofListKey::new(height, stop_index);
+                let branch_hash = H::hash_single_node(&self.get_branch_unchecked(key));
+                self.base.put(&key.parent(), branch_hash);
+                last_branch_hash = Some(branch_hash);
+            }
+
+            first_index /= 2;
+            last_index /= 2;
+            last_index_on_height /= 2;
+        }
+
+        debug_assert_eq!(first_index, 0);
+        debug_assert_eq!(last_index, 0);
+        debug_assert_eq!(last_index_on_height, 0);
+        if let Some(root) = last_branch_hash {
+            self.root_cache = Some(root);
+        }
+    }
+
+    /// Updates parents of sibling pairs on the specified `height`, starting from the pair
+    /// with the left branch at `index` and ending before `stop_index`. Pairs are hashed
+    /// in parallel in chunks of bounded size. Returns the last written branch hash.
+    #[cfg(feature = "rayon")]
+    fn update_pairs_in_parallel(
+        &mut self,
+        height: u8,
+        mut index: u64,
+        stop_index: u64,
+    ) -> Option<Hash> {
+        use rayon::prelude::*;
+
+        let mut last_branch_hash = None;
+        let mut pairs = Vec::with_capacity(PARALLEL_CHUNK_PAIRS);
+        while index < stop_index {
+            let first_parent_index = index / 2;
+            pairs.clear();
+            while index < stop_index && pairs.len() < PARALLEL_CHUNK_PAIRS {
+                let key = ProofListKey::new(height, index);
+                pairs.push((
+                    self.get_branch_unchecked(key),
+                    self.get_branch_unchecked(key.as_right()),
+                ));
+                index += 2;
+            }
+
+            let hashes: Vec<_> = pairs
+                .par_iter()
+                .map(|(left, right)| H::hash_node(left, right))
+                .collect();
+            for (i, branch_hash) in hashes.into_iter().enumerate() {
+                let key = ProofListKey::new(height + 1, first_parent_index + i as u64);
+                self.base.put(&key, branch_hash);
+                last_branch_hash = Some(branch_hash);
+            }
+        }
+        last_branch_hash
+    }
+
+    /// Updates the parent of the tree branch with the specified `key`.
+    ///
+    /// # Invariants
+    ///
+    /// - `last_index_on_height` is the index of the last element on the height of `key`.
+    /// - The branch and its sibling (if any) are assumed to be updated.
+    fn update_parent(&mut self, key: ProofListKey, last_index_on_height: u64) {
+        let parent_hash = if !key.is_left() {
+            let left_sibling = self.get_branch_unchecked(key.as_left());
+            H::hash_node(&left_sibling, &self.get_branch_unchecked(key))
+        } else if key.index() < last_index_on_height {
+            let right_sibling = self.get_branch_unchecked(key.as_right());
+            H::hash_node(&self.get_branch_unchecked(key), &right_sibling)
+        } else {
+            H::hash_single_node(&self.get_branch_unchecked(key))
+        };
+        self.base.put(&key.parent(), parent_hash);
+    }
+
+    /// Removes the extra elements in the tree on heights `1..` and updates elements
+    /// where it is necessary.
+    ///
+    /// # Invariants
+    ///
+    /// - List length is assumed to be updated.
+    fn remove_branches(&mut self, mut old_last_index: u64, old_height: u8) {
+        let new_length = self.len();
+        // New last index of the element on a certain height. The height of the tree
+        // may decrease after removing elements; we encode this case as `last_index == None`.
+        let mut last_index = Some(new_length - 1);
+
+        // Have we started updating hashes in the tree? Up to a certain height, we may just
+        // remove hashes from the tree. However, once the last element on the tree level is even
+        // and has its right neighbor removed, the hashes of the rightmost elements on each
+        // following height must be updated.
+        let mut started_updating_hashes = false;
 
         for height in 1..old_height {
             // Remove excessive branches on the level.
@@ -456,10 +1865,10 @@ where
                     let key = ProofListKey::new(height, last_index);
                     let hash = self.get_branch_unchecked(key);
                     let parent_hash = if key.is_left() {
-                        HashTag::hash_single_node(&hash)
+                        H::hash_single_node(&hash)
                     } else {
                         let left_sibling = self.get_branch_unchecked(key.as_left());
-                        HashTag::hash_node(&left_sibling, &hash)
+                        H::hash_node(&left_sibling, &hash)
                     };
                     self.base.put(&key.parent(), parent_hash);
                 }
@@ -473,25 +1882,376 @@ where
         }
     }
 
-    /// Appends an element to the back of the proof list.
+    /// Appends an element to the back of the proof list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the list after the element is appended would exceed
+    /// the maximum allowed value (see the [type-level docs](#safety)). The list is not
+    /// modified in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    ///
+    /// index.push(1);
+    /// assert!(!index.is_empty());
+    /// ```
+    pub fn push(&mut self, value: V) {
+        let index = self.len();
+        let new_list_len = Self::next_len(index);
+        self.put_leaf(index, value);
+        self.finish_extend(index, new_list_len);
+    }
+
+    /// Appends an element to the back of the proof list using a pre-computed hash of the element.
+    ///
+    /// This method is useful if leaf hashes are computed externally (e.g., in a secure enclave).
+    /// Unlike [`push`], the hash of the `value` is not computed; `leaf_hash` is used instead
+    /// to update the Merkle tree.
+    ///
+    /// # Trust implications
+    ///
+    /// The list trusts the caller that `leaf_hash` is equal to `H::hash_leaf(value.to_bytes())`;
+    /// this is only checked in the test builds of the crate. Proof verifiers always recompute
+    /// leaf hashes from the values, so if `leaf_hash` is incorrect, the list hash will commit
+    /// to a value different from the stored one, and proofs involving the element or
+    /// the branches covering it will fail verification.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{
+    ///     access::CopyAccessExt, proof_list::Hasher, TemporaryDB, Database, HashTag, ObjectHash,
+    ///     BinaryValue,
+    /// };
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// let leaf_hash = <HashTag as Hasher>::hash_leaf(&1_u32.to_bytes());
+    /// index.push_prehashed(1_u32, leaf_hash);
+    ///
+    /// let proof = index.get_proof(0);
+    /// assert!(proof.check_against_hash(index.object_hash()).is_ok());
+    /// ```
+    ///
+    /// [`push`]: #method.push
+    pub fn push_prehashed(&mut self, value: V, leaf_hash: Hash) {
+        #[cfg(test)]
+        assert_eq!(
+            leaf_hash,
+            H::hash_leaf(&value.to_bytes()),
+            "Supplied leaf hash does not match the hash of the value"
+        );
+
+        let index = self.len();
+        let new_list_len = Self::next_len(index);
+        self.base.put(&ProofListKey::new(1, index), leaf_hash);
+        self.base.put(&ProofListKey::leaf(index), value);
+        self.finish_extend(index, new_list_len);
+    }
+
+    /// Reserves space for at least `additional` elements to be appended to the list.
+    ///
+    /// The method is advisory: it does not change the list contents, and appending elements
+    /// without reserving space beforehand is always correct. Currently, neither changes
+    /// in a `Fork` (which are kept in an ordered map until the fork is merged) nor the database
+    /// backends support preallocation, so the method only checks that the list may grow
+    /// by `additional` elements. This allows to detect an overflow before a bulk load
+    /// rather than in the middle of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the list after appending `additional` elements would exceed
+    /// the maximum allowed value (see the [type-level docs](#safety)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    ///
+    /// index.reserve(1_000);
+    /// index.extend(0_u32..1_000);
+    /// assert_eq!(index.len(), 1_000);
+    /// ```
+    pub fn reserve(&mut self, additional: u64) {
+        let new_list_len = self
+            .len()
+            .checked_add(additional)
+            .expect("Proof list length overflow");
+        Self::check_list_len(new_list_len);
+    }
+
+    /// Extends the proof list with the contents of an iterator.
+    ///
+    /// All new elements are written first, after which each affected branch of the Merkle tree
+    /// is recomputed exactly once. Thus, appending `k` elements to a list of length `n` takes
+    /// `O(k + log n)` hash operations, which is more efficient than calling [`push`]
+    /// for each element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    ///
+    /// index.extend([1, 2, 3].iter().cloned());
+    /// assert_eq!(3, index.len());
+    /// ```
+    ///
+    /// [`push`]: #method.push
+    pub fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let old_list_len = self.len();
+        let mut new_list_len = old_list_len;
+
+        for value in iter {
+            // The length is checked before writing the element, since an element with
+            // an out-of-bounds index would be written under a key of a tree branch.
+            let next_len = Self::next_len(new_list_len);
+            self.put_leaf(new_list_len, value);
+            new_list_len = next_len;
+        }
+        self.finish_extend(old_list_len, new_list_len);
+    }
+
+    /// Extends the proof list with the contents of an iterator and returns the new list length
+    /// together with the resulting [`list_hash`].
+    ///
+    /// This method works like [`extend`]. The Merkle root is computed during the bottom-up
+    /// rebuild of the tree and is cached, so the returned values do not require additional
+    /// reads from the database.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.push(1_u32);
+    ///
+    /// let (len, list_hash) = index.extend_reporting(vec![2, 3, 4]);
+    /// assert_eq!(len, 4);
+    /// assert_eq!(list_hash, index.object_hash());
+    /// ```
+    ///
+    /// [`list_hash`]: #method.list_hash
+    /// [`extend`]: #method.extend
+    pub fn extend_reporting<I>(&mut self, iter: I) -> (u64, Hash)
+    where
+        I: IntoIterator<Item = V>,
+    {
+        self.extend(iter);
+        self.head()
+    }
+
+    /// Extends the proof list with the clones of elements in a slice.
+    ///
+    /// Unlike [`extend`], the final length of the list is known in advance, so the length
+    /// constraint is checked before any elements are written. As with [`extend`], each affected
+    /// branch of the Merkle tree is recomputed exactly once after all elements are written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the list after the extension would exceed the maximum
+    /// allowed value (see the [type-level docs](#safety)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    ///
+    /// index.extend_from_slice(&[1_u32, 2, 3]);
+    /// assert_eq!(3, index.len());
+    /// index.extend_from_slice(&[4, 5]);
+    /// assert!(index.iter().eq(1..=5));
+    /// ```
+    ///
+    /// [`extend`]: #method.extend
+    pub fn extend_from_slice(&mut self, values: &[V])
+    where
+        V: Clone,
+    {
+        let old_list_len = self.len();
+        let new_list_len = old_list_len.saturating_add(values.len() as u64);
+        Self::check_list_len(new_list_len);
+
+        for (index, value) in (old_list_len..).zip(values) {
+            self.put_leaf(index, value.clone());
+        }
+        self.finish_extend(old_list_len, new_list_len);
+    }
+
+    /// Appends all elements of `other` list to this list, preserving their order.
+    ///
+    /// The elements are appended with [`extend`], so that each affected branch of the Merkle tree
+    /// is recomputed only once. The hash of the resulting list is the same as if the list
+    /// were built from the elements of this list followed by the elements of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// fork.get_proof_list("other").extend(vec![3_u32, 4]);
+    /// db.merge(fork.into_patch()).unwrap();
+    ///
+    /// let snapshot = db.snapshot();
+    /// let other = snapshot.get_proof_list("other");
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2]);
+    /// index.append_list(&other);
+    /// assert!(index.iter().eq(1..=4));
+    /// ```
+    ///
+    /// [`extend`]: #method.extend
+    pub fn append_list<U: RawAccess>(&mut self, other: &ProofListIndex<U, V, H>) {
+        self.extend(other.iter());
+    }
+
+    /// Writes a value and its hash to the list. The tree branches on heights `2..` are
+    /// not updated.
+    fn put_leaf(&mut self, index: u64, value: V) {
+        self.base.put(
+            &ProofListKey::new(1, index),
+            H::hash_leaf(&value.to_bytes()),
+        );
+        self.base.put(&ProofListKey::leaf(index), value);
+    }
+
+    /// Updates the list length and the Merkle tree after values with indexes
+    /// `old_list_len..new_list_len` were written with `put_leaf`.
+    fn finish_extend(&mut self, old_list_len: u64, new_list_len: u64) {
+        if new_list_len == old_list_len {
+            // No elements were added; we're done.
+            return;
+        }
+        Self::check_list_len(new_list_len);
+        self.set_len(new_list_len);
+        self.update_range(old_list_len, new_list_len - 1);
+    }
+
+    /// Returns the length of the list with `len` elements after appending an element to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new length overflows `u64` or exceeds the maximum allowed value.
+    fn next_len(len: u64) -> u64 {
+        let next_len = len.checked_add(1).expect("Proof list length overflow");
+        Self::check_list_len(next_len);
+        next_len
+    }
+
+    fn check_list_len(len: u64) {
+        assert!(
+            len < MAX_INDEX + 1,
+            "Length of a `ProofListIndex` exceeding the maximum allowed value ({}). \
+             This should never happen in realistic scenarios. If you feel this is not a bug, \
+             open an issue on https://github.com/exonum/exonum and tell us your use case \
+             for such a large list.",
+            MAX_INDEX + 1
+        );
+    }
+
+    /// Changes a value at the specified position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is equal or greater than the current state of the proof list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    ///
+    /// index.push(1);
+    /// assert_eq!(Some(1), index.get(0));
+    ///
+    /// index.set(0, 100);
+    /// assert_eq!(Some(100), index.get(0));
+    /// ```
+    pub fn set(&mut self, index: u64, value: V) {
+        if let Err(err) = self.try_set(index, value) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Changes a value at the specified position. Unlike [`set`], this method returns
+    /// an error instead of panicking if the `index` is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `index` is equal or greater than the current state of the proof list.
     ///
     /// # Examples
     ///
     /// ```
-    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    /// use exonum_merkledb::{
+    ///     access::CopyAccessExt, proof_list::ProofListError, TemporaryDB, Database,
+    /// };
     ///
     /// let db = TemporaryDB::new();
     /// let fork = db.fork();
     /// let mut index = fork.get_proof_list("name");
     ///
     /// index.push(1);
-    /// assert!(!index.is_empty());
+    /// index.try_set(0, 100).unwrap();
+    /// assert_eq!(Some(100), index.get(0));
+    ///
+    /// let err = index.try_set(1, 200).unwrap_err();
+    /// assert_eq!(err, ProofListError::IndexOutOfBounds { index: 1, len: 1 });
     /// ```
-    pub fn push(&mut self, value: V) {
-        self.extend(iter::once(value));
+    ///
+    /// [`set`]: #method.set
+    pub fn try_set(&mut self, index: u64, value: V) -> Result<(), ProofListError> {
+        let len = self.len();
+        if index >= len {
+            return Err(ProofListError::IndexOutOfBounds { index, len });
+        }
+        self.put_leaf(index, value);
+        self.invalidate_root();
+        self.update_range(index, index);
+        Ok(())
     }
 
-    /// Extends the proof list with the contents of an iterator.
+    /// Changes values at consecutive positions starting from `start`.
+    ///
+    /// Unlike calling [`set`] for each value, the branches of the Merkle tree affected
+    /// by the update are recomputed only once, after all values are written.
+    /// If `values` is empty, this method has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the updated positions is equal or greater than the current state
+    /// of the proof list. In this case, the list is not modified.
     ///
     /// # Examples
     ///
@@ -502,52 +2262,112 @@ where
     /// let fork = db.fork();
     /// let mut index = fork.get_proof_list("name");
     ///
-    /// index.extend([1, 2, 3].iter().cloned());
-    /// assert_eq!(3, index.len());
+    /// index.extend(vec![1, 2, 3, 4, 5]);
+    /// index.set_many(1, vec![20, 30, 40]);
+    /// assert!(index.iter().eq(vec![1, 20, 30, 40, 5]));
     /// ```
-    pub fn extend<I>(&mut self, iter: I)
+    ///
+    /// [`set`]: #method.set
+    pub fn set_many<I>(&mut self, start: u64, values: I)
     where
         I: IntoIterator<Item = V>,
     {
-        let old_list_len = self.len();
-        let mut new_list_len = old_list_len;
+        // Values are collected beforehand, so that the list is not modified if some
+        // of them are out of bounds.
+        let values: Vec<_> = values.into_iter().collect();
+        if values.is_empty() {
+            return;
+        }
 
-        for value in iter {
-            self.base.put(
-                &ProofListKey::new(1, new_list_len),
-                HashTag::hash_leaf(&value.to_bytes()),
+        let len = self.len();
+        let last_index = start.saturating_add(values.len() as u64 - 1);
+        if last_index >= len {
+            panic!(
+                "{}",
+                ProofListError::IndexOutOfBounds {
+                    index: last_index,
+                    len
+                }
             );
-            self.base.put(&ProofListKey::leaf(new_list_len), value);
-            new_list_len += 1;
         }
 
-        if new_list_len == old_list_len {
-            // No elements in the iterator; we're done.
-            return;
+        for (index, value) in (start..).zip(values) {
+            self.put_leaf(index, value);
         }
+        self.invalidate_root();
+        self.update_range(start, last_index);
+    }
 
-        // For efficiency, we check the constraint once rather than in a loop above.
-        // If the list length exceeds the allowed bounds, `ProofListKey::leaf` in the loop
-        // will panic in the debug mode, but we don't expect users to run MerkleDB in the debug mode
-        // in all cases.
-        assert!(
-            new_list_len < MAX_INDEX + 1,
-            "Length of a `ProofListIndex` exceeding the maximum allowed value ({}). \
-             This should never happen in realistic scenarios. If you feel this is not a bug, \
-             open an issue on https://github.com/exonum/exonum and tell us your use case \
-             for such a large list.",
-            MAX_INDEX + 1
-        );
+    /// Swaps two elements in the list.
+    ///
+    /// Only the branches of the Merkle tree on the paths from the swapped elements
+    /// to the tree root are recomputed. If `first == second`, this method has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `first` or `second` is equal or greater than the current state of the proof list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    ///
+    /// index.extend(vec![1, 2, 3, 4]);
+    /// index.swap(0, 2);
+    /// assert!(index.iter().eq(vec![3, 2, 1, 4]));
+    /// ```
+    pub fn swap(&mut self, first: u64, second: u64) {
+        let len = self.len();
+        for &index in &[first, second] {
+            if index >= len {
+                panic!("{}", ProofListError::IndexOutOfBounds { index, len });
+            }
+        }
+        if first == second {
+            return;
+        }
 
-        self.set_len(new_list_len);
-        self.update_range(old_list_len, new_list_len - 1);
+        self.invalidate_root();
+        let (mut left, mut right) = (cmp::min(first, second), cmp::max(first, second));
+        let left_value: V = self.base.get(&ProofListKey::leaf(left)).unwrap();
+        let right_value: V = self.base.get(&ProofListKey::leaf(right)).unwrap();
+        self.base.put(&ProofListKey::leaf(left), right_value);
+        self.base.put(&ProofListKey::leaf(right), left_value);
+
+        // Value hashes do not need to be recomputed; it's enough to swap them.
+        let (left_key, right_key) = (ProofListKey::new(1, left), ProofListKey::new(1, right));
+        let left_hash = self.get_branch_unchecked(left_key);
+        let right_hash = self.get_branch_unchecked(right_key);
+        self.base.put(&left_key, right_hash);
+        self.base.put(&right_key, left_hash);
+
+        // Update both paths to the root separately until they converge, and then
+        // update the common part of the paths.
+        let mut height = 1;
+        let mut last_index_on_height = len - 1;
+        while left / 2 != right / 2 {
+            self.update_parent(ProofListKey::new(height, left), last_index_on_height);
+            self.update_parent(ProofListKey::new(height, right), last_index_on_height);
+            left /= 2;
+            right /= 2;
+            last_index_on_height /= 2;
+            height += 1;
+        }
+        self.update_range_from_height(height, left, right);
     }
 
-    /// Changes a value at the specified position.
+    /// Resizes the list so that its length is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than the current length, the list is extended with clones
+    /// of `value`. If `new_len` is less than the current length, the list is [truncated].
     ///
     /// # Panics
     ///
-    /// Panics if `index` is equal or greater than the current state of the proof list.
+    /// Panics if `new_len` exceeds the maximum allowed list length.
     ///
     /// # Examples
     ///
@@ -557,27 +2377,93 @@ where
     /// let db = TemporaryDB::new();
     /// let fork = db.fork();
     /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2]);
     ///
-    /// index.push(1);
-    /// assert_eq!(Some(1), index.get(0));
+    /// index.resize(4, 0);
+    /// assert!(index.iter().eq(vec![1, 2, 0, 0]));
+    /// index.resize(1, 0);
+    /// assert!(index.iter().eq(vec![1]));
+    /// ```
+    ///
+    /// [truncated]: #method.truncate
+    pub fn resize(&mut self, new_len: u64, value: V)
+    where
+        V: Clone,
+    {
+        let len = self.len();
+        if new_len <= len {
+            self.truncate(new_len);
+        } else {
+            Self::check_list_len(new_len);
+            self.extend((len..new_len).map(|_| value.clone()));
+        }
+    }
+
+    /// Replaces the contents of the list with `len` clones of `value`.
+    ///
+    /// Since all list elements are equal, the hashes of complete subtrees of the same height
+    /// are equal as well. Thus, only `O(log len)` hash operations are performed, although
+    /// all `O(len)` tree branches are still written to the database.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` exceeds the maximum allowed list length.
+    ///
+    /// # Examples
     ///
-    /// index.set(0, 100);
-    /// assert_eq!(Some(100), index.get(0));
     /// ```
-    pub fn set(&mut self, index: u64, value: V) {
-        if index >= self.len() {
-            panic!(
-                "Index out of bounds: the len is {} but the index is {}",
-                self.len(),
-                index
-            );
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2]);
+    ///
+    /// index.fill(3, 5);
+    /// assert!(index.iter().eq(vec![5, 5, 5]));
+    /// ```
+    pub fn fill(&mut self, len: u64, value: V)
+    where
+        V: Clone,
+    {
+        self.clear();
+        if len == 0 {
+            return;
+        }
+        Self::check_list_len(len);
+
+        let leaf_hash = H::hash_leaf(&value.to_bytes());
+        for index in 0..len {
+            self.base.put(&ProofListKey::new(1, index), leaf_hash);
+            self.base.put(&ProofListKey::leaf(index), value.clone());
+        }
+        self.set_len(len);
+
+        // Hash of a complete subtree on the current `height`.
+        let mut complete_hash = leaf_hash;
+        // Hash of the last node on the current `height`, which may be an incomplete subtree.
+        let mut last_hash = leaf_hash;
+        let mut last_index = len - 1;
+
+        for height in 1..self.height() {
+            let last_parent_index = last_index / 2;
+            let parent_hash = H::hash_node(&complete_hash, &complete_hash);
+            for index in 0..last_parent_index {
+                self.base
+                    .put(&ProofListKey::new(height + 1, index), parent_hash);
+            }
+
+            last_hash = if last_index % 2 == 1 {
+                H::hash_node(&complete_hash, &last_hash)
+            } else {
+                H::hash_single_node(&last_hash)
+            };
+            let last_parent_key = ProofListKey::new(height + 1, last_parent_index);
+            self.base.put(&last_parent_key, last_hash);
+
+            complete_hash = parent_hash;
+            last_index = last_parent_index;
         }
-        self.base.put(
-            &ProofListKey::new(1, index),
-            HashTag::hash_leaf(&value.to_bytes()),
-        );
-        self.base.put(&ProofListKey::leaf(index), value);
-        self.update_range(index, index);
     }
 
     /// Shortens the list, keeping the indicated number of first `len` elements
@@ -617,7 +2503,145 @@ where
             self.base.remove(&ProofListKey::leaf(index));
         }
 
-        self.remove_range(old_last_index, old_height);
+        self.remove_branches(old_last_index, old_height);
+    }
+
+    /// Checks that the list stores elements with the specified fingerprint of their type.
+    /// If the list has no fingerprint yet, `fingerprint` is stored in the index metadata.
+    ///
+    /// The fingerprint is an arbitrary number chosen by the user of the list (e.g., a version
+    /// of the serialization format of elements, or a hash of the element schema). It should
+    /// be changed whenever the binary layout of elements changes. Calling this method
+    /// right after opening the list (e.g., in the constructor of the schema the list belongs to)
+    /// ensures that a list created for elements with a different layout fails loudly instead
+    /// of deserializing garbage.
+    ///
+    /// The fingerprint is retained by [`clear`]ing the list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the list has a different fingerprint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{
+    ///     access::CopyAccessExt, proof_list::ProofListError, TemporaryDB, Database,
+    /// };
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// {
+    ///     let mut index = fork.get_proof_list::<_, u64>("name");
+    ///     assert_eq!(index.element_fingerprint(), None);
+    ///     index.ensure_element_fingerprint(1).unwrap();
+    ///     assert_eq!(index.element_fingerprint(), Some(1));
+    /// }
+    ///
+    /// // Later, the layout of elements is changed.
+    /// let mut index = fork.get_proof_list::<_, String>("name");
+    /// let err = index.ensure_element_fingerprint(2).unwrap_err();
+    /// assert_eq!(err, ProofListError::FingerprintMismatch { expected: 2, actual: 1 });
+    /// ```
+    ///
+    /// [`clear`]: #method.clear
+    pub fn ensure_element_fingerprint(&mut self, fingerprint: u64) -> Result<(), ProofListError> {
+        match self.state.fingerprint() {
+            None => {
+                self.state.set_fingerprint(fingerprint);
+                Ok(())
+            }
+            Some(actual) if actual == fingerprint => Ok(()),
+            Some(actual) => Err(ProofListError::FingerprintMismatch {
+                expected: fingerprint,
+                actual,
+            }),
+        }
+    }
+
+    /// Removes elements with indexes in `range` from the list, shifting the following elements
+    /// to the left.
+    ///
+    /// Since the positions of elements following the removed range change, this method
+    /// is effectively a compaction of the list: previously issued proofs for these elements
+    /// become invalid. The list is rebuilt starting from `range.start` with [`truncate`] and
+    /// [`extend`], so that each affected branch of the Merkle tree is recomputed only once.
+    /// The hash of the resulting list is the same as for a list built from the remaining elements.
+    ///
+    /// If `range.end` exceeds the list length, it is clamped to the length. If the range
+    /// is empty (i.e., `range.start >= range.end`), the list is not modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(1_u32..=6);
+    /// index.remove_range(1..3);
+    /// assert!(index.iter().eq(vec![1, 4, 5, 6]));
+    /// index.remove_range(2..100);
+    /// assert!(index.iter().eq(vec![1, 4]));
+    /// ```
+    ///
+    /// [`truncate`]: #method.truncate
+    /// [`extend`]: #method.extend
+    pub fn remove_range(&mut self, range: Range<u64>) {
+        let end = cmp::min(range.end, self.len());
+        if range.start >= end {
+            return;
+        }
+
+        let retained_values: Vec<_> = self.iter_from(end).collect();
+        self.truncate(range.start);
+        self.extend(retained_values);
+    }
+
+    /// Retains only the elements specified by the predicate, preserving their relative order.
+    ///
+    /// Removed elements are compacted, so that the positions of retained elements following
+    /// the first removed element change. The list is rebuilt starting from the first removed
+    /// element with [`truncate`] and [`extend`], so that each affected branch of the Merkle tree
+    /// is recomputed only once. The hash of the resulting list is the same as for a list
+    /// built from the retained elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(1_u32..=6);
+    /// index.retain(|&x| x % 2 == 0);
+    /// assert!(index.iter().eq(vec![2, 4, 6]));
+    /// ```
+    ///
+    /// [`truncate`]: #method.truncate
+    /// [`extend`]: #method.extend
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&V) -> bool,
+    {
+        let mut first_removed_index = None;
+        let mut retained_values = vec![];
+        for (index, value) in self.iter().enumerate() {
+            if first_removed_index.is_none() {
+                if !predicate(&value) {
+                    first_removed_index = Some(index as u64);
+                }
+            } else if predicate(&value) {
+                retained_values.push(value);
+            }
+        }
+
+        if let Some(index) = first_removed_index {
+            self.truncate(index);
+            self.extend(retained_values);
+        }
     }
 
     /// Removes the last element from the list and returns it, or returns `None`
@@ -645,13 +2669,14 @@ where
         }
     }
 
-    /// Clears the proof list, removing all values.
+    /// Clears the proof list, removing all values and Merkle tree branches.
     ///
     /// # Notes
     ///
-    /// Currently, this method is not optimized to delete a large set of data. During the execution of
-    /// this method, the amount of allocated memory is linearly dependent on the number of elements
-    /// in the index.
+    /// The list is cleared without enumerating its elements: the fork only records the fact
+    /// that the index was cleared, and the backend removes the data with a single range
+    /// deletion once the fork is merged. Thus, memory consumption of this method does not depend
+    /// on the list length.
     ///
     /// # Examples
     ///
@@ -668,17 +2693,217 @@ where
     /// ```
     pub fn clear(&mut self) {
         self.base.clear();
+        self.invalidate_root();
         self.state.unset();
     }
+
+    /// Replaces the contents of the list with `values`.
+    ///
+    /// The list is [`clear`]ed, after which `values` are appended with [`extend`]. Thus,
+    /// the Merkle tree is built with a single bottom-up pass, and the list length is updated
+    /// only after all values are written. Since the list is borrowed mutably for the duration
+    /// of the call, no reader can observe the list in an intermediate state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2, 3, 4]);
+    /// index.reset_to(vec![5, 6]);
+    /// assert_eq!(index.to_vec(), vec![5, 6]);
+    /// ```
+    ///
+    /// [`clear`]: #method.clear
+    /// [`extend`]: #method.extend
+    pub fn reset_to<I>(&mut self, values: I)
+    where
+        I: IntoIterator<Item = V>,
+    {
+        self.clear();
+        self.extend(values);
+    }
+}
+
+impl<T, V> ProofListIndex<T, V>
+where
+    T: RawAccessMut,
+    V: BinaryValue,
+{
+    /// Creates a list at the specified address, extends it with the contents of an iterator
+    /// and returns the resulting list hash.
+    ///
+    /// This is a shortcut for the case when the list is built once (e.g., from the genesis
+    /// or seed data) and only its hash is of interest. The elements are appended with
+    /// [`extend`], so the Merkle tree is built with a single bottom-up pass. If the list
+    /// already contains elements, the new elements are appended to them.
+    ///
+    /// For an empty iterator and an empty list, the returned hash is equal to
+    /// [`HashTag::empty_list_hash()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index cannot be accessed at `addr` (e.g., if an index of another type
+    /// is stored there), or if the resulting list length would exceed the maximum allowed value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{
+    ///     access::CopyAccessExt, TemporaryDB, Database, HashTag, ObjectHash, ProofListIndex,
+    /// };
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let list_hash = ProofListIndex::build_from(&fork, "name", vec![1_u32, 2, 3]);
+    /// assert_eq!(list_hash, fork.get_proof_list::<_, u32>("name").object_hash());
+    ///
+    /// let empty_hash = ProofListIndex::<_, u32>::build_from(&fork, "empty", vec![]);
+    /// assert_eq!(empty_hash, HashTag::empty_list_hash());
+    /// ```
+    ///
+    /// [`extend`]: #method.extend
+    /// [`HashTag::empty_list_hash()`]: ../../enum.HashTag.html#method.empty_list_hash
+    pub fn build_from<A, I>(access: A, addr: impl Into<IndexAddress>, iter: I) -> Hash
+    where
+        A: Access<Base = T>,
+        I: IntoIterator<Item = V>,
+    {
+        let mut list = Self::from_access(access, addr.into())
+            .unwrap_or_else(|e| panic!("MerkleDB error: {}", e));
+        list.extend(iter);
+        list.object_hash()
+    }
+}
+
+impl<'a, V> ProofListIndex<&'a Fork, V>
+where
+    V: BinaryValue,
+{
+    /// Creates a list with the specified address in the fork and fills it with `values`.
+    ///
+    /// **Warning.** Any existing content of the list is overwritten. Values are loaded
+    /// with [`reset_to`], so that each branch of the Merkle tree is computed only once.
+    ///
+    /// # Panics
+    ///
+    /// If the index exists, but is not a Merkelized list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// fork.get_proof_list("name").extend(vec![5_u32, 6, 7, 8]);
+    ///
+    /// let index: ProofListIndex<_, u32> = ProofListIndex::from_values("name", &fork, 0..3);
+    /// assert_eq!(index.to_vec(), vec![0, 1, 2]);
+    /// ```
+    ///
+    /// [`reset_to`]: #method.reset_to
+    pub fn from_values<S, I>(name: S, fork: &'a Fork, values: I) -> Self
+    where
+        S: Into<IndexAddress>,
+        I: IntoIterator<Item = V>,
+    {
+        let mut index = Self::from_access(fork, name.into())
+            .unwrap_or_else(|e| panic!("MerkleDB error: {}", e));
+        index.reset_to(values);
+        index
+    }
+}
+
+/// An error that can occur during operations with a `ProofListIndex`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum ProofListError {
+    /// The index of an element is equal or greater than the length of the list.
+    #[error("Index out of bounds: the len is {len} but the index is {index}")]
+    IndexOutOfBounds {
+        /// Requested index of the element.
+        index: u64,
+        /// Length of the list.
+        len: u64,
+    },
+
+    /// The start of a range is greater than its end.
+    #[error("Illegal range boundaries: the range start is {start}, but the range end is {end}")]
+    IllegalRange {
+        /// Inclusive start of the range.
+        start: u64,
+        /// Exclusive end of the range.
+        end: u64,
+    },
+
+    /// The list element is equal to the value it was required to differ from.
+    #[error("List element with index {index} is equal to the specified value")]
+    EqualValue {
+        /// Index of the element.
+        index: u64,
+    },
+
+    /// The fingerprint of the element type stored in the index metadata differs from
+    /// the expected one.
+    #[error(
+        "Element fingerprint mismatch: expected {expected}, but the list has fingerprint {actual}"
+    )]
+    FingerprintMismatch {
+        /// Expected fingerprint.
+        expected: u64,
+        /// Fingerprint stored in the index metadata.
+        actual: u64,
+    },
+
+    /// The proof would contain more elements and hashes than allowed.
+    #[error("Proof size exceeds the limit of {limit} nodes")]
+    ProofTooLarge {
+        /// Maximum allowed number of elements and hashes in the proof.
+        limit: usize,
+    },
+}
+
+/// Inconsistency in the internal structure of a `ProofListIndex` detected by
+/// the [`verify_integrity`] method.
+///
+/// [`verify_integrity`]: struct.ProofListIndex.html#method.verify_integrity
+#[cfg(any(test, feature = "integrity-check"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum IntegrityError {
+    /// A list element or a tree node is missing from the index.
+    #[error("Entry with key {key:?} is missing")]
+    MissingEntry {
+        /// Key of the missing entry.
+        key: ProofListKey,
+    },
+
+    /// The stored hash of a tree node differs from the hash recomputed from its children
+    /// (or from the list element, if the node is a leaf).
+    #[error("Stored hash of the tree node with key {key:?} differs from the recomputed one")]
+    HashMismatch {
+        /// Key of the mismatching node.
+        key: ProofListKey,
+    },
+
+    /// The cached Merkle root or the list hash does not correspond to the root of the tree.
+    #[error("List hash does not correspond to the root of the Merkle tree")]
+    RootMismatch,
 }
 
 /// `object_hash` for a list depends on all list items. It explicitly commits to the list length
 /// in order to be able to more easily prove absence of elements and to prevent second pre-image
 /// attacks.
 ///
+/// The object hash of a proof list is its [list hash](#method.list_hash).
+///
 /// # Specification
 ///
-/// The `object_hash` is calculated as follows:
+/// With the default [`Hasher`], the `object_hash` is calculated as follows:
 ///
 /// ```text
 /// h = sha256( HashTag::ListNode || u64_LE(len) || root_hash )
@@ -738,33 +2963,52 @@ where
 /// let hash = index.object_hash();
 /// assert_ne!(hash, default_hash);
 /// ```
-impl<T, V> ObjectHash for ProofListIndex<T, V>
+///
+/// [`Hasher`]: trait.Hasher.html
+impl<T, V, H> ObjectHash for ProofListIndex<T, V, H>
 where
     T: RawAccess,
     V: BinaryValue,
+    H: Hasher,
 {
     fn object_hash(&self) -> Hash {
-        HashTag::hash_list_node(self.len(), self.merkle_root())
+        self.list_hash()
+    }
+}
+
+impl<T, V, H> IntoIterator for ProofListIndex<T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+    type Item = V;
+    type IntoIter = ProofListIndexIntoIter<T, V, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ProofListIndexIntoIter::new(self)
     }
 }
 
-impl<'a, T, V> IntoIterator for &'a ProofListIndex<T, V>
+impl<'a, T, V, H> IntoIterator for &'a ProofListIndex<T, V, H>
 where
     T: RawAccess,
     V: BinaryValue,
+    H: Hasher,
 {
     type Item = V;
-    type IntoIter = Values<'a, V>;
+    type IntoIter = ProofListIndexIter<'a, T, V, H>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
     }
 }
 
-impl<T, V> IndexIterator for ProofListIndex<T, V>
+impl<T, V, H> IndexIterator for ProofListIndex<T, V, H>
 where
     T: RawAccess,
     V: BinaryValue,
+    H: Hasher,
 {
     type Key = u64;
     type Value = V;
@@ -787,7 +3031,7 @@ mod proto {
 
     use super::{
         key::{HEIGHT_SHIFT, MAX_INDEX},
-        HashedEntry, ListProof, ProofListKey,
+        HashedEntry, Hasher, ListProof, ProofListKey,
     };
     use crate::{
         proto::{self, ListProofEntry},
@@ -830,9 +3074,10 @@ mod proto {
         assert!(err.to_string().contains("index is out of range"));
     }
 
-    impl<V> ProtobufConvert for ListProof<V>
+    impl<V, H> ProtobufConvert for ListProof<V, H>
     where
         V: BinaryValue,
+        H: Hasher,
     {
         type ProtoStruct = proto::ListProof;
 
@@ -18,11 +18,18 @@ use exonum_crypto::Hash;
 use serde_derive::{Deserialize, Serialize};
 use thiserror::Error;
 
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    fmt,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
 
 use super::{
+    height_of_len,
     key::{ProofListKey, MAX_INDEX},
-    tree_height_by_length,
+    proof_builder::range_proof_keys,
+    Hasher,
 };
 use crate::{BinaryValue, HashTag};
 
@@ -119,6 +126,12 @@ impl HashedEntry {
 /// );
 /// ```
 ///
+/// ## Hash functions
+///
+/// The `H` type parameter specifies the [`Hasher`] used to restore the list hash from the proof.
+/// It must be the same as the hasher of the `ProofListIndex` the proof was created for;
+/// the hasher is not included into the serialized proof.
+///
 /// ## Note on external implementations
 ///
 /// External implementations (e.g., in light clients) must treat serialized `ListProof`s
@@ -129,11 +142,47 @@ impl HashedEntry {
 /// [`get_proof()`]: struct.ProofListIndex.html#method.get_proof
 /// [`get_range_proof()`]: struct.ProofListIndex.html#method.get_range_proof
 /// [`check()`]: #method.check
+/// [`Hasher`]: trait.Hasher.html
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
-pub struct ListProof<V> {
+pub struct ListProof<V, H = HashTag> {
     proof: Vec<HashedEntry>,
     entries: Vec<(u64, V)>,
     length: u64,
+    #[serde(skip)]
+    _hasher: PhantomData<H>,
+}
+
+// Implemented manually to avoid the `H: Clone` bound.
+impl<V: Clone, H> Clone for ListProof<V, H> {
+    fn clone(&self) -> Self {
+        Self {
+            proof: self.proof.clone(),
+            entries: self.entries.clone(),
+            length: self.length,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+/// Proof of existence for elements of a `ProofListIndex` belonging to a [`Group`],
+/// tagged with the identifier of the list within the group.
+///
+/// The identifier allows a verifier handling proofs for many group members (e.g., lists
+/// of transactions for different wallets) to route each proof to the trusted list hash it
+/// should be checked against. The identifier itself is not authenticated by the proof;
+/// it is the responsibility of the verifier to check the proof against the hash
+/// of the list with the same identifier.
+///
+/// Proofs of this type are created by the [`get_proof_in_family`] method of `Group`.
+///
+/// [`Group`]: ../struct.Group.html
+/// [`get_proof_in_family`]: ../struct.Group.html#method.get_proof_in_family
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FamilyListProof<V, H = HashTag> {
+    /// Identifier of the list within the group, i.e., the serialized group key.
+    pub id: Vec<u8>,
+    /// Proof for the list elements.
+    pub proof: ListProof<V, H>,
 }
 
 /// Merges two iterators with `HashedEntry`s so that the elements in the resulting iterator
@@ -145,12 +194,12 @@ pub struct ListProof<V> {
 ///
 /// # Return value
 ///
-/// Iterator will yield an error if there is an equal `HashedEntry.key` present in both
-/// input iterators.
+/// Iterator will yield an error with the duplicate key if there is an equal `HashedEntry.key`
+/// present in both input iterators.
 fn merge(
     first: impl Iterator<Item = HashedEntry>,
     second: impl Iterator<Item = HashedEntry>,
-) -> impl Iterator<Item = Result<HashedEntry, ()>> {
+) -> impl Iterator<Item = Result<HashedEntry, ProofListKey>> {
     struct Merge<T, U> {
         first: T,
         second: U,
@@ -179,7 +228,7 @@ fn merge(
         T: Iterator<Item = HashedEntry>,
         U: Iterator<Item = HashedEntry>,
     {
-        type Item = Result<HashedEntry, ()>;
+        type Item = Result<HashedEntry, ProofListKey>;
 
         fn next(&mut self) -> Option<Self::Item> {
             match (self.first_item, self.second_item) {
@@ -192,7 +241,7 @@ fn merge(
                         self.second_item = self.second.next();
                         Some(Ok(y))
                     }
-                    Ordering::Equal => Some(Err(())),
+                    Ordering::Equal => Some(Err(x.key)),
                 },
 
                 (Some(x), None) => {
@@ -228,7 +277,10 @@ fn merge(
 /// # Examples
 ///
 /// See unit tests at the end of this file.
-fn hash_layer(layer: &mut Vec<HashedEntry>, last_index: u64) -> Result<(), ListProofError> {
+fn hash_layer<H: Hasher>(
+    layer: &mut Vec<HashedEntry>,
+    last_index: u64,
+) -> Result<(), ListProofError> {
     let new_len = (layer.len() + 1) / 2;
     for i in 0..new_len {
         let x = &layer[2 * i];
@@ -236,16 +288,20 @@ fn hash_layer(layer: &mut Vec<HashedEntry>, last_index: u64) -> Result<(), ListP
             // To be able to zip two hashes on the layer, they need to be adjacent to each other,
             // and the first of them needs to have an even index.
             if !x.key.is_left() || y.key.index() != x.key.index() + 1 {
-                return Err(ListProofError::MissingHash);
+                return Err(ListProofError::MissingHash {
+                    height: x.key.height(),
+                });
             }
-            HashedEntry::new(x.key.parent(), HashTag::hash_node(&x.hash, &y.hash))
+            HashedEntry::new(x.key.parent(), H::hash_node(&x.hash, &y.hash))
         } else {
             // If there is an odd number of hashes on the layer, the solitary hash must have
             // the greatest possible index.
             if last_index % 2 == 1 || x.key.index() != last_index {
-                return Err(ListProofError::MissingHash);
+                return Err(ListProofError::MissingHash {
+                    height: x.key.height(),
+                });
             }
-            HashedEntry::new(x.key.parent(), HashTag::hash_single_node(&x.hash))
+            HashedEntry::new(x.key.parent(), H::hash_single_node(&x.hash))
         };
     }
 
@@ -253,7 +309,7 @@ fn hash_layer(layer: &mut Vec<HashedEntry>, last_index: u64) -> Result<(), ListP
     Ok(())
 }
 
-impl<V: BinaryValue> ListProof<V> {
+impl<V: BinaryValue, H: Hasher> ListProof<V, H> {
     pub(super) fn new<I>(values: I, length: u64) -> Self
     where
         I: IntoIterator<Item = (u64, V)>,
@@ -262,6 +318,7 @@ impl<V: BinaryValue> ListProof<V> {
             entries: values.into_iter().collect(),
             proof: vec![],
             length,
+            _hasher: PhantomData,
         }
     }
 
@@ -270,7 +327,7 @@ impl<V: BinaryValue> ListProof<V> {
             // The empty tree is special: it does not require the root element in the proof.
             vec![]
         } else {
-            let height = tree_height_by_length(length);
+            let height = height_of_len(length);
             vec![HashedEntry {
                 key: ProofListKey::new(height, 0),
                 hash: merkle_root,
@@ -281,6 +338,7 @@ impl<V: BinaryValue> ListProof<V> {
             entries: vec![],
             proof,
             length,
+            _hasher: PhantomData,
         }
     }
 
@@ -329,7 +387,7 @@ impl<V: BinaryValue> ListProof<V> {
     /// the total number of restored hashes is `O(log_2(N))`, where `N` is the list length.
     fn collect(&self) -> Result<Hash, ListProofError> {
         self.check_index_bounds()?;
-        let tree_height = tree_height_by_length(self.length);
+        let tree_height = height_of_len(self.length);
 
         // First, check an edge case when the list contains no elements.
         if tree_height == 0 {
@@ -343,10 +401,21 @@ impl<V: BinaryValue> ListProof<V> {
         // Fast path in case there are no values: in this case, the proof can contain
         // only a single root hash.
         if self.entries.is_empty() {
+            let root_key = ProofListKey::new(tree_height, 0);
             return match self.proof[..] {
-                [] => Err(ListProofError::MissingHash),
-                [HashedEntry { key, hash }] if key == ProofListKey::new(tree_height, 0) => Ok(hash),
-                _ => Err(ListProofError::UnexpectedBranch),
+                [] => Err(ListProofError::MissingHash {
+                    height: tree_height,
+                }),
+                [HashedEntry { key, hash }] if key == root_key => Ok(hash),
+                _ => {
+                    let key = self
+                        .proof
+                        .iter()
+                        .map(|entry| entry.key)
+                        .find(|&key| key != root_key)
+                        .unwrap_or(root_key);
+                    Err(ListProofError::UnexpectedBranch { key })
+                }
             };
         }
 
@@ -372,13 +441,13 @@ impl<V: BinaryValue> ListProof<V> {
         for &HashedEntry { key, .. } in &self.proof {
             let height = key.height();
             if height == 0 {
-                return Err(ListProofError::UnexpectedLeaf);
+                return Err(ListProofError::UnexpectedLeaf { key });
             }
 
             // `self.length - 1` is the index of the last element at `height = 1`. This index
             // is divided by 2 with each new height.
             if height >= tree_height || key.index() > (self.length - 1) >> u64::from(height - 1) {
-                return Err(ListProofError::UnexpectedBranch);
+                return Err(ListProofError::UnexpectedBranch { key });
             }
         }
 
@@ -386,10 +455,7 @@ impl<V: BinaryValue> ListProof<V> {
             .entries
             .iter()
             .map(|(i, value)| {
-                HashedEntry::new(
-                    ProofListKey::new(1, *i),
-                    HashTag::hash_leaf(&value.to_bytes()),
-                )
+                HashedEntry::new(ProofListKey::new(1, *i), H::hash_leaf(&value.to_bytes()))
             })
             .collect();
 
@@ -424,10 +490,10 @@ impl<V: BinaryValue> ListProof<V> {
             // Merge `hashes` with those obtained by zipping the previous layer.
             layer = merge(layer.into_iter(), hashes.into_iter())
                 .collect::<Result<Vec<_>, _>>()
-                .map_err(|_| ListProofError::RedundantHash)?;
+                .map_err(|key| ListProofError::RedundantHash { key })?;
 
             // Zip the current layer.
-            hash_layer(&mut layer, last_index)?;
+            hash_layer::<H>(&mut layer, last_index)?;
             last_index /= 2;
             hashes = remaining_hashes;
         }
@@ -442,6 +508,33 @@ impl<V: BinaryValue> ListProof<V> {
         self.length
     }
 
+    /// Checks whether the proof is certainly stale for the list with the specified current length,
+    /// e.g., if the proof was cached before the list was truncated or extended.
+    ///
+    /// The proof commits to the length of the list it was built for, so it cannot be valid
+    /// for a list with a different length. In particular, a proof for the elements removed
+    /// from the list with `truncate()` or `pop()` is always stale. Note that the converse
+    /// does not hold: if this method returns `false`, the proof may still be invalid
+    /// (e.g., if list elements were modified), and it needs to be verified against
+    /// the trusted list hash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, Database, TemporaryDB};
+    /// let fork = { let db = TemporaryDB::new(); db.fork() };
+    /// let mut list = fork.get_proof_list("index");
+    /// list.extend(vec![100_u32, 200, 300]);
+    ///
+    /// let proof = list.get_proof(2);
+    /// assert!(!proof.is_stale_against(list.len()));
+    /// list.truncate(2);
+    /// assert!(proof.is_stale_against(list.len()));
+    /// ```
+    pub fn is_stale_against(&self, current_len: u64) -> bool {
+        self.length != current_len
+    }
+
     /// Returns indexes and references to elements in the proof without verifying it.
     pub fn entries_unchecked(&self) -> &[(u64, V)] {
         &self.entries
@@ -453,6 +546,24 @@ impl<V: BinaryValue> ListProof<V> {
         self.entries_unchecked().iter().map(|(index, _)| *index)
     }
 
+    /// Returns the number of elements in the proof without verifying it.
+    ///
+    /// This method can be used to bound the size of an untrusted proof before
+    /// spending resources on its verification.
+    pub fn indexes_len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether the proof contains a non-empty contiguous range of elements, without
+    /// verifying the proof.
+    pub fn is_range(&self) -> bool {
+        !self.entries.is_empty()
+            && self
+                .entries
+                .windows(2)
+                .all(|window| window[0].0.checked_add(1) == Some(window[1].0))
+    }
+
     /// Provides access to the proof part of the view. Used in serialization.
     pub(crate) fn proof_unchecked(&self) -> &[HashedEntry] {
         &self.proof
@@ -478,7 +589,7 @@ impl<V: BinaryValue> ListProof<V> {
             // If `key.height()`s are not ordered, we know for sure that the proof is malformed.
             if key.height() < height {
                 return Err(if height == 0 {
-                    ListProofError::UnexpectedLeaf
+                    ListProofError::UnexpectedLeaf { key: *key }
                 } else {
                     ListProofError::Unordered
                 });
@@ -530,10 +641,55 @@ impl<V: BinaryValue> ListProof<V> {
         Ok(CheckedListProof {
             entries: &self.entries,
             length: self.length,
-            hash: HashTag::hash_list_node(self.length, tree_root),
+            hash: H::hash_list_node(self.length, tree_root),
         })
     }
 
+    /// Flips a bit in the byte with index `byte_index` (modulo the hash length) of the hash
+    /// with index `hash_index` (modulo the number of hashes) in the proof. Returns `false`
+    /// if the proof contains no hashes, in which case it is not modified.
+    #[cfg(any(test, feature = "fuzz"))]
+    pub(super) fn tamper_hash(&mut self, hash_index: usize, byte_index: usize) -> bool {
+        if self.proof.is_empty() {
+            return false;
+        }
+        let entry = &mut self.proof[hash_index % self.proof.len()];
+        let mut bytes = entry.hash.as_bytes();
+        bytes[byte_index % bytes.len()] ^= 1;
+        entry.hash = Hash::new(bytes);
+        true
+    }
+
+    /// Restores the list length and the list hash implied by the proof, without comparing
+    /// them to any trusted values.
+    ///
+    /// This is useful if the expected list hash is not known in advance, e.g., if the proof
+    /// needs to be compared against several candidate hashes. Note that the returned values
+    /// are not trusted by themselves; they need to be compared to the trusted ones.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the proof is malformed; see [`check()`] for the list
+    /// of performed checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, Database, TemporaryDB, ObjectHash};
+    /// let fork = { let db = TemporaryDB::new(); db.fork() };
+    /// let mut list = fork.get_proof_list("index");
+    /// list.extend(vec![100_u32, 200, 300]);
+    ///
+    /// let proof = list.get_proof(1);
+    /// assert_eq!(proof.compute_root().unwrap(), (3, list.object_hash()));
+    /// ```
+    ///
+    /// [`check()`]: #method.check
+    pub fn compute_root(&self) -> Result<(u64, Hash), ListProofError> {
+        let tree_root = self.collect()?;
+        Ok((self.length, H::hash_list_node(self.length, tree_root)))
+    }
+
     /// Verifies the correctness of the proof according to the trusted list hash.
     ///
     /// The method is essentially a convenience wrapper around `check()`.
@@ -552,11 +708,252 @@ impl<V: BinaryValue> ListProof<V> {
                 if checked_proof.index_hash() == expected_list_hash {
                     Ok(checked_proof)
                 } else {
-                    Err(ValidationError::UnmatchedRootHash)
+                    Err(ValidationError::UnmatchedRootHash {
+                        expected: expected_list_hash,
+                        actual: checked_proof.index_hash(),
+                    })
                 }
             })
     }
 
+    /// Verifies the proof according to the trusted list hash and list length, and returns
+    /// the proven elements together with their indexes.
+    ///
+    /// The method is a convenience wrapper around `check_against_hash()` for clients
+    /// that know both the `object_hash()` of the list and its length.
+    ///
+    /// # Errors
+    ///
+    /// `ValidationError::Malformed` is returned if the proof is malformed (see [`check()`]
+    /// for the list of performed checks), or if the list length in the proof does not match
+    /// `expected_len`. `ValidationError::UnmatchedRootHash` is returned if the restored list
+    /// hash does not match `expected_list_hash`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, Database, TemporaryDB, ObjectHash};
+    /// let fork = { let db = TemporaryDB::new(); db.fork() };
+    /// let mut list = fork.get_proof_list("index");
+    /// list.extend(vec![100_u32, 200, 300]);
+    ///
+    /// let proof = list.get_range_proof(1..);
+    /// let entries = proof.validate(list.object_hash(), 3).unwrap();
+    /// assert_eq!(entries, [(1, &200), (2, &300)]);
+    /// assert!(proof.validate(list.object_hash(), 4).is_err());
+    /// ```
+    ///
+    /// [`check()`]: #method.check
+    pub fn validate(
+        &self,
+        expected_list_hash: Hash,
+        expected_len: u64,
+    ) -> Result<Vec<(u64, &V)>, ValidationError<ListProofError>> {
+        let checked_proof = self.check_against_hash(expected_list_hash)?;
+        if checked_proof.list_len() != expected_len {
+            return Err(ValidationError::Malformed(ListProofError::LengthMismatch {
+                expected: expected_len,
+                actual: checked_proof.list_len(),
+            }));
+        }
+        Ok(checked_proof
+            .entries()
+            .iter()
+            .map(|(index, value)| (*index, value))
+            .collect())
+    }
+
+    /// Verifies that the proof asserts absence of the elements in the specified `range`
+    /// according to the trusted list hash.
+    ///
+    /// Besides checking the proof against `expected_list_hash` (which binds the list length
+    /// stated in the proof to the hash), the method checks that the proof contains no elements
+    /// and that `range` starts at or after the list length. Thus, a prover cannot claim
+    /// absence of an existing element by supplying a smaller list length.
+    ///
+    /// # Errors
+    ///
+    /// `ValidationError::Malformed` is returned if the proof is malformed (see [`check()`]
+    /// for the list of performed checks), or if it does not prove absence of the elements
+    /// in `range`. `ValidationError::UnmatchedRootHash` is returned if the restored list hash
+    /// does not match `expected_list_hash`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, Database, TemporaryDB, ObjectHash};
+    /// let fork = { let db = TemporaryDB::new(); db.fork() };
+    /// let mut list = fork.get_proof_list("index");
+    /// list.extend(vec![100_u32, 200, 300]);
+    ///
+    /// let proof = list.get_range_proof(5..10);
+    /// proof.validate_absence(list.object_hash(), 5..10).unwrap();
+    /// // The proof does not assert absence of the existing elements.
+    /// assert!(proof.validate_absence(list.object_hash(), 2..10).is_err());
+    /// ```
+    ///
+    /// [`check()`]: #method.check
+    pub fn validate_absence<R: RangeBounds<u64>>(
+        &self,
+        expected_list_hash: Hash,
+        range: R,
+    ) -> Result<(), ValidationError<ListProofError>> {
+        let checked_proof = self.check_against_hash(expected_list_hash)?;
+        if !checked_proof.entries().is_empty() {
+            return Err(ValidationError::Malformed(ListProofError::NotAbsent));
+        }
+
+        let from = match range.start_bound() {
+            Bound::Unbounded => 0,
+            Bound::Included(from) => *from,
+            Bound::Excluded(from) => from.saturating_add(1),
+        };
+        if from < checked_proof.list_len() {
+            return Err(ValidationError::Malformed(ListProofError::NotAbsent));
+        }
+        Ok(())
+    }
+
+    /// Verifies the proof for a single list element according to the trusted list hash,
+    /// and returns the value of the element or the default value if the element is absent.
+    ///
+    /// This method is the counterpart of [`ProofListIndex::get_or_default`] for proof
+    /// verifiers. The list is dense, so an element is absent only if its index is not less
+    /// than the list length; absence is proven by a proof of absence returned by
+    /// [`ProofListIndex::get_proof`] for such an index.
+    ///
+    /// # Errors
+    ///
+    /// `ValidationError::Malformed` is returned if the proof is malformed (see [`check()`]
+    /// for the list of performed checks), or if it neither contains the element with
+    /// the specified `index` nor proves its absence. `ValidationError::UnmatchedRootHash`
+    /// is returned if the restored list hash does not match `expected_list_hash`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, Database, TemporaryDB, ObjectHash};
+    /// let fork = { let db = TemporaryDB::new(); db.fork() };
+    /// let mut list = fork.get_proof_list("index");
+    /// list.extend(vec![100_u32, 200, 300]);
+    /// let list_hash = list.object_hash();
+    ///
+    /// let proof = list.get_proof(1);
+    /// assert_eq!(proof.validate_or_default(list_hash, 1).unwrap(), 200);
+    /// let proof = list.get_proof(5);
+    /// assert_eq!(proof.validate_or_default(list_hash, 5).unwrap(), 0);
+    /// // The proof does not concern the element with index 2.
+    /// assert!(proof.validate_or_default(list_hash, 2).is_err());
+    /// ```
+    ///
+    /// [`ProofListIndex::get_or_default`]: struct.ProofListIndex.html#method.get_or_default
+    /// [`ProofListIndex::get_proof`]: struct.ProofListIndex.html#method.get_proof
+    /// [`check()`]: #method.check
+    pub fn validate_or_default(
+        &self,
+        expected_list_hash: Hash,
+        index: u64,
+    ) -> Result<V, ValidationError<ListProofError>>
+    where
+        V: Clone + Default,
+    {
+        let checked_proof = self.check_against_hash(expected_list_hash)?;
+        if let Some((_, value)) = checked_proof.entries().iter().find(|(i, _)| *i == index) {
+            return Ok(value.clone());
+        }
+
+        if checked_proof.entries().is_empty() && index >= checked_proof.list_len() {
+            Ok(V::default())
+        } else {
+            Err(ValidationError::Malformed(ListProofError::NotAbsent))
+        }
+    }
+
+    /// Merges proofs for two adjacent ranges of elements of the same list into a single proof
+    /// for the union of these ranges.
+    ///
+    /// Both proofs are checked before merging, and they must restore the same list hash
+    /// and length. Since hashes of all internal nodes of the Merkle tree are committed to by
+    /// the list hash, this ensures that the proofs agree on all nodes they have in common.
+    /// The merged proof reuses branch hashes from the inputs: hashes to the left of the merged
+    /// range are taken from `left`, and hashes to the right of it from `right`. Hashes covering
+    /// elements of the other proof are dropped, so the merged proof is the same as the one
+    /// built by the list for the merged range.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if any of the proofs is malformed (see [`check()`] for the list
+    /// of performed checks). `ListProofError::InconsistentRoots` is returned if the proofs
+    /// restore different list hashes or lengths. `ListProofError::NonAdjacent` is returned
+    /// if either of the proofs does not cover a non-empty contiguous range of elements,
+    /// or if the range of `right` does not immediately follow the range of `left`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exonum_merkledb::{access::CopyAccessExt, Database, TemporaryDB, ObjectHash};
+    /// # use exonum_merkledb::ListProof;
+    /// let fork = { let db = TemporaryDB::new(); db.fork() };
+    /// let mut list = fork.get_proof_list("index");
+    /// list.extend(0_u32..200);
+    ///
+    /// let left = list.get_range_proof(0..100);
+    /// let right = list.get_range_proof(100..150);
+    /// let merged = ListProof::merge(left, right).unwrap();
+    /// assert_eq!(merged, list.get_range_proof(0..150));
+    ///
+    /// let checked_proof = merged.check_against_hash(list.object_hash()).unwrap();
+    /// assert!(checked_proof.indexes().eq(0..150));
+    /// ```
+    ///
+    /// [`check()`]: #method.check
+    pub fn merge(left: Self, right: Self) -> Result<Self, ListProofError> {
+        let (left_root, right_root) = (left.collect()?, right.collect()?);
+        if left.length != right.length || left_root != right_root {
+            return Err(ListProofError::InconsistentRoots);
+        }
+
+        let is_adjacent = left.is_range()
+            && right.is_range()
+            && match (left.entries.last(), right.entries.first()) {
+                (Some((last, _)), Some((first, _))) => last.checked_add(1) == Some(*first),
+                _ => false,
+            };
+        if !is_adjacent {
+            return Err(ListProofError::NonAdjacent);
+        }
+
+        let from = left.entries[0].0;
+        let inclusive_to = right.entries[right.entries.len() - 1].0;
+        let proof = range_proof_keys(left.length, from, inclusive_to)
+            .into_iter()
+            .map(|key| {
+                let source = if key.first_left_leaf_index() < from {
+                    &left.proof
+                } else {
+                    &right.proof
+                };
+                // Since both proofs are valid, they contain all branches adjacent
+                // to the merged range; thus, the lookup should always succeed.
+                source
+                    .binary_search_by(|entry| entry.key.cmp(&key))
+                    .map(|position| source[position])
+                    .map_err(|_| ListProofError::MissingHash {
+                        height: key.height(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut entries = left.entries;
+        entries.extend(right.entries);
+        Ok(Self {
+            proof,
+            entries,
+            length: left.length,
+            _hasher: PhantomData,
+        })
+    }
+
     /// Creates `ListProof` from `proof` and `entries` vectors. Used to construct proof
     /// after deserialization.
     pub(crate) fn from_raw_parts(
@@ -568,10 +965,96 @@ impl<V: BinaryValue> ListProof<V> {
             proof,
             entries,
             length,
+            _hasher: PhantomData,
         }
     }
 }
 
+/// Number of hex digits of a hash displayed by `ListProof`'s `Display` implementation.
+const SHORT_HASH_LEN: usize = 8;
+
+fn short_hash(hash: &Hash) -> String {
+    let mut hex = hash.to_hex();
+    hex.truncate(SHORT_HASH_LEN);
+    hex
+}
+
+/// Renders the proof as an indented tree, which is useful for debugging.
+///
+/// Hashes in the proof are grouped by their height in the Merkle tree, starting from
+/// the greatest height; proven elements are displayed at the bottom of the tree as leaves.
+/// Hashes are abbreviated to their first 8 hex digits. A proof without elements
+/// is displayed as `Absent(length, root)`.
+///
+/// # Examples
+///
+/// ```
+/// # use exonum_merkledb::{access::CopyAccessExt, Database, TemporaryDB};
+/// let fork = { let db = TemporaryDB::new(); db.fork() };
+/// let mut list = fork.get_proof_list("index");
+/// list.extend(vec![1_u32, 2, 3]);
+///
+/// let proof = list.get_proof(2);
+/// println!("{}", proof);
+/// // ListProof(length = 3)
+/// //   Left(height = 2, index = 0): ...
+/// //     Leaf(index = 2): 3
+/// let proof = list.get_proof(5);
+/// assert!(proof.to_string().starts_with("Absent(3, "));
+/// ```
+impl<V: fmt::Debug, H> fmt::Display for ListProof<V, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.entries.is_empty() {
+            let root = self
+                .proof
+                .first()
+                .map_or_else(Hash::zero, |entry| entry.hash);
+            return write!(formatter, "Absent({}, {})", self.length, short_hash(&root));
+        }
+
+        write!(formatter, "ListProof(length = {})", self.length)?;
+        let mut hashes = self.proof.clone();
+        hashes.sort_by(|x, y| {
+            y.key
+                .height()
+                .cmp(&x.key.height())
+                .then(x.key.index().cmp(&y.key.index()))
+        });
+
+        let mut depth = 0;
+        let mut last_height = None;
+        for HashedEntry { key, hash } in &hashes {
+            if last_height != Some(key.height()) {
+                depth += 1;
+                last_height = Some(key.height());
+            }
+            let kind = if key.is_left() { "Left" } else { "Right" };
+            write!(
+                formatter,
+                "\n{:indent$}{}(height = {}, index = {}): {}",
+                "",
+                kind,
+                key.height(),
+                key.index(),
+                short_hash(hash),
+                indent = 2 * depth
+            )?;
+        }
+
+        for (index, value) in &self.entries {
+            write!(
+                formatter,
+                "\n{:indent$}Leaf(index = {}): {:?}",
+                "",
+                index,
+                value,
+                indent = 2 * (depth + 1)
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// Version of `ListProof` obtained after verification.
 ///
 /// See [`ListProof`] for an example of usage.
@@ -612,14 +1095,21 @@ impl<'a, V> CheckedListProof<'a, V> {
 #[non_exhaustive]
 pub enum ListProofError {
     /// Proof contains a hash in a place where a value was expected.
-    #[error("proof contains a hash in a place where a value was expected")]
-    UnexpectedLeaf,
+    #[error("proof contains a hash in a place where a value was expected (key: {key:?})")]
+    UnexpectedLeaf {
+        /// Key of the unexpected hash.
+        key: ProofListKey,
+    },
 
     /// Proof contains a hash in the position which is impossible according to the list length.
     #[error(
-        "proof contains a hash in the position which is impossible according to the list length"
+        "proof contains a hash in the position which is impossible according to the list length \
+         (key: {key:?})"
     )]
-    UnexpectedBranch,
+    UnexpectedBranch {
+        /// Key of the unexpected hash.
+        key: ProofListKey,
+    },
 
     /// Values or hashes in the proof are not ordered by their keys.
     #[error("values or hashes in the proof are not ordered by their keys")]
@@ -627,12 +1117,18 @@ pub enum ListProofError {
 
     /// There are redundant hashes in the proof: the hash of the underlying list can be calculated
     /// without some of them.
-    #[error("redundant hash in the proof")]
-    RedundantHash,
+    #[error("redundant hash in the proof (key: {key:?})")]
+    RedundantHash {
+        /// Key of the redundant hash.
+        key: ProofListKey,
+    },
 
     /// Proof does not contain necessary information to compute the hash of the underlying list.
-    #[error("missing hash")]
-    MissingHash,
+    #[error("missing hash at height {height}")]
+    MissingHash {
+        /// Height in the Merkle tree at which the hash is missing.
+        height: u8,
+    },
 
     /// Non-empty proof for an empty list.
     ///
@@ -647,6 +1143,29 @@ pub enum ListProofError {
     /// exceeds the maximum possible list length (`2**56`).
     #[error("proof does not satisfy built-in constraints on element positions")]
     OutOfBounds,
+
+    /// Proof does not assert absence of the requested elements: either it contains elements,
+    /// or the requested range intersects with the list.
+    #[error("proof does not assert absence of the requested elements")]
+    NotAbsent,
+
+    /// The list length in the proof differs from the expected one.
+    #[error("list length in the proof ({actual}) differs from the expected length ({expected})")]
+    LengthMismatch {
+        /// Expected list length.
+        expected: u64,
+        /// List length in the proof.
+        actual: u64,
+    },
+
+    /// Merged proofs do not cover adjacent ranges of elements.
+    #[error("merged proofs do not cover adjacent ranges of elements")]
+    NonAdjacent,
+
+    /// Merged proofs restore different list hashes, i.e., they are built for different lists
+    /// or for different states of the same list.
+    #[error("merged proofs restore different list hashes")]
+    InconsistentRoots,
 }
 
 #[cfg(test)]
@@ -700,7 +1219,7 @@ mod tests {
             entry(1, 7),
             entry(1, 8),
         ];
-        hash_layer(&mut layer, 8).unwrap();
+        hash_layer::<HashTag>(&mut layer, 8).unwrap();
         assert!(layer.iter().map(|entry| entry.key).eq(vec![
             ProofListKey::new(2, 0),
             ProofListKey::new(2, 3),
@@ -721,21 +1240,21 @@ mod tests {
 
         // layer[0] has odd index
         let mut layer = vec![entry(1, 1), entry(1, 2)];
-        assert!(hash_layer(&mut layer, 2).is_err());
+        assert!(hash_layer::<HashTag>(&mut layer, 2).is_err());
 
         // layer[1] is not adjacent to layer[0]
         let mut layer = vec![entry(1, 0), entry(1, 2)];
-        assert!(hash_layer(&mut layer, 3).is_err());
+        assert!(hash_layer::<HashTag>(&mut layer, 3).is_err());
         let mut layer = vec![entry(1, 0), entry(1, 3)];
-        assert!(hash_layer(&mut layer, 3).is_err());
+        assert!(hash_layer::<HashTag>(&mut layer, 3).is_err());
 
         // layer[-1] has odd index, while there is even number of elements in the layer
         let mut layer = vec![entry(1, 0), entry(1, 1), entry(1, 7)];
-        assert!(hash_layer(&mut layer, 7).is_err());
+        assert!(hash_layer::<HashTag>(&mut layer, 7).is_err());
 
         // layer[-1] has index lesser that the layer length
         let mut layer = vec![entry(1, 0), entry(1, 1), entry(1, 4)];
-        assert!(hash_layer(&mut layer, 6).is_err());
+        assert!(hash_layer::<HashTag>(&mut layer, 6).is_err());
     }
 
     #[test]
@@ -745,11 +1264,11 @@ mod tests {
         assert_eq!(proof.hash_ops().unwrap(), 0);
 
         // Proof for a single-element tree.
-        let proof = ListProof::new(vec![(0, 0_u32)], 1);
+        let proof: ListProof<_> = ListProof::new(vec![(0, 0_u32)], 1);
         assert_eq!(proof.hash_ops().unwrap(), 1);
 
         // Proof for index 1 in a 3-element tree.
-        let mut proof = ListProof::new(vec![(1, 1_u32)], 3);
+        let mut proof: ListProof<_> = ListProof::new(vec![(1, 1_u32)], 3);
         proof.push_hash(1, 0, Hash::zero());
         proof.push_hash(2, 1, Hash::zero());
         assert_eq!(proof.hash_ops().unwrap(), 3);
@@ -764,7 +1283,7 @@ mod tests {
         //   x       Values
 
         // Proof for index 4 in a 5-element tree.
-        let mut proof = ListProof::new(vec![(4, 4_u32)], 5);
+        let mut proof: ListProof<_> = ListProof::new(vec![(4, 4_u32)], 5);
         proof.push_hash(3, 0, Hash::zero());
         assert_eq!(proof.hash_ops().unwrap(), 4);
         // 1 ops to hash values + 1 op per heights 1..=3:
@@ -780,7 +1299,7 @@ mod tests {
         //       x   Values
 
         // Proof for indexes 1..=2 in a 3-element tree.
-        let mut proof = ListProof::new(vec![(1, 1_u32), (2, 2)], 3);
+        let mut proof: ListProof<_> = ListProof::new(vec![(1, 1_u32), (2, 2)], 3);
         proof.push_hash(1, 0, Hash::zero());
         assert_eq!(proof.hash_ops().unwrap(), 5);
         // 2 ops to hash values + 2 ops on height 1 + 1 op on height 2:
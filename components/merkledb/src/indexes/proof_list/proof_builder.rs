@@ -16,9 +16,12 @@
 
 use exonum_crypto::Hash;
 
-use std::ops::{Bound, RangeBounds};
+use std::{
+    cmp,
+    ops::{Bound, RangeBounds},
+};
 
-use super::{key::ProofListKey, tree_height_by_length, ListProof};
+use super::{height_of_len, key::ProofListKey, Hasher, ListProof, ProofListError};
 use crate::BinaryValue;
 
 /// Encapsulation of a binary Merkle tree allowing to access its terminal and intermediate
@@ -38,73 +41,171 @@ pub trait MerkleTree<V> {
 
     /// Gets the Merkle root of the tree.
     fn merkle_root(&self) -> Hash {
-        let tree_height = tree_height_by_length(self.len());
+        let tree_height = height_of_len(self.len());
         self.node(ProofListKey::new(tree_height, 0))
     }
 }
 
-pub trait BuildProof<V> {
-    fn create_proof(&self, index: u64) -> ListProof<V>;
-    fn create_range_proof(&self, indexes: impl RangeBounds<u64>) -> ListProof<V>;
+pub trait BuildProof<V, H> {
+    fn create_proof(&self, index: u64) -> ListProof<V, H>;
+    fn create_range_proof(
+        &self,
+        indexes: impl RangeBounds<u64>,
+    ) -> Result<ListProof<V, H>, ProofListError>;
+    fn create_multiproof(&self, indexes: impl IntoIterator<Item = u64>) -> ListProof<V, H>;
+    fn create_multiproof_limited(
+        &self,
+        indexes: impl IntoIterator<Item = u64>,
+        max_proof_nodes: usize,
+    ) -> Result<ListProof<V, H>, ProofListError>;
 }
 
-impl<V, T> BuildProof<V> for T
+impl<V, H, T> BuildProof<V, H> for T
 where
     V: BinaryValue,
+    H: Hasher,
     T: MerkleTree<V>,
 {
-    fn create_proof(&self, index: u64) -> ListProof<V> {
+    fn create_proof(&self, index: u64) -> ListProof<V, H> {
         create_proof(self, index, index)
     }
 
-    fn create_range_proof(&self, indexes: impl RangeBounds<u64>) -> ListProof<V> {
-        // Inclusive lower boundary of the proof range.
-        let from = match indexes.start_bound() {
-            Bound::Unbounded => 0_u64,
-            Bound::Included(from) => *from,
-            Bound::Excluded(from) => *from + 1,
-        };
-
-        // Exclusive upper boundary of the proof range.
-        let to = match indexes.end_bound() {
-            Bound::Unbounded => self.len(),
-            // Saturation below doesn't matter: if `to == u64::max_value()`, it is guaranteed
-            // to be larger than any possible list length.
-            Bound::Included(to) => to.saturating_add(1),
-            Bound::Excluded(to) => *to,
-        };
-
-        if (from >= self.len() && indexes.end_bound() == Bound::Unbounded) || from == to {
-            // We assume the first condition is a "legal" case of the caller not knowing
-            // the list length, so we don't want to panic in the `to > from` assertion below.
-            return ListProof::empty(self.merkle_root(), self.len());
+    fn create_range_proof(
+        &self,
+        indexes: impl RangeBounds<u64>,
+    ) -> Result<ListProof<V, H>, ProofListError> {
+        Ok(match resolve_range(indexes, self.len())? {
+            Some((from, inclusive_to)) => create_proof(self, from, inclusive_to),
+            None => ListProof::empty(self.merkle_root(), self.len()),
+        })
+    }
+
+    fn create_multiproof(&self, indexes: impl IntoIterator<Item = u64>) -> ListProof<V, H> {
+        self.create_multiproof_limited(indexes, usize::max_value())
+            .expect("Proof without size limit cannot be too large")
+    }
+
+    fn create_multiproof_limited(
+        &self,
+        indexes: impl IntoIterator<Item = u64>,
+        max_proof_nodes: usize,
+    ) -> Result<ListProof<V, H>, ProofListError> {
+        let mut indexes: Vec<_> = indexes.into_iter().collect();
+        indexes.sort_unstable();
+        indexes.dedup();
+
+        match indexes.last() {
+            Some(&last_index) if last_index < self.len() => {
+                create_multiproof(self, indexes, max_proof_nodes)
+            }
+            // Either there are no indexes, or some of them are out of bounds.
+            _ => {
+                let proof = ListProof::empty(self.merkle_root(), self.len());
+                if proof.proof_unchecked().len() > max_proof_nodes {
+                    return Err(ProofListError::ProofTooLarge {
+                        limit: max_proof_nodes,
+                    });
+                }
+                Ok(proof)
+            }
         }
-        assert!(
-            to > from,
-            "Illegal range boundaries: the range start is {}, but the range end is {}",
-            from,
-            to
-        );
-        create_proof(self, from, to - 1)
     }
 }
 
+/// Converts range bounds into a closed range of indexes `[from, inclusive_to]` to be included
+/// into a proof for a list with the specified length.
+///
+/// # Return value
+///
+/// `Ok(None)` is returned if the proof should contain no elements, i.e., if the range is empty
+/// or lies entirely beyond the list length. Otherwise, it is guaranteed
+/// that `from <= inclusive_to < len`. An error is returned if the range bounds are illegal,
+/// i.e., the range end is less than its start.
+pub fn resolve_range(
+    indexes: impl RangeBounds<u64>,
+    len: u64,
+) -> Result<Option<(u64, u64)>, ProofListError> {
+    // Inclusive lower boundary of the proof range.
+    let from = match indexes.start_bound() {
+        Bound::Unbounded => 0_u64,
+        Bound::Included(from) => *from,
+        // Saturation here and below doesn't matter: `u64::max_value()` is guaranteed
+        // to be larger than any possible list length.
+        Bound::Excluded(from) => from.saturating_add(1),
+    };
+
+    // Exclusive upper boundary of the proof range.
+    let to = match indexes.end_bound() {
+        Bound::Unbounded => len,
+        Bound::Included(to) => to.saturating_add(1),
+        Bound::Excluded(to) => *to,
+    };
+
+    if (from >= len && indexes.end_bound() == Bound::Unbounded) || from == to {
+        // We assume the first condition is a "legal" case of the caller not knowing
+        // the list length, so we don't want to return an error in the `to > from`
+        // check below.
+        return Ok(None);
+    }
+    if to < from {
+        return Err(ProofListError::IllegalRange {
+            start: from,
+            end: to,
+        });
+    }
+
+    Ok(if from >= len {
+        None
+    } else {
+        Some((from, cmp::min(to, len) - 1))
+    })
+}
+
 /// Creates a `ListProof` for a contiguous closed range of indexes `[from, inclusive_to]`.
 ///
 /// The caller must ensure that `inclusive_to >= from`.
-fn create_proof<V: BinaryValue>(
+fn create_proof<V: BinaryValue, H: Hasher>(
     tree: &impl MerkleTree<V>,
     from: u64,
     inclusive_to: u64,
-) -> ListProof<V> {
+) -> ListProof<V, H> {
     let tree_len = tree.len();
-    let tree_height = tree_height_by_length(tree_len);
     if from >= tree_len {
         return ListProof::empty(tree.merkle_root(), tree_len);
     }
 
     let items = (from..=inclusive_to).zip(tree.values(from));
     let mut proof = ListProof::new(items, tree_len);
+    for (key, hash) in range_proof_hashes(tree, from, inclusive_to) {
+        proof.push_hash(key.height(), key.index(), hash);
+    }
+    proof
+}
+
+/// Collects hashes necessary to restore the Merkle root of the tree given the elements
+/// in the contiguous closed range of indexes `[from, inclusive_to]`. The hashes are ordered
+/// by increasing key, as required by `ListProof`.
+///
+/// The caller must ensure that `inclusive_to >= from` and `from < tree.len()`.
+pub fn range_proof_hashes<V>(
+    tree: &impl MerkleTree<V>,
+    from: u64,
+    inclusive_to: u64,
+) -> Vec<(ProofListKey, Hash)> {
+    range_proof_keys(tree.len(), from, inclusive_to)
+        .into_iter()
+        .map(|key| (key, tree.node(key)))
+        .collect()
+}
+
+/// Computes positions of hashes necessary to restore the Merkle root of the tree
+/// with the specified length given the elements in the contiguous closed range of indexes
+/// `[from, inclusive_to]`. The positions are ordered by increasing key.
+///
+/// The caller must ensure that `inclusive_to >= from` and `from < tree_len`.
+pub fn range_proof_keys(tree_len: u64, from: u64, inclusive_to: u64) -> Vec<ProofListKey> {
+    let tree_height = height_of_len(tree_len);
+    let mut keys = vec![];
 
     // `left` and `right` track the indexes of elements for which we build the proof,
     // on the particular `height` of the tree. Both these values are inclusive; i.e., the range
@@ -121,20 +222,86 @@ fn create_proof<V: BinaryValue>(
         // thus, iff `left` is odd, we need a hash with index `left - 1` to restore the
         // root hash.
         if left % 2 == 1 {
-            let hash = tree.node(ProofListKey::new(height, left - 1));
-            proof.push_hash(height, left - 1, hash);
+            keys.push(ProofListKey::new(height, left - 1));
         }
 
         // Similarly, we may need a hash to the right of the end of the range, provided
         // that the end has an even index and the hash to the right exists.
         if right % 2 == 0 && right < last_index_on_level {
-            let hash = tree.node(ProofListKey::new(height, right + 1));
-            proof.push_hash(height, right + 1, hash);
+            keys.push(ProofListKey::new(height, right + 1));
         }
 
         left /= 2;
         right /= 2;
         last_index_on_level /= 2;
     }
-    proof
+    keys
+}
+
+/// Creates a `ListProof` for an arbitrary set of indexes. An error is returned as soon as
+/// the total number of elements and hashes in the proof exceeds `max_proof_nodes`.
+///
+/// The caller must ensure that `indexes` are non-empty, sorted, deduplicated,
+/// and are lesser than the tree length.
+fn create_multiproof<V: BinaryValue, H: Hasher>(
+    tree: &impl MerkleTree<V>,
+    mut indexes: Vec<u64>,
+    max_proof_nodes: usize,
+) -> Result<ListProof<V, H>, ProofListError> {
+    let too_large = || ProofListError::ProofTooLarge {
+        limit: max_proof_nodes,
+    };
+    // All elements are included into the proof, so their number is checked before
+    // reading them from the tree.
+    let mut proof_nodes = indexes.len();
+    if proof_nodes > max_proof_nodes {
+        return Err(too_large());
+    }
+
+    let tree_len = tree.len();
+    let tree_height = height_of_len(tree_len);
+
+    let items = indexes.iter().map(|&index| {
+        let value = tree
+            .values(index)
+            .next()
+            .expect("Value with index lesser than tree length is missing");
+        (index, value)
+    });
+    let mut proof = ListProof::new(items, tree_len);
+
+    // `indexes` track the positions of elements for which we build the proof,
+    // on the particular `height` of the tree.
+    let mut last_index_on_level = tree_len - 1;
+
+    for height in 1..tree_height {
+        // For each known position, we need the hash of its sibling, unless the sibling
+        // is known as well or does not exist. Since `indexes` are sorted, the sibling
+        // may only be adjacent to the position. Sibling hashes are pushed in the increasing
+        // order of indexes, as the proof requires.
+        for (i, &index) in indexes.iter().enumerate() {
+            let sibling = index ^ 1;
+            let is_sibling_known = if index % 2 == 0 {
+                indexes.get(i + 1) == Some(&sibling)
+            } else {
+                i > 0 && indexes[i - 1] == sibling
+            };
+
+            if !is_sibling_known && sibling <= last_index_on_level {
+                proof_nodes += 1;
+                if proof_nodes > max_proof_nodes {
+                    return Err(too_large());
+                }
+                let hash = tree.node(ProofListKey::new(height, sibling));
+                proof.push_hash(height, sibling, hash);
+            }
+        }
+
+        for index in &mut indexes {
+            *index /= 2;
+        }
+        indexes.dedup();
+        last_index_on_level /= 2;
+    }
+    Ok(proof)
 }
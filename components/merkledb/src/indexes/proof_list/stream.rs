@@ -0,0 +1,226 @@
+// Copyright 2020 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Streaming binary serialization of range proofs.
+//!
+//! The proof is serialized as follows (all integers are little-endian):
+//!
+//! ```text
+//! length: u64
+//! hashes_count: u64
+//! hashes_count * { height: u8, index: u64, hash: [u8; 32] }
+//! entries_count: u64
+//! entries_count * { index: u64, value_len: u64, value: [u8; value_len] }
+//! ```
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use exonum_crypto::{Hash, HASH_SIZE};
+
+use std::{
+    borrow::Cow,
+    io::{self, Read, Write},
+    mem,
+    ops::RangeBounds,
+};
+
+use super::{
+    height_of_len,
+    key::{ProofListKey, HEIGHT_SHIFT, MAX_INDEX},
+    proof::HashedEntry,
+    proof_builder::{range_proof_hashes, range_proof_keys, resolve_range, MerkleTree},
+    Hasher, ListProof, ProofListIndex,
+};
+use crate::{views::RawAccess, BinaryValue};
+
+impl<T, V, H> ProofListIndex<T, V, H>
+where
+    T: RawAccess,
+    V: BinaryValue,
+    H: Hasher,
+{
+    /// Writes the proof of existence for the list elements in the specified range
+    /// to the `writer`.
+    ///
+    /// Unlike [`get_range_proof`], this method does not build the proof in memory; list elements
+    /// are written as they are read from the database. Thus, the consumed memory is proportional
+    /// to the height of the Merkle tree rather than to the number of elements in the range.
+    /// The written proof can be restored with [`ListProof::read_range_proof`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the `writer` fails, or if the range bounds are illegal
+    /// (in the latter case, the error has `InvalidInput` kind).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database, ListProof};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2, 3, 4, 5]);
+    ///
+    /// let mut buffer = vec![];
+    /// index.write_range_proof(1..3, &mut buffer).unwrap();
+    /// let proof = ListProof::<u32>::read_range_proof(&buffer[..]).unwrap();
+    /// assert_eq!(proof, index.get_range_proof(1..3));
+    /// ```
+    ///
+    /// [`get_range_proof`]: #method.get_range_proof
+    /// [`ListProof::read_range_proof`]: struct.ListProof.html#method.read_range_proof
+    pub fn write_range_proof<R, W>(&self, range: R, mut writer: W) -> io::Result<()>
+    where
+        R: RangeBounds<u64>,
+        W: Write,
+    {
+        let len = self.len();
+        let range = resolve_range(range, len)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        let hashes = match range {
+            Some((from, inclusive_to)) => range_proof_hashes(self, from, inclusive_to),
+            // The empty tree does not require the root hash in the proof.
+            None if len == 0 => vec![],
+            None => vec![(ProofListKey::new(height_of_len(len), 0), self.merkle_root())],
+        };
+
+        writer.write_u64::<LittleEndian>(len)?;
+        writer.write_u64::<LittleEndian>(hashes.len() as u64)?;
+        for (key, hash) in hashes {
+            writer.write_u8(key.height())?;
+            writer.write_u64::<LittleEndian>(key.index())?;
+            writer.write_all(hash.as_ref())?;
+        }
+
+        if let Some((from, inclusive_to)) = range {
+            writer.write_u64::<LittleEndian>(inclusive_to - from + 1)?;
+            for (index, value) in (from..=inclusive_to).zip(self.iter_from(from)) {
+                let bytes = value.into_bytes();
+                writer.write_u64::<LittleEndian>(index)?;
+                writer.write_u64::<LittleEndian>(bytes.len() as u64)?;
+                writer.write_all(&bytes)?;
+            }
+        } else {
+            writer.write_u64::<LittleEndian>(0)?;
+        }
+        Ok(())
+    }
+
+    /// Estimates the size in bytes of the proof for the list elements in the specified range,
+    /// as written by [`write_range_proof`].
+    ///
+    /// The estimate is computed from the list length and the range alone, without reading
+    /// list elements or hashes from the database. The number of hashes in the proof is exact;
+    /// the size of each list element is estimated as `mem::size_of::<V>()`, which is exact
+    /// for fixed-size values like integers or hashes, but may be arbitrarily off for values
+    /// with variable length (e.g., strings or vectors). Thus, the estimate can be used
+    /// to reject requests for overly large proofs before building them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range bounds are illegal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{access::CopyAccessExt, TemporaryDB, Database};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = fork.get_proof_list("name");
+    /// index.extend(vec![1_u32, 2, 3, 4, 5]);
+    ///
+    /// let mut buffer = vec![];
+    /// index.write_range_proof(1..3, &mut buffer).unwrap();
+    /// assert_eq!(index.range_proof_size_hint(1..3), buffer.len());
+    /// ```
+    ///
+    /// [`write_range_proof`]: #method.write_range_proof
+    pub fn range_proof_size_hint<R: RangeBounds<u64>>(&self, range: R) -> usize {
+        // Size of the list length, and of the hash and entry counts.
+        const HEADER_SIZE: usize = 3 * mem::size_of::<u64>();
+        // Size of a hash in the proof together with its height and index.
+        const HASH_ENTRY_SIZE: usize = 1 + mem::size_of::<u64>() + HASH_SIZE;
+        // Size of the index and length prefix of a list element.
+        const VALUE_PREFIX_SIZE: usize = 2 * mem::size_of::<u64>();
+
+        let len = self.len();
+        let range = resolve_range(range, len).unwrap_or_else(|err| panic!("{}", err));
+        let (hashes_count, entries_count) = match range {
+            Some((from, inclusive_to)) => (
+                range_proof_keys(len, from, inclusive_to).len(),
+                (inclusive_to - from + 1) as usize,
+            ),
+            None if len == 0 => (0, 0),
+            None => (1, 0),
+        };
+
+        let value_size = VALUE_PREFIX_SIZE + mem::size_of::<V>();
+        entries_count
+            .saturating_mul(value_size)
+            .saturating_add(HEADER_SIZE + hashes_count * HASH_ENTRY_SIZE)
+    }
+}
+
+impl<V: BinaryValue, H: Hasher> ListProof<V, H> {
+    /// Reads a proof written with [`ProofListIndex::write_range_proof`] from the `reader`.
+    ///
+    /// The proof is not verified; use [`check`] or [`check_against_hash`] to verify it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the `reader` fails, or if the proof cannot be decoded
+    /// (in the latter case, the error has `InvalidData` kind).
+    ///
+    /// [`ProofListIndex::write_range_proof`]: struct.ProofListIndex.html#method.write_range_proof
+    /// [`check`]: #method.check
+    /// [`check_against_hash`]: #method.check_against_hash
+    pub fn read_range_proof<R: Read>(mut reader: R) -> io::Result<Self> {
+        let length = reader.read_u64::<LittleEndian>()?;
+
+        // Counts are not used to preallocate memory since they come from an untrusted source.
+        let hashes_count = reader.read_u64::<LittleEndian>()?;
+        let mut proof = vec![];
+        for _ in 0..hashes_count {
+            let height = reader.read_u8()?;
+            let index = reader.read_u64::<LittleEndian>()?;
+            if u64::from(height) > HEIGHT_SHIFT || index > MAX_INDEX {
+                let message = "proof key is out of range";
+                return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+            }
+            let mut hash_bytes = [0; HASH_SIZE];
+            reader.read_exact(&mut hash_bytes)?;
+            let key = ProofListKey::new(height, index);
+            proof.push(HashedEntry::new(key, Hash::new(hash_bytes)));
+        }
+
+        let entries_count = reader.read_u64::<LittleEndian>()?;
+        let mut entries = vec![];
+        for _ in 0..entries_count {
+            let index = reader.read_u64::<LittleEndian>()?;
+            let value_len = reader.read_u64::<LittleEndian>()?;
+            let mut bytes = vec![];
+            reader.by_ref().take(value_len).read_to_end(&mut bytes)?;
+            if bytes.len() as u64 != value_len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let value = V::from_bytes(Cow::Owned(bytes))
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            entries.push((index, value));
+        }
+
+        Ok(Self::from_raw_parts(proof, entries, length))
+    }
+}
@@ -16,14 +16,25 @@
 
 #![allow(clippy::too_many_lines)]
 
-use exonum_crypto::Hash;
+use assert_matches::assert_matches;
+use exonum_crypto::{Hash, HashStream};
 use rand::{thread_rng, Rng};
 use serde_json::{self, json};
 
-use std::cmp;
-
-use super::{key::ProofListKey, tree_height_by_length, ListProof, ListProofError, ProofListIndex};
-use crate::{access::CopyAccessExt, BinaryValue, Database, HashTag, ObjectHash, TemporaryDB};
+use std::{cmp, io};
+
+use super::{
+    height_of_len,
+    key::{ProofListKey, MAX_INDEX},
+    proof_branch_keys, verify_checkpoint_chain, CachedProofList, Hasher, IntegrityError,
+    ListCheckpoint, ListHasher, ListProof, ListProofError, MemoryProofList, ProofListError,
+    ProofListIndex,
+};
+use crate::{
+    access::{AccessErrorKind, CopyAccessExt, FromAccess},
+    BinaryValue, Database, DbOptions, HashTag, ObjectHash, RocksDB, SystemSchema, TemporaryDB,
+    ValidationError,
+};
 
 const IDX_NAME: &str = "idx_name";
 
@@ -66,6 +77,8 @@ fn list_methods() {
     assert_eq!(index.get(0), Some(vec![1]));
     assert_eq!(index.get(1), Some(vec![2]));
     assert_eq!(index.get(2), Some(vec![3]));
+    assert_eq!(index.first(), Some(vec![1]));
+    assert_eq!(index.last(), Some(vec![3]));
 }
 
 #[test]
@@ -149,6 +162,118 @@ fn extend_is_equivalent_to_sequential_pushes() {
     }
 }
 
+#[test]
+fn extending_large_list_is_equivalent_to_sequential_pushes() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    let mut reference = fork.get_proof_list("reference");
+
+    for &(initial_len, extension_len) in &[(0_u64, 1000_u64), (1, 1023), (511, 513), (1000, 3)] {
+        list.clear();
+        reference.clear();
+        list.extend(0..initial_len);
+        reference.extend(0..initial_len);
+
+        list.extend(initial_len..initial_len + extension_len);
+        for value in initial_len..initial_len + extension_len {
+            reference.push(value);
+        }
+        assert_eq!(list.len(), reference.len());
+        assert_eq!(list.object_hash(), reference.object_hash());
+        assert_eq!(
+            list.get_range_proof(initial_len / 2..),
+            reference.get_range_proof(initial_len / 2..)
+        );
+    }
+}
+
+#[test]
+fn extending_from_slice_is_equivalent_to_sequential_pushes() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    let mut reference = fork.get_proof_list("reference");
+
+    for &(initial_len, extension_len) in &[(0_u64, 0_u64), (0, 1000), (1, 1023), (511, 513), (7, 0)]
+    {
+        list.clear();
+        reference.clear();
+        list.extend(0..initial_len);
+        reference.extend(0..initial_len);
+
+        let values: Vec<_> = (initial_len..initial_len + extension_len).collect();
+        list.extend_from_slice(&values);
+        for &value in &values {
+            reference.push(value);
+        }
+        assert_eq!(list.len(), reference.len());
+        assert_eq!(list.object_hash(), reference.object_hash());
+        assert!(list.iter().eq(0..initial_len + extension_len));
+    }
+}
+
+#[test]
+fn building_list_from_iterator() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let list_hash = ProofListIndex::build_from(&fork, IDX_NAME, 0_u64..100);
+    let mut expected_list = fork.get_proof_list("expected");
+    for i in 0_u64..100 {
+        expected_list.push(i);
+    }
+    assert_eq!(list_hash, expected_list.object_hash());
+    let list = fork.get_proof_list::<_, u64>(IDX_NAME);
+    assert!(list.structurally_eq(&expected_list));
+
+    let list_hash = ProofListIndex::<_, u64>::build_from(&fork, "empty", vec![]);
+    assert_eq!(list_hash, HashTag::empty_list_hash());
+}
+
+#[test]
+fn appending_lists() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    fork.get_proof_list(IDX_NAME).extend(0_u64..1000);
+    for &split_point in &[0_u64, 1, 511, 512, 513, 999, 1000] {
+        let mut list = fork.get_proof_list("first");
+        let mut other = fork.get_proof_list("second");
+        list.clear();
+        other.clear();
+        list.extend(0..split_point);
+        other.extend(split_point..1000);
+
+        list.append_list(&other);
+        assert_eq!(list.len(), 1000);
+        assert_eq!(other.len(), 1000 - split_point);
+        let original = fork.get_proof_list::<_, u64>(IDX_NAME);
+        assert_eq!(list.object_hash(), original.object_hash());
+        assert!(list.structurally_eq(&original));
+    }
+}
+
+#[test]
+fn structural_equality() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    fork.get_proof_list(IDX_NAME).extend(vec![1_u32, 2, 3]);
+    db.merge(fork.into_patch()).unwrap();
+
+    let snapshot = db.snapshot();
+    let fork = db.fork();
+    let snapshot_list = snapshot.get_proof_list::<_, u32>(IDX_NAME);
+    let mut fork_list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert!(fork_list.structurally_eq(&snapshot_list));
+    assert!(snapshot_list.structurally_eq(&fork_list));
+
+    fork_list.set(1, 5);
+    assert!(!fork_list.structurally_eq(&snapshot_list));
+    fork_list.set(1, 2);
+    assert!(fork_list.structurally_eq(&snapshot_list));
+    fork_list.push(4);
+    assert!(!fork_list.structurally_eq(&snapshot_list));
+}
+
 #[test]
 fn tree_height() {
     let db = TemporaryDB::new();
@@ -164,6 +289,33 @@ fn tree_height() {
     assert_eq!(index.height(), 3);
     index.push(vec![4]);
     assert_eq!(index.height(), 3);
+    index.push(vec![5]);
+    assert_eq!(index.height(), 4);
+}
+
+#[test]
+fn tree_height_by_length() {
+    let expected_heights = [0, 1, 2, 3, 3, 4, 4, 4, 4, 5];
+    for (len, &height) in expected_heights.iter().enumerate() {
+        assert_eq!(height_of_len(len as u64), height, "len = {}", len);
+    }
+
+    assert_eq!(height_of_len(1 << 10), 11);
+    assert_eq!(height_of_len((1 << 10) + 1), 12);
+    assert_eq!(height_of_len(1 << 56), 57);
+    assert_eq!(height_of_len((1 << 40) + 1), 42);
+
+    // Lengths for which `len.next_power_of_two()` would overflow.
+    assert_eq!(height_of_len(1 << 63), 64);
+    assert_eq!(height_of_len((1 << 63) + 1), 65);
+    assert_eq!(height_of_len(u64::max_value()), 65);
+
+    for power in 2..64 {
+        let len = 1_u64 << power;
+        assert_eq!(height_of_len(len - 1), power + 1, "len = {}", len - 1);
+        assert_eq!(height_of_len(len), power + 1, "len = {}", len);
+        assert_eq!(height_of_len(len + 1), power + 2, "len = {}", len + 1);
+    }
 }
 
 #[test]
@@ -175,6 +327,7 @@ fn iter() {
     list_index.extend(vec![1_u8, 2, 3]);
 
     assert_eq!(list_index.iter().collect::<Vec<u8>>(), vec![1, 2, 3]);
+    assert_eq!(list_index.to_vec(), vec![1, 2, 3]);
     assert_eq!(list_index.iter_from(0).collect::<Vec<u8>>(), vec![1, 2, 3]);
     assert_eq!(list_index.iter_from(1).collect::<Vec<u8>>(), vec![2, 3]);
     assert_eq!(
@@ -183,6 +336,152 @@ fn iter() {
     );
 }
 
+#[test]
+fn iter_count_and_nth() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list_index = fork.get_proof_list(IDX_NAME);
+    assert_eq!(list_index.iter().count(), 0);
+    assert_eq!(list_index.iter().nth(0), None::<u32>);
+
+    list_index.extend(0_u32..10);
+    for start in 0..12 {
+        let naive_count = list_index.iter_from(start).fold(0, |acc, _| acc + 1);
+        assert_eq!(list_index.iter_from(start).count(), naive_count);
+    }
+    assert_eq!(list_index.iter_from(2).count(), 8);
+
+    for n in 0..12 {
+        let naive_nth = list_index.to_vec().get(n).copied();
+        assert_eq!(list_index.iter().nth(n), naive_nth);
+    }
+    assert_eq!(list_index.iter().nth(5), Some(5));
+    assert_eq!(list_index.iter().nth(usize::max_value()), None);
+
+    // `nth` and `next` can be interleaved.
+    let mut iter = list_index.iter_from(1);
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.nth(2), Some(4));
+    assert_eq!(iter.next(), Some(5));
+    assert_eq!(iter.next_back(), Some(9));
+    assert_eq!(iter.nth(3), None);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let mut iter = list_index.iter();
+    assert_eq!(iter.nth(3), Some(3));
+    assert_eq!(iter.count(), 6);
+}
+
+#[test]
+fn iter_exact_size() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list_index = fork.get_proof_list(IDX_NAME);
+    assert_eq!(list_index.iter().len(), 0);
+
+    list_index.extend(0_u32..10);
+    assert_eq!(list_index.iter().len(), 10);
+    assert_eq!(list_index.iter_from(3).len(), 7);
+    assert_eq!(list_index.iter_from(10).len(), 0);
+    assert_eq!(list_index.iter_from(1 << 60).len(), 0);
+
+    let mut iter = list_index.iter_from(2);
+    for expected_len in (0..8).rev() {
+        assert!(iter.next().is_some());
+        assert_eq!(iter.len(), expected_len);
+        assert_eq!(iter.size_hint(), (expected_len, Some(expected_len)));
+    }
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.len(), 0);
+
+    let mut iter = list_index.iter();
+    iter.next_back();
+    iter.nth(2);
+    assert_eq!(iter.len(), 6);
+    let values: Vec<_> = iter.collect();
+    assert_eq!(values, vec![3, 4, 5, 6, 7, 8]);
+}
+
+#[test]
+fn binary_search() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    assert_eq!(list.binary_search_by(|probe: &u32| probe.cmp(&1)), Err(0));
+
+    for len in 1..40 {
+        let values: Vec<u32> = (0..len).map(|i| i * 2 + 1).collect();
+        list.clear();
+        list.extend(values.iter().copied());
+
+        for target in 0..=len * 2 + 1 {
+            let expected = values
+                .binary_search(&target)
+                .map(|i| i as u64)
+                .map_err(|i| i as u64);
+            assert_eq!(list.binary_search_by(|probe| probe.cmp(&target)), expected);
+        }
+    }
+}
+
+#[test]
+fn iter_with_indexes() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list_index = fork.get_proof_list(IDX_NAME);
+    assert_eq!(list_index.iter_with_indexes().next(), None::<(u64, u8)>);
+
+    list_index.extend(vec![1_u8, 2, 3]);
+    assert_eq!(
+        list_index.iter_with_indexes().collect::<Vec<_>>(),
+        vec![(0, 1), (1, 2), (2, 3)]
+    );
+    assert_eq!(
+        list_index.iter_with_indexes_from(2).collect::<Vec<_>>(),
+        vec![(2, 3)]
+    );
+    assert_eq!(list_index.iter_with_indexes_from(3).count(), 0);
+
+    // Branch hashes must not be returned by the iterator.
+    let key = ProofListKey::new(1, 0).as_db_key();
+    assert_eq!(list_index.iter_with_indexes_from(key).count(), 0);
+}
+
+#[test]
+fn reverse_iter() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list_index = fork.get_proof_list(IDX_NAME);
+    assert_eq!(list_index.iter().next_back(), None::<u32>);
+
+    list_index.extend(0_u32..10);
+    assert!(list_index.iter().rev().eq((0..10).rev()));
+    assert!(list_index.iter_from(7).rev().eq(vec![9, 8, 7]));
+    assert_eq!(list_index.iter_from(10).next_back(), None);
+    assert_eq!(list_index.iter_from(1 << 60).next_back(), None);
+
+    // Alternate iteration from both ends.
+    for len in 0..10 {
+        list_index.clear();
+        list_index.extend(0..len);
+        let mut iter = list_index.iter();
+        let mut items = vec![];
+        loop {
+            match (iter.next(), iter.next_back()) {
+                (Some(x), Some(y)) => items.extend_from_slice(&[x, y]),
+                (Some(x), None) => items.push(x),
+                (None, None) => break,
+                (None, Some(_)) => panic!("Iterator is exhausted from the front only"),
+            }
+        }
+        items.sort_unstable();
+        assert_eq!(items, (0..len).collect::<Vec<_>>());
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+}
+
 #[test]
 fn iter_from_with_large_index() {
     let db = TemporaryDB::new();
@@ -227,7 +526,7 @@ fn simple_proof() {
 
     let proof = index.get_proof(0);
     assert_eq!(proof, {
-        let mut proof = ListProof::new(vec![(0, 2_u64)], index.len());
+        let mut proof: ListProof<_> = ListProof::new(vec![(0, 2_u64)], index.len());
         proof.push_hash(1, 1, h1);
         proof
     });
@@ -237,7 +536,7 @@ fn simple_proof() {
 
     let proof = index.get_proof(1);
     assert_eq!(proof, {
-        let mut proof = ListProof::new(vec![(1, 4_u64)], index.len());
+        let mut proof: ListProof<_> = ListProof::new(vec![(1, 4_u64)], index.len());
         proof.push_hash(1, 0, h0);
         proof
     });
@@ -264,7 +563,7 @@ fn simple_proof() {
 
     let proof = index.get_proof(0);
     assert_eq!(proof, {
-        let mut proof = ListProof::new(vec![(0, 2_u64)], index.len());
+        let mut proof: ListProof<_> = ListProof::new(vec![(0, 2_u64)], index.len());
         proof.push_hash(1, 1, h1);
         proof.push_hash(2, 1, h22);
         proof
@@ -279,7 +578,7 @@ fn simple_proof() {
 
     let proof = index.get_range_proof(1..3);
     assert_eq!(proof, {
-        let mut proof = ListProof::new(vec![(1, 4_u64), (2, 6_u64)], index.len());
+        let mut proof: ListProof<_> = ListProof::new(vec![(1, 4_u64), (2, 6_u64)], index.len());
         proof.push_hash(1, 0, h0);
         proof
     });
@@ -293,7 +592,7 @@ fn simple_proof() {
 
     let proof = index.get_range_proof(0..2);
     assert_eq!(proof, {
-        let mut proof = ListProof::new(vec![(0, 2_u64), (1, 4_u64)], index.len());
+        let mut proof: ListProof<_> = ListProof::new(vec![(0, 2_u64), (1, 4_u64)], index.len());
         proof.push_hash(2, 1, h22);
         proof
     });
@@ -306,6 +605,28 @@ fn simple_proof() {
     );
 }
 
+#[test]
+fn checked_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+
+    let err = index.get_proof_checked(0).unwrap_err();
+    assert_eq!(err, ProofListError::IndexOutOfBounds { index: 0, len: 0 });
+
+    index.extend(0_u32..5);
+    for i in 0..5 {
+        let proof = index.get_proof_checked(i).unwrap();
+        assert_eq!(proof, index.get_proof(i));
+        let checked_proof = proof.check_against_hash(index.object_hash()).unwrap();
+        assert_eq!(*checked_proof.entries(), [(i, i as u32)]);
+    }
+    for &i in &[5, 100, u64::max_value()] {
+        let err = index.get_proof_checked(i).unwrap_err();
+        assert_eq!(err, ProofListError::IndexOutOfBounds { index: i, len: 5 });
+    }
+}
+
 #[test]
 fn proofs_in_empty_list() {
     let db = TemporaryDB::new();
@@ -371,6 +692,96 @@ fn random_proofs() {
     }
 }
 
+#[test]
+fn random_multiproofs() {
+    const LIST_SIZE: u64 = 1 << 10;
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+    let mut rng = thread_rng();
+    let values = random_values(&mut rng, LIST_SIZE as usize);
+    index.extend(values.iter().cloned());
+    let index_hash = index.object_hash();
+
+    for _ in 0..20 {
+        let count = rng.gen_range(1, 20);
+        let indexes: Vec<_> = (0..count).map(|_| rng.gen_range(0, LIST_SIZE)).collect();
+        let proof = index.get_multiproof(indexes.clone());
+        let checked_proof = proof.check_against_hash(index_hash).unwrap();
+
+        let mut expected_indexes = indexes;
+        expected_indexes.sort_unstable();
+        expected_indexes.dedup();
+        assert!(checked_proof.indexes().eq(expected_indexes.iter().copied()));
+        for (i, value) in checked_proof.entries() {
+            assert_eq!(*value, values[*i as usize]);
+        }
+    }
+}
+
+#[test]
+fn multiproofs_for_contiguous_indexes() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+
+    for len in 1..20 {
+        index.clear();
+        index.extend(0..len);
+        for start in 0..len {
+            for end in start + 1..=len {
+                let proof = index.get_multiproof((start..end).rev());
+                assert_eq!(proof, index.get_range_proof(start..end));
+            }
+        }
+    }
+}
+
+#[test]
+fn multiproofs_of_absence() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+
+    let proof = index.get_multiproof(vec![0, 1]);
+    proof.check_against_hash(index.object_hash()).unwrap();
+    assert!(proof.entries_unchecked().is_empty());
+
+    index.extend(0_u64..10);
+    for indexes in &[vec![], vec![10], vec![1, 2, 10], vec![5, u64::max_value()]] {
+        let proof = index.get_multiproof(indexes.iter().copied());
+        let checked_proof = proof.check_against_hash(index.object_hash()).unwrap();
+        assert!(checked_proof.entries().is_empty());
+        assert_eq!(checked_proof.list_len(), 10);
+    }
+}
+
+#[test]
+fn unchecked_proof_size() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+    index.extend(0_u32..10);
+
+    let proof = index.get_range_proof(2..7);
+    assert_eq!(proof.indexes_len(), 5);
+    assert!(proof.is_range());
+    let proof = index.get_proof(3);
+    assert_eq!(proof.indexes_len(), 1);
+    assert!(proof.is_range());
+    let proof = index.get_multiproof(vec![1, 2, 5]);
+    assert_eq!(proof.indexes_len(), 3);
+    assert!(!proof.is_range());
+    let proof = index.get_proof(10);
+    assert_eq!(proof.indexes_len(), 0);
+    assert!(!proof.is_range());
+
+    let proof: ListProof<_> = ListProof::new(vec![(u64::max_value(), 0_u32), (0, 0)], 10);
+    assert_eq!(proof.indexes_len(), 2);
+    assert!(!proof.is_range());
+}
+
 #[test]
 fn proofs_with_overly_large_indexes() {
     const LARGE_INDEXES: &[u64] = &[
@@ -592,99 +1003,1652 @@ fn proof_illegal_inclusive_range() {
 }
 
 #[test]
-fn ranges_work_similar_to_vec_slicing() {
-    use std::{
-        fmt,
-        ops::RangeBounds,
-        panic::{self, UnwindSafe},
-        slice::SliceIndex,
-    };
-
-    #[allow(clippy::needless_pass_by_value)] // references to ranges look awkward
-    fn check_bounds(
-        v: &[u32],
-        slice_bounds: impl SliceIndex<[u32], Output = [u32]> + Clone + UnwindSafe + fmt::Debug,
-        index_bounds: impl RangeBounds<u64> + UnwindSafe,
-    ) {
-        let panic_hook = panic::take_hook();
-        panic::set_hook(Box::new(|_| {}));
+fn illegal_range_errors() {
+    use std::ops::Bound;
 
-        let slice_bounds_ = slice_bounds.clone();
-        let slicing_res = panic::catch_unwind(|| v[slice_bounds_].to_vec());
-        let proof_res = panic::catch_unwind(|| {
-            let fork = TemporaryDB::new().fork();
-            let mut index = fork.get_proof_list(IDX_NAME);
-            index.extend(v.to_vec());
-            (index.object_hash(), index.get_range_proof(index_bounds))
-        });
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+    index.extend(vec![1_u8, 2, 4]);
 
-        panic::set_hook(panic_hook);
+    let err = index.try_get_range_proof(2..1).unwrap_err();
+    assert_eq!(err, ProofListError::IllegalRange { start: 2, end: 1 });
+    assert_eq!(
+        err.to_string(),
+        "Illegal range boundaries: the range start is 2, but the range end is 1"
+    );
+    let err = index.try_get_range_proof(2..=0).unwrap_err();
+    assert_eq!(err, ProofListError::IllegalRange { start: 2, end: 1 });
+    let err = index
+        .try_get_range_proof((Bound::Excluded(1), Bound::Excluded(1)))
+        .unwrap_err();
+    assert_eq!(err, ProofListError::IllegalRange { start: 2, end: 1 });
+
+    // Legal ranges.
+    let proof = index.try_get_range_proof(1..1).unwrap();
+    assert!(proof.entries_unchecked().is_empty());
+    let proof = index.try_get_range_proof(5..).unwrap();
+    assert!(proof.entries_unchecked().is_empty());
+    let proof = index.try_get_range_proof(1..).unwrap();
+    assert_eq!(proof, index.get_range_proof(1..));
+}
 
-        if let Ok((index_hash, proof)) = proof_res {
-            let checked_proof = proof.check_against_hash(index_hash).unwrap();
+#[test]
+#[cfg(feature = "rayon")]
+fn parallel_and_serial_hashing_produce_same_roots() {
+    use super::PARALLEL_CHUNK_PAIRS;
 
-            match slicing_res {
-                Ok(slice) => {
-                    assert_eq!(
-                        checked_proof
-                            .entries()
-                            .iter()
-                            .map(|(_, value)| *value)
-                            .collect::<Vec<_>>(),
-                        slice
-                    );
-                }
-                Err(e) => {
-                    // Slicing is more strict if the range does not completely fit
-                    // in the slice indexes.
-                    assert!(
-                        e.downcast_ref::<String>().unwrap().contains("out of range"),
-                        "{:?}",
-                        slice_bounds
-                    );
-                }
-            }
-        } else {
-            assert!(slicing_res.is_err(), "{:?}", slice_bounds);
-        }
+    // Lists extended in bulk are hashed in parallel, while pushing elements one by one
+    // updates the tree serially.
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    // The first level spans several chunks of pairs hashed in parallel.
+    let len = 2 * PARALLEL_CHUNK_PAIRS as u64 + 3;
+    let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+    list.extend(0..len);
+    let mut serial_list = fork.get_proof_list::<_, u64>("serial");
+    for i in 0..len {
+        serial_list.push(i);
     }
+    assert_eq!(list.object_hash(), serial_list.object_hash());
 
-    let v = vec![1, 2, 3, 4, 5];
-    check_bounds(&v, .., ..);
-    for start in 0_usize..10 {
-        for end in 0_usize..10 {
-            check_bounds(&v, start..end, (start as u64)..(end as u64));
-            check_bounds(&v, start..=end, (start as u64)..=(end as u64));
-            if start == 0 {
-                check_bounds(&v, ..end, ..(end as u64));
-                check_bounds(&v, ..=end, ..=(end as u64));
-            }
-            if end == 0 && start <= v.len() {
-                check_bounds(&v, start.., (start as u64)..);
-            }
-        }
+    // Extending a non-empty list.
+    list.extend(len..2 * len);
+    for i in len..2 * len {
+        serial_list.push(i);
     }
+    assert_eq!(list.object_hash(), serial_list.object_hash());
 }
 
 #[test]
-fn proof_with_range_start_exceeding_list_size() {
+fn object_hash_is_list_hash() {
     let db = TemporaryDB::new();
     let fork = db.fork();
-    let mut index = fork.get_proof_list(IDX_NAME);
-    for i in 0_u8..4 {
-        index.push(vec![i]);
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.object_hash(), list.list_hash());
+    assert_eq!(list.list_hash(), HashTag::empty_list_hash());
+
+    for i in 0..10 {
+        list.push(i);
+        assert_eq!(list.object_hash(), list.list_hash());
+        assert_eq!(
+            list.list_hash(),
+            HashTag::hash_list_node(list.len(), list.merkle_root())
+        );
     }
+    list.truncate(3);
+    assert_eq!(list.object_hash(), list.list_hash());
+
+    let mut list =
+        ProofListIndex::<_, u32, PrefixedHasher>::from_access(&fork, CUSTOM_HASHER_ADDR.into())
+            .unwrap();
+    list.extend(0..5);
+    assert_eq!(list.object_hash(), list.list_hash());
+    assert_eq!(
+        list.list_hash(),
+        PrefixedHasher::hash_list_node(list.len(), list.merkle_root())
+    );
+}
 
-    let proof = index.get_range_proof(8..10_000_000);
-    assert!(proof
-        .check_against_hash(index.object_hash())
-        .unwrap()
-        .entries()
-        .is_empty());
+#[test]
+fn diff_between_lists() {
+    fn naive_diff(first: &[u32], second: &[u32]) -> Vec<u64> {
+        let max_len = cmp::max(first.len(), second.len());
+        (0..max_len)
+            .filter(|&i| first.get(i) != second.get(i))
+            .map(|i| i as u64)
+            .collect()
+    }
 
-    let proof = index.get_range_proof(8..);
-    assert!(proof
-        .check_against_hash(index.object_hash())
+    const LENGTHS: &[(u32, u32)] = &[(0, 0), (0, 5), (1, 1), (7, 7), (8, 9), (33, 20), (64, 100)];
+
+    let db = TemporaryDB::new();
+    let mut rng = thread_rng();
+
+    for &(first_len, second_len) in LENGTHS {
+        let fork = db.fork();
+        let mut first = fork.get_proof_list::<_, u32>(IDX_NAME);
+        let mut second = fork.get_proof_list::<_, u32>("other");
+        first.extend(0..first_len);
+        second.extend(0..second_len);
+
+        let common_len = cmp::min(first_len, second_len);
+        for _ in 0..3 {
+            if common_len > 0 {
+                let index = rng.gen_range(0, common_len);
+                second.set(u64::from(index), rng.gen_range(1_000, 2_000));
+            }
+
+            let expected = naive_diff(&first.to_vec(), &second.to_vec());
+            assert_eq!(first.diff(&second), expected);
+            assert_eq!(second.diff(&first), expected);
+        }
+        assert!(first.diff(&first).is_empty());
+    }
+}
+
+#[test]
+fn computing_roots_of_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+
+    let proof = list.get_range_proof(..);
+    assert_eq!(proof.compute_root().unwrap(), (0, list.object_hash()));
+
+    list.extend(0..20);
+    let proofs = vec![
+        list.get_proof(5),
+        list.get_proof(100),
+        list.get_range_proof(3..15),
+        list.get_multiproof(vec![1, 7, 19]),
+    ];
+    for proof in &proofs {
+        assert_eq!(proof.compute_root().unwrap(), (20, list.object_hash()));
+    }
+
+    // Malformed proof: a hash required to restore the root is missing.
+    let proof = ListProof::<u32>::new(vec![(1, 1)], 20);
+    assert_matches!(
+        proof.compute_root(),
+        Err(ListProofError::MissingHash { height: 1 })
+    );
+    // Malformed proof: the same element is proven twice.
+    let proof = ListProof::<u32>::new(vec![(1, 1), (1, 1)], 2);
+    assert_matches!(proof.compute_root(), Err(ListProofError::Unordered));
+}
+
+#[test]
+fn cached_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = CachedProofList::new(fork.get_proof_list::<_, u32>(IDX_NAME), 3);
+    list.list_mut().extend(0..10);
+
+    let proof = list.get_proof(5);
+    assert_eq!(list.cached_len(), 1);
+    let cached_proof = list.get_proof(5);
+    assert_eq!(list.cached_len(), 1);
+    assert_eq!(cached_proof, proof);
+    assert_eq!(cached_proof, list.list().get_proof(5));
+
+    // Least recently used proofs are evicted.
+    for i in 6..9 {
+        list.get_proof(i);
+    }
+    assert_eq!(list.cached_len(), 3);
+    assert_eq!(list.get_proof(5), list.list().get_proof(5));
+
+    // Mutating the list invalidates the cache.
+    list.list_mut().push(10);
+    let proof = list.get_proof(5);
+    assert_eq!(list.cached_len(), 1);
+    assert_eq!(proof, list.list().get_proof(5));
+    assert_ne!(proof, cached_proof);
+    proof.check_against_hash(list.list().object_hash()).unwrap();
+    assert!(cached_proof
+        .check_against_hash(list.list().object_hash())
+        .is_err());
+}
+
+#[test]
+fn iter_range() {
+    use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+    const CASES: &[((Bound<u64>, Bound<u64>), &[u64])] = &[
+        ((Unbounded, Unbounded), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]),
+        ((Included(3), Excluded(6)), &[3, 4, 5]),
+        ((Included(3), Included(6)), &[3, 4, 5, 6]),
+        ((Excluded(3), Included(6)), &[4, 5, 6]),
+        ((Unbounded, Included(0)), &[0]),
+        ((Included(8), Unbounded), &[8, 9]),
+        ((Included(8), Included(u64::max_value())), &[8, 9]),
+        ((Excluded(u64::max_value()), Unbounded), &[]),
+        ((Included(5), Excluded(5)), &[]),
+        ((Included(5), Excluded(3)), &[]),
+        ((Included(10), Unbounded), &[]),
+    ];
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+    list.extend(0..10);
+
+    for (range, expected) in CASES {
+        let values: Vec<_> = list.iter_range(*range).collect();
+        assert_eq!(values, *expected, "range = {:?}", range);
+        assert_eq!(list.iter_range(*range).len(), expected.len());
+        let mut reversed: Vec<_> = list.iter_range(*range).rev().collect();
+        reversed.reverse();
+        assert_eq!(reversed, *expected, "range = {:?}", range);
+    }
+}
+
+#[test]
+fn set_many_is_equivalent_to_sequential_sets() {
+    const CASES: &[(u64, u64)] = &[(0, 1), (0, 10), (3, 4), (4, 9), (9, 1), (5, 0), (1, 8)];
+
+    let db = TemporaryDB::new();
+    for &(start, count) in CASES {
+        let fork = db.fork();
+        let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+        let mut expected_list = fork.get_proof_list::<_, u64>("expected");
+        list.extend(0..10);
+        expected_list.extend(0..10);
+
+        let values = (0..count).map(|i| 100 + i);
+        list.set_many(start, values.clone());
+        for (index, value) in (start..).zip(values) {
+            expected_list.set(index, value);
+        }
+
+        assert_eq!(list.to_vec(), expected_list.to_vec());
+        assert_eq!(list.object_hash(), expected_list.object_hash());
+    }
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds")]
+fn set_many_out_of_bounds() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+    list.extend(0..10);
+    list.set_many(8, vec![1, 2, 3]);
+}
+
+#[test]
+fn last_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+
+    let proof = list.last_proof();
+    assert_eq!(proof, list.get_proof(0));
+    assert!(proof.entries_unchecked().is_empty());
+    proof.check_against_hash(list.object_hash()).unwrap();
+
+    for len in 1..=70 {
+        list.push(len);
+        let proof = list.last_proof();
+        assert_eq!(proof, list.get_proof(u64::from(len) - 1));
+        let checked_proof = proof.check_against_hash(list.object_hash()).unwrap();
+        assert_eq!(*checked_proof.entries(), [(u64::from(len) - 1, len)]);
+    }
+}
+
+#[test]
+fn prefix_hashes() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.prefix_hash(0), HashTag::empty_list_hash());
+    assert_eq!(list.prefix_hash(5), HashTag::empty_list_hash());
+
+    let mut historic_hashes = vec![list.list_hash()];
+    for i in 0..70 {
+        list.push(i);
+        historic_hashes.push(list.list_hash());
+    }
+
+    for (len, expected_hash) in historic_hashes.iter().enumerate() {
+        assert_eq!(
+            list.prefix_hash(len as u64),
+            *expected_hash,
+            "len = {}",
+            len
+        );
+    }
+    assert_eq!(list.prefix_hash(100), list.list_hash());
+    assert_eq!(list.prefix_hash(u64::max_value()), list.list_hash());
+}
+
+#[test]
+fn owned_list_iteration() {
+    use std::rc::Rc;
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    fork.get_proof_list(IDX_NAME).extend(0_u64..200);
+    db.merge(fork.into_patch()).unwrap();
+
+    // The index owns the snapshot, so it can be returned from a function or stored.
+    let snapshot: Rc<dyn crate::Snapshot> = Rc::from(db.snapshot());
+    let open_list = || -> ProofListIndex<Rc<dyn crate::Snapshot>, u64> {
+        ProofListIndex::from_access(Rc::clone(&snapshot), IDX_NAME.into()).unwrap()
+    };
+
+    let mut values = vec![];
+    for value in open_list() {
+        values.push(value);
+    }
+    assert_eq!(values, (0..200).collect::<Vec<_>>());
+
+    let iter = open_list().into_iter();
+    assert_eq!(iter.len(), 200);
+    assert!(iter.rev().eq((0..200).rev()));
+
+    // Iteration from both ends.
+    let mut iter = open_list().into_iter();
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(199));
+    assert_eq!(iter.len(), 198);
+    let rest: Vec<_> = iter.by_ref().rev().take(150).collect();
+    assert_eq!(rest, (49..199).rev().collect::<Vec<_>>());
+    assert!(iter.eq(1..49));
+}
+
+#[test]
+fn index_bounds() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert!(!list.contains_index(0));
+    assert_eq!(list.valid_range(), 0..0);
+
+    list.extend(0..5);
+    assert!(list.valid_range().all(|i| list.contains_index(i)));
+    assert!(!list.contains_index(5));
+    assert!(!list.contains_index(u64::max_value()));
+    assert_eq!(list.valid_range(), 0..5);
+
+    list.truncate(2);
+    assert!(!list.contains_index(2));
+    assert_eq!(list.valid_range(), 0..2);
+}
+
+#[test]
+fn fuzzed_proof_roundtrips() {
+    use proptest::{collection::vec, prop_assert, proptest};
+
+    use super::fuzz::fuzz_proof_roundtrip;
+
+    proptest!(|(elements in vec(0_u32..1_000, 0..100), start in 0_u64..120, end in 0_u64..120)| {
+        prop_assert!(fuzz_proof_roundtrip(elements, start..end));
+    });
+}
+
+#[test]
+fn inequality_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.extend(vec![10, 20, 30]);
+
+    let proof = list.get_inequality_proof(2, &20).unwrap();
+    assert_eq!(proof, list.get_proof(2));
+    let checked_proof = proof.check_against_hash(list.object_hash()).unwrap();
+    assert_eq!(*checked_proof.entries(), [(2, 30)]);
+
+    assert_eq!(
+        list.get_inequality_proof(1, &20).unwrap_err(),
+        ProofListError::EqualValue { index: 1 }
+    );
+    assert_eq!(
+        list.get_inequality_proof(3, &20).unwrap_err(),
+        ProofListError::IndexOutOfBounds { index: 3, len: 3 }
+    );
+}
+
+#[test]
+fn pushing_prehashed_values() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    let mut expected_list = fork.get_proof_list::<_, u32>("expected");
+
+    for i in 0..20 {
+        list.push_prehashed(i, HashTag::hash_leaf(&i.to_bytes()));
+        expected_list.push(i);
+        assert_eq!(list.object_hash(), expected_list.object_hash());
+    }
+    assert_eq!(list.to_vec(), expected_list.to_vec());
+    list.get_range_proof(5..12)
+        .check_against_hash(list.object_hash())
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Supplied leaf hash does not match")]
+fn pushing_value_with_wrong_hash() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.push_prehashed(1, HashTag::hash_leaf(&2_u32.to_bytes()));
+}
+
+#[test]
+fn stale_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.extend(0..10);
+
+    let element_proof = list.get_proof(8);
+    let absence_proof = list.get_proof(20);
+    let range_proof = list.get_range_proof(2..5);
+    for proof in &[&element_proof, &absence_proof, &range_proof] {
+        assert!(!proof.is_stale_against(list.len()));
+    }
+
+    list.pop();
+    for proof in &[&element_proof, &absence_proof, &range_proof] {
+        assert!(proof.is_stale_against(list.len()));
+        assert!(proof.check_against_hash(list.object_hash()).is_err());
+    }
+
+    // Proofs for the current list state are not stale.
+    assert!(!list.get_proof(8).is_stale_against(list.len()));
+    list.truncate(0);
+    assert!(!list.get_proof(0).is_stale_against(list.len()));
+}
+
+#[test]
+fn incremental_list_hashing() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    let mut hasher = ListHasher::<HashTag>::new();
+    assert_eq!(hasher.list_hash(), list.list_hash());
+
+    for i in 0..100 {
+        list.push(i);
+        hasher.append_hash(HashTag::hash_leaf(&i.to_bytes()));
+        assert_eq!(hasher.len(), list.len());
+        assert_eq!(hasher.merkle_root(), list.merkle_root());
+        assert_eq!(hasher.list_hash(), list.list_hash());
+
+        // Hasher initialized from the list produces the same hashes.
+        let mut list_hasher = list.list_hasher();
+        assert_eq!(list_hasher.list_hash(), list.list_hash());
+        list_hasher.append_value(&1_000);
+        let mut expected_hasher = hasher.clone();
+        expected_hasher.append_value(&1_000);
+        assert_eq!(list_hasher.list_hash(), expected_hasher.list_hash());
+    }
+
+    let mut list =
+        ProofListIndex::<_, u32, PrefixedHasher>::from_access(&fork, CUSTOM_HASHER_ADDR.into())
+            .unwrap();
+    let mut hasher = ListHasher::<PrefixedHasher>::default();
+    for i in 0..20 {
+        list.push(i);
+        hasher.append_value(&i);
+        assert_eq!(hasher.list_hash(), list.list_hash());
+    }
+}
+
+#[test]
+fn list_as_fixed_vector() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.get_or_default(0), 0);
+
+    list.resize(5, 0);
+    assert_eq!(list.to_vec(), vec![0; 5]);
+    list.set(3, 42);
+    assert_eq!(list.get_or_default(3), 42);
+    assert_eq!(list.get_or_default(5), 0);
+    assert_eq!(list.get_or_default(u64::max_value()), 0);
+
+    list.resize(8, 7);
+    assert_eq!(list.to_vec(), vec![0, 0, 0, 42, 0, 7, 7, 7]);
+    list.resize(8, 1);
+    assert_eq!(list.len(), 8);
+
+    list.resize(4, 0);
+    let mut expected_list = fork.get_proof_list::<_, u32>("expected");
+    expected_list.extend(vec![0, 0, 0, 42]);
+    assert_eq!(list.object_hash(), expected_list.object_hash());
+    assert_eq!(list.get_or_default(5), 0);
+}
+
+#[test]
+fn length_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+
+    let (len, merkle_root) = list.length_proof();
+    assert_eq!((len, merkle_root), (0, Hash::zero()));
+    assert!(HashTag::verify_length(len, merkle_root, list.object_hash()));
+
+    list.extend(0..10);
+    let (len, merkle_root) = list.length_proof();
+    assert_eq!(len, 10);
+    assert!(HashTag::verify_length(len, merkle_root, list.object_hash()));
+    assert!(!HashTag::verify_length(9, merkle_root, list.object_hash()));
+    assert!(!HashTag::verify_length(
+        len,
+        Hash::zero(),
+        list.object_hash()
+    ));
+}
+
+#[test]
+fn filling_list() {
+    let db = TemporaryDB::new();
+    for &len in &[0, 1, 2, 3, 4, 5, 7, 8, 9, 16, 33, 100] {
+        let fork = db.fork();
+        let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+        list.extend(vec![1, 2, 3]);
+        list.fill(len, 42);
+
+        let mut naive_list = fork.get_proof_list::<_, u32>("naive");
+        naive_list.extend((0..len).map(|_| 42));
+
+        assert_eq!(list.len(), len);
+        assert_eq!(list.to_vec(), naive_list.to_vec());
+        assert_eq!(list.object_hash(), naive_list.object_hash());
+        // All tree branches must be the same; otherwise, proofs would differ.
+        for i in 0..len {
+            assert_eq!(list.get_proof(i), naive_list.get_proof(i));
+        }
+        assert_eq!(list.get_range_proof(1..), naive_list.get_range_proof(1..));
+
+        // The list remains consistent after modifications.
+        list.push(5);
+        naive_list.push(5);
+        assert_eq!(list.object_hash(), naive_list.object_hash());
+    }
+}
+
+#[test]
+fn opening_list_with_wrong_index_type() {
+    use crate::{access::AccessErrorKind, IndexType};
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    fork.get_map::<_, u64, u64>(IDX_NAME).put(&1, 2);
+    fork.get_proof_list::<_, u64>("list").push(1);
+    db.merge(fork.into_patch()).unwrap();
+
+    let snapshot = db.snapshot();
+    let err = ProofListIndex::<_, u64>::from_access(&snapshot, IDX_NAME.into()).unwrap_err();
+    assert_matches!(
+        err.kind,
+        AccessErrorKind::WrongIndexType {
+            expected: IndexType::ProofList,
+            actual: IndexType::Map,
+        }
+    );
+
+    // Opening a proof list as another index type fails as well.
+    let err = crate::ListIndex::<_, u64>::from_access(&snapshot, "list".into()).unwrap_err();
+    assert_matches!(
+        err.kind,
+        AccessErrorKind::WrongIndexType {
+            expected: IndexType::List,
+            actual: IndexType::ProofList,
+        }
+    );
+}
+
+#[test]
+#[should_panic(expected = "Wrong index type: expected ProofList, but got Map")]
+fn opening_list_with_wrong_index_type_panics() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    fork.get_map::<_, u64, u64>(IDX_NAME).put(&1, 2);
+    fork.get_proof_list::<_, u64>(IDX_NAME);
+}
+
+#[test]
+fn range_proofs_with_len() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+
+    let (proof, len) = list.get_range_proof_with_len(..);
+    assert_eq!(len, 0);
+    assert_eq!(proof, list.get_range_proof(..));
+
+    list.extend(0..10);
+    for range in &[0..10, 3..5, 9..100, 20..30] {
+        let (proof, len) = list.get_range_proof_with_len(range.clone());
+        assert_eq!(len, 10);
+        assert_eq!(proof, list.get_range_proof(range.clone()));
+        let checked_proof = proof.check_against_hash(list.object_hash()).unwrap();
+        assert_eq!(checked_proof.list_len(), len);
+    }
+}
+
+#[test]
+fn skipping_in_iterator() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+    list.extend((0..2_000).map(|i| i * 3));
+
+    assert_eq!(list.iter().skip_to(1_000).next(), list.get(1_000));
+    assert_eq!(list.iter().skip_to(1_000).len(), 1_000);
+    assert_eq!(list.iter().skip_to(1_999).next(), list.get(1_999));
+    assert_eq!(list.iter().skip_to(2_000).next(), None);
+    assert_eq!(list.iter().skip_to(u64::max_value()).next(), None);
+
+    // Windowed reads.
+    let window: Vec<_> = list.iter_from(100).skip_to(500).take(3).collect();
+    assert_eq!(window, vec![1_500, 1_503, 1_506]);
+    let window: Vec<_> = list.iter_range(10..20).skip_to(15).collect();
+    assert_eq!(window, (15..20).map(|i| i * 3).collect::<Vec<_>>());
+
+    // Skipping back does not change the iterator position.
+    let mut iter = list.iter_from(10);
+    assert_eq!(iter.next(), Some(30));
+    let mut iter = iter.skip_to(5);
+    assert_eq!(iter.next(), Some(33));
+
+    // Skipping after iteration from the back.
+    let mut iter = list.iter();
+    assert_eq!(iter.next_back(), Some(1_999 * 3));
+    let mut iter = iter.skip_to(1_998);
+    assert_eq!(iter.next(), Some(1_998 * 3));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn verifying_integrity() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.verify_integrity().unwrap();
+
+    list.extend(0..100);
+    list.verify_integrity().unwrap();
+    list.set(17, 1_000);
+    list.verify_integrity().unwrap();
+    list.pop();
+    list.verify_integrity().unwrap();
+    list.truncate(33);
+    list.verify_integrity().unwrap();
+    list.push(5);
+    list.verify_integrity().unwrap();
+    list.truncate(1);
+    list.verify_integrity().unwrap();
+    list.clear();
+    list.verify_integrity().unwrap();
+}
+
+#[test]
+fn verifying_integrity_of_corrupted_list() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.extend(0..10);
+    list.verify_integrity().unwrap();
+
+    // Corrupt a list element without updating its hash.
+    list.base.put(&ProofListKey::leaf(3), 42_u32);
+    let err = list.verify_integrity().unwrap_err();
+    assert_eq!(
+        err,
+        IntegrityError::HashMismatch {
+            key: ProofListKey::new(1, 3)
+        }
+    );
+    list.set(3, 3);
+    list.verify_integrity().unwrap();
+
+    // Corrupt a branch hash.
+    let key = ProofListKey::new(2, 2);
+    list.base.put(&key, Hash::zero());
+    let err = list.verify_integrity().unwrap_err();
+    assert_eq!(err, IntegrityError::HashMismatch { key });
+
+    // Remove a list element.
+    let mut list = fork.get_proof_list::<_, u32>("other");
+    list.extend(0..5);
+    list.base.remove(&ProofListKey::leaf(4));
+    let err = list.verify_integrity().unwrap_err();
+    assert_eq!(
+        err,
+        IntegrityError::MissingEntry {
+            key: ProofListKey::leaf(4)
+        }
+    );
+}
+
+#[test]
+fn list_from_values() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let list: ProofListIndex<_, u32> = ProofListIndex::from_values(IDX_NAME, &fork, 0..10);
+    assert_eq!(list.to_vec(), (0..10).collect::<Vec<_>>());
+    list.verify_integrity().unwrap();
+    let list_hash = list.object_hash();
+
+    // Existing data is overwritten.
+    let list: ProofListIndex<_, u32> = ProofListIndex::from_values(IDX_NAME, &fork, vec![1, 2]);
+    assert_eq!(list.to_vec(), vec![1, 2]);
+    assert_ne!(list.object_hash(), list_hash);
+    list.verify_integrity().unwrap();
+
+    let list: ProofListIndex<_, u32> = ProofListIndex::from_values(IDX_NAME, &fork, 0..10);
+    assert_eq!(list.object_hash(), list_hash);
+    let list: ProofListIndex<_, u32> = ProofListIndex::from_values(IDX_NAME, &fork, vec![]);
+    assert!(list.is_empty());
+    assert_eq!(list.object_hash(), HashTag::empty_list_hash());
+}
+
+#[test]
+fn finding_position_of_element() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.position(|_| true), None);
+
+    list.extend(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+    assert_eq!(list.position(|&value| value == 1), Some(1));
+    assert_eq!(list.position(|&value| value > 4), Some(4));
+    assert_eq!(list.position(|&value| value == 6), Some(7));
+    assert_eq!(list.position(|&value| value == 7), None);
+
+    let mut visited = 0;
+    list.position(|&value| {
+        visited += 1;
+        value == 4
+    });
+    assert_eq!(visited, 3);
+}
+
+#[test]
+fn removing_ranges() {
+    const CASES: &[(u64, u64, u64)] = &[
+        (0, 10, 1),
+        (0, 10, 10),
+        (3, 5, 10),
+        (0, 1, 10),
+        (9, 10, 10),
+        (5, 100, 10),
+        (0, 100, 10),
+        (4, 4, 10),
+        (7, 3, 10),
+        (10, 20, 10),
+        (1, 31, 33),
+        (16, 17, 33),
+    ];
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    for &(start, end, len) in CASES {
+        let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+        list.clear();
+        list.extend(0..len);
+        list.remove_range(start..end);
+
+        let expected_values: Vec<_> = (0..len)
+            .filter(|&i| i < start || i >= cmp::max(start, end))
+            .collect();
+        assert_eq!(list.to_vec(), expected_values);
+        list.verify_integrity().unwrap();
+
+        let mut expected_list = fork.get_proof_list::<_, u64>("expected");
+        expected_list.clear();
+        expected_list.extend(expected_values);
+        assert_eq!(list.object_hash(), expected_list.object_hash());
+    }
+}
+
+#[test]
+fn reading_list_head() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.head(), (0, HashTag::empty_list_hash()));
+
+    list.extend(0..10);
+    assert_eq!(list.head(), (10, list.object_hash()));
+    // Read the head once more to use the cached root.
+    assert_eq!(list.head(), (10, list.object_hash()));
+    list.set(3, 100);
+    assert_eq!(list.head(), (10, list.object_hash()));
+    list.truncate(5);
+    assert_eq!(list.head(), (5, list.object_hash()));
+    list.push(1);
+    let head = list.head();
+    assert_eq!(head, (6, list.object_hash()));
+    list.clear();
+    assert_eq!(list.head(), (0, HashTag::empty_list_hash()));
+    list.extend(vec![0, 1, 2, 100, 4, 1]);
+    assert_eq!(list.head(), head);
+    drop(list);
+
+    db.merge(fork.into_patch()).unwrap();
+    let snapshot = db.snapshot();
+    let list = snapshot.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.head(), head);
+}
+
+#[test]
+fn element_fingerprints() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.ensure_element_fingerprint(1).unwrap();
+    list.extend(0..5);
+    list.ensure_element_fingerprint(1).unwrap();
+    list.clear();
+    assert_eq!(list.element_fingerprint(), Some(1));
+    list.extend(0..3);
+    drop(list);
+
+    // The fingerprint is persisted.
+    db.merge(fork.into_patch()).unwrap();
+    let snapshot = db.snapshot();
+    let list = snapshot.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.element_fingerprint(), Some(1));
+    assert_eq!(list.len(), 3);
+    let other_list = snapshot.get_proof_list::<_, u32>("other");
+    assert_eq!(other_list.element_fingerprint(), None);
+
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, String>(IDX_NAME);
+    let err = list.ensure_element_fingerprint(2).unwrap_err();
+    assert_eq!(
+        err,
+        ProofListError::FingerprintMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+    assert_eq!(list.element_fingerprint(), Some(1));
+    assert_eq!(list.len(), 3);
+}
+
+#[test]
+fn extending_list_with_report() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+
+    let batches: &[&[u32]] = &[&[], &[1], &[2, 3], &[], &[4, 5, 6, 7, 8], &[9; 100]];
+    for batch in batches {
+        let report = list.extend_reporting(batch.iter().copied());
+        assert_eq!(report, (list.len(), list.object_hash()));
+
+        // Compare with a list without the cached root.
+        fork.get_proof_list::<_, u32>("other")
+            .extend(batch.iter().copied());
+        let fresh_list = fork.get_proof_list::<_, u32>("other");
+        assert_eq!(report, (fresh_list.len(), fresh_list.object_hash()));
+        assert_eq!(report.1, fresh_list.list_hash());
+    }
+}
+
+#[test]
+fn computing_proof_branch_keys() {
+    fn proof_keys(proof: &ListProof<u64>) -> Vec<ProofListKey> {
+        let json = serde_json::to_value(proof).unwrap();
+        json["proof"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| {
+                let height = entry["height"].as_u64().unwrap() as u8;
+                ProofListKey::new(height, entry["index"].as_u64().unwrap())
+            })
+            .collect()
+    }
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+    assert!(proof_branch_keys(0, 0..1).is_empty());
+    assert!(proof_branch_keys(0, 0..0).is_empty());
+
+    for len in 1..=33 {
+        list.push(len);
+        // Single-element ranges.
+        for i in 0..len {
+            let proof = list.get_range_proof(i..=i);
+            assert_eq!(proof_branch_keys(len, i..i + 1), proof_keys(&proof));
+        }
+        // The full range.
+        let proof = list.get_range_proof(..);
+        assert!(proof_keys(&proof).is_empty());
+        assert_eq!(proof_branch_keys(len, 0..len), proof_keys(&proof));
+        // Other ranges.
+        for &(start, end) in &[(1, len), (len / 2, len + 5), (len, len + 1), (3, 3)] {
+            let proof = list.get_range_proof(start..end);
+            assert_eq!(proof_branch_keys(len, start..end), proof_keys(&proof));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "Illegal range boundaries")]
+fn computing_proof_branch_keys_for_illegal_range() {
+    let (start, end) = (5, 3);
+    proof_branch_keys(10, start..end);
+}
+
+#[test]
+fn resetting_list() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    let cases: &[&[u32]] = &[&[1, 2, 3], &[4; 17], &[5], &[], &[6, 7]];
+
+    for &values in cases {
+        list.reset_to(values.iter().copied());
+        assert_eq!(list.to_vec(), values);
+        list.verify_integrity().unwrap();
+
+        let mut expected_list = fork.get_proof_list::<_, u32>("expected");
+        expected_list.clear();
+        expected_list.extend(values.iter().copied());
+        assert_eq!(list.object_hash(), expected_list.object_hash());
+    }
+}
+
+#[test]
+fn filtering_raw_values() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, Vec<u8>>(IDX_NAME);
+    assert_eq!(list.iter_filter_raw(|_| true).count(), 0);
+
+    list.extend((0_u8..20).map(|i| vec![i % 3, i]));
+    let values: Vec<_> = list.iter_filter_raw(|bytes| bytes[0] == 0).collect();
+    let expected: Vec<_> = list.iter().filter(|value| value[0] == 0).collect();
+    assert_eq!(values, expected);
+    assert_eq!(values.len(), 7);
+
+    assert_eq!(list.iter_filter_raw(|_| true).count(), 20);
+    assert_eq!(list.iter_filter_raw(|_| false).count(), 0);
+}
+
+#[test]
+fn merging_range_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(0_u32..37);
+    let list_hash = list.object_hash();
+
+    for &(start, mid, end) in &[
+        (0, 1, 2),
+        (0, 16, 37),
+        (3, 7, 30),
+        (10, 11, 12),
+        (35, 36, 37),
+    ] {
+        let left = list.get_range_proof(start..mid);
+        let right = list.get_range_proof(mid..end);
+        let merged = ListProof::merge(left, right).unwrap();
+        assert_eq!(merged, list.get_range_proof(start..end));
+        let checked_proof = merged.check_against_hash(list_hash).unwrap();
+        assert!(checked_proof.indexes().eq(start..end));
+    }
+
+    // Merged proofs can be merged further.
+    let left = ListProof::merge(list.get_range_proof(0..5), list.get_range_proof(5..9)).unwrap();
+    let merged = ListProof::merge(left, list.get_range_proof(9..20)).unwrap();
+    assert_eq!(merged, list.get_range_proof(0..20));
+}
+
+#[test]
+fn merging_invalid_range_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(0_u32..20);
+
+    let non_adjacent = [(0..5, 6..10), (5..10, 0..5), (0..5, 3..10), (0..5, 20..30)];
+    for (left, right) in non_adjacent.iter().cloned() {
+        let left = list.get_range_proof(left);
+        let right = list.get_range_proof(right);
+        let err = ListProof::merge(left, right).unwrap_err();
+        assert_eq!(err, ListProofError::NonAdjacent);
+    }
+    let multiproof = list.get_multiproof(vec![1, 3]);
+    let err = ListProof::merge(multiproof, list.get_range_proof(4..6)).unwrap_err();
+    assert_eq!(err, ListProofError::NonAdjacent);
+
+    // Proofs for different states of the list.
+    let left = list.get_range_proof(0..5);
+    list.set(15, 100);
+    let right = list.get_range_proof(5..10);
+    let err = ListProof::merge(left, right).unwrap_err();
+    assert_eq!(err, ListProofError::InconsistentRoots);
+
+    let left = list.get_range_proof(0..5);
+    list.push(20);
+    let right = list.get_range_proof(5..10);
+    let err = ListProof::merge(left, right).unwrap_err();
+    assert_eq!(err, ListProofError::InconsistentRoots);
+
+    // Malformed proof.
+    let mut left = list.get_range_proof(0..5);
+    left.push_hash(6, 0, Hash::zero());
+    let right = list.get_range_proof(5..10);
+    let err = ListProof::merge(left, right).unwrap_err();
+    assert_eq!(
+        err,
+        ListProofError::UnexpectedBranch {
+            key: ProofListKey::new(6, 0)
+        }
+    );
+}
+
+#[test]
+fn limiting_multiproof_size() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    assert_eq!(
+        list.get_multiproof_limited(vec![0], 0).unwrap(),
+        list.get_multiproof(vec![0])
+    );
+
+    list.extend(0_u32..37);
+    let index_sets = vec![
+        vec![0],
+        vec![1, 3, 7],
+        vec![5, 20, 21, 36],
+        (0..37).collect(),
+    ];
+    for indexes in index_sets {
+        let proof = list.get_multiproof(indexes.clone());
+        let proof_nodes = proof.indexes_len() + proof.proof_unchecked().len();
+        let limited_proof = list
+            .get_multiproof_limited(indexes.clone(), proof_nodes)
+            .unwrap();
+        assert_eq!(limited_proof, proof);
+
+        for &limit in &[0, indexes.len() - 1, proof_nodes - 1] {
+            let err = list
+                .get_multiproof_limited(indexes.clone(), limit)
+                .unwrap_err();
+            assert_matches!(err, ProofListError::ProofTooLarge { limit: l } if l == limit);
+        }
+    }
+
+    // Proofs of absence contain a single hash.
+    let proof = list.get_multiproof_limited(vec![1, 100], 1).unwrap();
+    assert_eq!(proof, list.get_multiproof(vec![1, 100]));
+    let err = list.get_multiproof_limited(vec![1, 100], 0).unwrap_err();
+    assert_matches!(err, ProofListError::ProofTooLarge { limit: 0 });
+}
+
+#[test]
+fn in_memory_lists() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+
+    for len in 0_u32..40 {
+        let memory_list = MemoryProofList::new((0..len).collect());
+        assert_eq!(memory_list.len(), list.len());
+        assert_eq!(memory_list.merkle_root(), list.merkle_root());
+        assert_eq!(memory_list.object_hash(), list.object_hash());
+
+        for index in 0..=u64::from(len) {
+            assert_eq!(memory_list.get_proof(index), list.get_proof(index));
+            assert_eq!(
+                memory_list.get_range_proof(index..),
+                list.get_range_proof(index..)
+            );
+            assert_eq!(
+                memory_list.get_range_proof(..index),
+                list.get_range_proof(..index)
+            );
+        }
+        let indexes = vec![0, 3, 7, 20, 21];
+        assert_eq!(
+            memory_list.get_multiproof(indexes.clone()),
+            list.get_multiproof(indexes)
+        );
+        assert!(memory_list.try_get_range_proof(5..3).is_err());
+
+        list.push(len);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Proof list length overflow")]
+fn pushing_to_list_with_overflowing_length() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.set_len(u64::max_value());
+    list.push(1);
+}
+
+#[test]
+#[should_panic(expected = "Proof list length overflow")]
+fn extending_list_with_overflowing_length() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.set_len(u64::max_value());
+    list.extend(vec![1, 2]);
+}
+
+#[test]
+#[should_panic(expected = "exceeding the maximum allowed value")]
+fn pushing_to_list_of_maximum_length() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.set_len(MAX_INDEX + 1);
+    list.push(1);
+}
+
+#[test]
+fn checked_iteration_from_position() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    assert_eq!(list.try_iter_from(0).unwrap().count(), 0);
+    assert_eq!(
+        list.try_iter_from(1).unwrap_err(),
+        ProofListError::IndexOutOfBounds { index: 1, len: 0 }
+    );
+
+    list.extend(0_u32..10);
+    for from in 0..=10 {
+        assert!(list.try_iter_from(from).unwrap().eq(list.iter_from(from)));
+    }
+    assert!(list.try_iter_from(5).unwrap().rev().eq((5..10).rev()));
+    assert_eq!(
+        list.try_iter_from(11).unwrap_err(),
+        ProofListError::IndexOutOfBounds { index: 11, len: 10 }
+    );
+    assert_eq!(list.iter_from(11).count(), 0);
+}
+
+#[test]
+fn reserving_space_in_list() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.reserve(10);
+    assert!(list.is_empty());
+    list.extend(0_u32..5);
+    list.reserve(MAX_INDEX - 5);
+    assert!(list.iter().eq(0..5));
+}
+
+#[test]
+#[should_panic(expected = "exceeding the maximum allowed value")]
+fn reserving_too_much_space_in_list() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    list.push(1);
+    list.reserve(MAX_INDEX);
+}
+
+#[test]
+fn validating_proofs_with_default_values() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    let proof = list.get_proof(0);
+    assert_eq!(
+        proof.validate_or_default(list.object_hash(), 0).unwrap(),
+        0_u32
+    );
+
+    list.extend(vec![1_u32, 2, 3]);
+    list.resize(5, 0);
+    let list_hash = list.object_hash();
+    for index in 0..10 {
+        let proof = list.get_proof(index);
+        let value = proof.validate_or_default(list_hash, index).unwrap();
+        assert_eq!(value, list.get_or_default(index));
+    }
+
+    // A proof concerning another element.
+    let proof = list.get_proof(1);
+    let err = proof.validate_or_default(list_hash, 2).unwrap_err();
+    assert_matches!(err, ValidationError::Malformed(ListProofError::NotAbsent));
+    let proof = list.get_range_proof(1..3);
+    assert_eq!(proof.validate_or_default(list_hash, 2).unwrap(), 3);
+    // A proof of absence cannot be used for an existing element.
+    let proof = list.get_proof(10);
+    let err = proof.validate_or_default(list_hash, 4).unwrap_err();
+    assert_matches!(err, ValidationError::Malformed(ListProofError::NotAbsent));
+    // A proof for another list.
+    let err = proof.validate_or_default(Hash::zero(), 10).unwrap_err();
+    assert_matches!(err, ValidationError::UnmatchedRootHash { .. });
+}
+
+#[test]
+fn checkpoint_chains() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+
+    let mut checkpoints = vec![list.checkpoint()];
+    assert_eq!(checkpoints[0].len, 0);
+    assert!(checkpoints[0].frontier.is_empty());
+    for i in 1_u64..=20 {
+        let appended: Vec<_> = (0..i % 4).map(|j| i * 10 + j).collect();
+        list.extend(appended.iter().copied());
+        let checkpoint = list.checkpoint();
+        assert_eq!(checkpoint.len, list.len());
+        assert_eq!(checkpoint.list_hash, list.object_hash());
+
+        let prev = checkpoints.last().unwrap();
+        assert!(verify_checkpoint_chain(prev, &checkpoint, &appended));
+        let restored = ListHasher::<HashTag>::from_checkpoint(&checkpoint).unwrap();
+        assert_eq!(restored.checkpoint(), checkpoint);
+        checkpoints.push(checkpoint);
+    }
+
+    let first = &checkpoints[0];
+    let last = checkpoints.last().unwrap();
+    let values: Vec<u64> = list.iter().collect();
+    assert!(verify_checkpoint_chain(first, last, &values));
+    assert!(verify_checkpoint_chain(last, last, &[] as &[u64]));
+}
+
+#[test]
+fn invalid_checkpoint_chains() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec![1_u64, 2, 3, 4, 5]);
+    let prev = list.checkpoint();
+    list.extend(vec![6, 7]);
+    let next = list.checkpoint();
+
+    assert!(!verify_checkpoint_chain(&prev, &next, &[6_u64]));
+    assert!(!verify_checkpoint_chain(&prev, &next, &[6_u64, 8]));
+    assert!(!verify_checkpoint_chain(&prev, &next, &[6_u64, 7, 8]));
+    assert!(!verify_checkpoint_chain(&next, &prev, &[] as &[u64]));
+
+    let mut tampered = prev.clone();
+    tampered.frontier[0] = Hash::zero();
+    assert!(ListHasher::<HashTag>::from_checkpoint(&tampered).is_none());
+    assert!(!verify_checkpoint_chain(&tampered, &next, &[6_u64, 7]));
+
+    let mut truncated = prev.clone();
+    truncated.frontier.pop();
+    assert!(!verify_checkpoint_chain(&truncated, &next, &[6_u64, 7]));
+
+    let overflowing = ListCheckpoint {
+        len: u64::max_value(),
+        ..prev
+    };
+    assert!(ListHasher::<HashTag>::from_checkpoint(&overflowing).is_none());
+}
+
+#[test]
+fn iterating_over_values_and_indices() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+    assert_eq!(list.values().count(), 0);
+    assert_eq!(list.indices().count(), 0);
+
+    list.extend((0..10).map(|i| i * 3));
+    assert!(list.values().eq(list.iter()));
+    assert!(list.indices().eq(0..10));
+    assert!(list.values().rev().eq(list.iter().rev()));
+}
+
+#[test]
+fn detecting_list_prefixes() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let values: Vec<u64> = (0..37).collect();
+    {
+        let mut list = fork.get_proof_list(IDX_NAME);
+        list.extend(values.iter().copied());
+
+        let mut prefix = fork.get_proof_list::<_, u64>("prefix");
+        assert!(prefix.is_prefix_of(&list));
+        for &value in &values {
+            prefix.push(value);
+            assert!(prefix.is_prefix_of(&list), "len = {}", prefix.len());
+        }
+        assert!(list.is_prefix_of(&prefix));
+        prefix.push(37);
+        assert!(!prefix.is_prefix_of(&list));
+        assert!(list.is_prefix_of(&prefix));
+
+        // Lists diverging at index `k`.
+        for k in [0, 1, 16, 20, 36].iter().copied() {
+            let mut divergent = fork.get_proof_list("divergent");
+            divergent.clear();
+            divergent.extend(values.iter().copied());
+            divergent.set(k, 100);
+            for len in (0..=values.len() as u64).rev() {
+                divergent.truncate(len);
+                assert_eq!(
+                    divergent.is_prefix_of(&list),
+                    len <= k,
+                    "k = {}, len = {}",
+                    k,
+                    len
+                );
+            }
+        }
+    }
+
+    // Lists in different snapshots.
+    db.merge(fork.into_patch()).unwrap();
+    let snapshot = db.snapshot();
+    let fork = db.fork();
+    let mut extended = fork.get_proof_list::<_, u64>(IDX_NAME);
+    extended.push(37);
+    let list = snapshot.get_proof_list::<_, u64>(IDX_NAME);
+    assert!(list.is_prefix_of(&extended));
+    assert!(!extended.is_prefix_of(&list));
+}
+
+#[test]
+fn tail_proofs_near_powers_of_two() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+
+    for k in 0..10 {
+        let len = 1_u64 << k;
+        list.extend(list.len()..len);
+        let full_root = list.merkle_root();
+
+        // All left siblings on the path to the last element of a full tree are present.
+        let proof = list.get_proof(len - 1);
+        let checked_proof = proof.check_against_hash(list.object_hash()).unwrap();
+        assert_eq!(*checked_proof.entries(), [(len - 1, len - 1)]);
+        assert_eq!(proof.proof_unchecked().len(), usize::from(k));
+
+        // Appending an element results in a new branch without left siblings except
+        // for the root of the full tree, so the proof of the element consists of a single hash.
+        list.push(len);
+        let proof = list.get_proof(len);
+        let checked_proof = proof.check_against_hash(list.object_hash()).unwrap();
+        assert_eq!(*checked_proof.entries(), [(len, len)]);
+        let mut expected_proof = ListProof::new(vec![(len, len)], len + 1);
+        expected_proof.push_hash(k + 1, 0, full_root);
+        assert_eq!(proof, expected_proof);
+    }
+}
+
+#[test]
+fn compacting_list() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let db = RocksDB::open(&dir, &DbOptions::default()).unwrap();
+    let fork = db.fork();
+    fork.get_proof_list(IDX_NAME).extend(0_u64..1_000);
+    db.merge(fork.into_patch()).unwrap();
+
+    let fork = db.fork();
+    {
+        let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+        list.truncate(10);
+        list.set(3, 42);
+    }
+    db.merge(fork.into_patch()).unwrap();
+    db.compact_index(IDX_NAME);
+
+    let snapshot = db.snapshot();
+    let list = snapshot.get_proof_list::<_, u64>(IDX_NAME);
+    assert_eq!(list.len(), 10);
+    assert_eq!(list.get(3), Some(42));
+    assert_eq!(
+        list.object_hash(),
+        MemoryProofList::<_>::new(list.iter().collect()).object_hash()
+    );
+    list.verify_integrity().unwrap();
+}
+
+#[test]
+fn proofs_for_first_matching_elements() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u64>(IDX_NAME);
+    assert!(list.get_first_matching_proof(|_| true).is_none());
+
+    list.extend((0..50).map(|i| i * 7 % 50));
+    let list_hash = list.object_hash();
+    for target in 0..50 {
+        let (index, proof) = list
+            .get_first_matching_proof(|&value| value == target)
+            .unwrap();
+        assert_eq!(list.get(index), Some(target));
+        assert!(list
+            .iter()
+            .take(index as usize)
+            .all(|value| value != target));
+        assert_eq!(proof, list.get_proof(index));
+        let checked_proof = proof.check_against_hash(list_hash).unwrap();
+        assert_eq!(*checked_proof.entries(), [(index, target)]);
+    }
+
+    // The first of multiple matching elements is proven.
+    let (index, _) = list.get_first_matching_proof(|&value| value > 40).unwrap();
+    assert_eq!(index, 6);
+    assert!(list
+        .get_first_matching_proof(|&value| value >= 50)
+        .is_none());
+}
+
+#[test]
+fn full_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+
+    let proof = list.get_full_proof();
+    assert_eq!(proof, list.get_range_proof(..));
+    assert!(proof.entries_unchecked().is_empty());
+    proof.check_against_hash(list.object_hash()).unwrap();
+
+    for len in 1..=33 {
+        list.push(len);
+        let proof = list.get_full_proof();
+        assert!(proof.proof_unchecked().is_empty());
+        assert_eq!(proof, list.get_range_proof(..));
+
+        let checked_proof = proof.check_against_hash(list.object_hash()).unwrap();
+        assert_eq!(checked_proof.list_len(), list.len());
+        let values: Vec<_> = checked_proof
+            .entries()
+            .iter()
+            .map(|(_, value)| *value)
+            .collect();
+        assert_eq!(values, list.to_vec());
+        assert!(checked_proof.indexes().eq(0..list.len()));
+    }
+}
+
+#[test]
+fn range_boundary_combinations() {
+    use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+    // `None` stands for an illegal range; otherwise, the expected proven indexes are specified.
+    const CASES: &[((Bound<u64>, Bound<u64>), Option<&[u64]>)] = &[
+        // `from == to`
+        ((Included(0), Excluded(0)), Some(&[])),
+        ((Included(5), Excluded(5)), Some(&[])),
+        ((Included(10), Excluded(10)), Some(&[])),
+        ((Included(20), Excluded(20)), Some(&[])),
+        ((Excluded(4), Included(4)), Some(&[])),
+        // `from == len`
+        ((Included(10), Excluded(11)), Some(&[])),
+        ((Included(10), Included(10)), Some(&[])),
+        ((Included(10), Unbounded), Some(&[])),
+        ((Excluded(9), Unbounded), Some(&[])),
+        // `from > len`
+        ((Included(11), Excluded(15)), Some(&[])),
+        ((Included(1_000_000), Excluded(1_000_005)), Some(&[])),
+        ((Included(1_000_000), Unbounded), Some(&[])),
+        ((Excluded(u64::max_value()), Unbounded), Some(&[])),
+        (
+            (Included(u64::max_value()), Included(u64::max_value())),
+            Some(&[]),
+        ),
+        // Ranges intersecting with the list.
+        ((Included(9), Excluded(11)), Some(&[9])),
+        ((Included(8), Unbounded), Some(&[8, 9])),
+        ((Unbounded, Excluded(2)), Some(&[0, 1])),
+        ((Excluded(7), Included(1_000)), Some(&[8, 9])),
+        // `to < from`
+        ((Included(5), Excluded(3)), None),
+        ((Included(5), Included(3)), None),
+        ((Included(12), Excluded(11)), None),
+        ((Excluded(1_000), Included(5)), None),
+    ];
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+    index.extend(0_u64..10);
+    let index_hash = index.object_hash();
+
+    for (range, expected) in CASES {
+        let res = index.try_get_range_proof(*range);
+        match expected {
+            Some(indexes) => {
+                let proof = res.unwrap_or_else(|err| panic!("{:?}: {}", range, err));
+                let checked_proof = proof.check_against_hash(index_hash).unwrap();
+                assert!(
+                    checked_proof.indexes().eq(indexes.iter().copied()),
+                    "{:?}",
+                    range
+                );
+                if indexes.is_empty() {
+                    assert_eq!(proof, ListProof::empty(index.merkle_root(), 10));
+                }
+            }
+            None => {
+                assert_matches!(res, Err(ProofListError::IllegalRange { .. }), "{:?}", range);
+            }
+        }
+    }
+}
+
+#[test]
+fn ranges_work_similar_to_vec_slicing() {
+    use std::{
+        fmt,
+        ops::RangeBounds,
+        panic::{self, UnwindSafe},
+        slice::SliceIndex,
+    };
+
+    #[allow(clippy::needless_pass_by_value)] // references to ranges look awkward
+    fn check_bounds(
+        v: &[u32],
+        slice_bounds: impl SliceIndex<[u32], Output = [u32]> + Clone + UnwindSafe + fmt::Debug,
+        index_bounds: impl RangeBounds<u64> + UnwindSafe,
+    ) {
+        let panic_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+
+        let slice_bounds_ = slice_bounds.clone();
+        let slicing_res = panic::catch_unwind(|| v[slice_bounds_].to_vec());
+        let proof_res = panic::catch_unwind(|| {
+            let fork = TemporaryDB::new().fork();
+            let mut index = fork.get_proof_list(IDX_NAME);
+            index.extend(v.to_vec());
+            (index.object_hash(), index.get_range_proof(index_bounds))
+        });
+
+        panic::set_hook(panic_hook);
+
+        if let Ok((index_hash, proof)) = proof_res {
+            let checked_proof = proof.check_against_hash(index_hash).unwrap();
+
+            match slicing_res {
+                Ok(slice) => {
+                    assert_eq!(
+                        checked_proof
+                            .entries()
+                            .iter()
+                            .map(|(_, value)| *value)
+                            .collect::<Vec<_>>(),
+                        slice
+                    );
+                }
+                Err(e) => {
+                    // Slicing is more strict if the range does not completely fit
+                    // in the slice indexes.
+                    assert!(
+                        e.downcast_ref::<String>().unwrap().contains("out of range"),
+                        "{:?}",
+                        slice_bounds
+                    );
+                }
+            }
+        } else {
+            assert!(slicing_res.is_err(), "{:?}", slice_bounds);
+        }
+    }
+
+    let v = vec![1, 2, 3, 4, 5];
+    check_bounds(&v, .., ..);
+    for start in 0_usize..10 {
+        for end in 0_usize..10 {
+            check_bounds(&v, start..end, (start as u64)..(end as u64));
+            check_bounds(&v, start..=end, (start as u64)..=(end as u64));
+            if start == 0 {
+                check_bounds(&v, ..end, ..(end as u64));
+                check_bounds(&v, ..=end, ..=(end as u64));
+            }
+            if end == 0 && start <= v.len() {
+                check_bounds(&v, start.., (start as u64)..);
+            }
+        }
+    }
+}
+
+#[test]
+fn proofs_with_inclusive_and_excluded_bounds() {
+    use std::ops::Bound;
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+    index.extend(0_u32..10);
+    let index_hash = index.object_hash();
+
+    let proof = index.get_range_proof(1..=3);
+    let checked_proof = proof.check_against_hash(index_hash).unwrap();
+    assert!(checked_proof.indexes().eq(1..=3));
+
+    let proof = index.get_range_proof(..=2);
+    let checked_proof = proof.check_against_hash(index_hash).unwrap();
+    assert!(checked_proof.indexes().eq(0..=2));
+
+    let proof = index.get_range_proof((Bound::Excluded(0), Bound::Included(4)));
+    let checked_proof = proof.check_against_hash(index_hash).unwrap();
+    assert!(checked_proof.indexes().eq(1..=4));
+
+    let proof = index.get_range_proof((Bound::Excluded(3), Bound::Excluded(5)));
+    let checked_proof = proof.check_against_hash(index_hash).unwrap();
+    assert!(checked_proof.indexes().eq(vec![4]));
+
+    let proof = index.get_range_proof(9..=9);
+    let checked_proof = proof.check_against_hash(index_hash).unwrap();
+    assert!(checked_proof.indexes().eq(vec![9]));
+}
+
+#[test]
+fn proof_with_range_start_exceeding_list_size() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = fork.get_proof_list(IDX_NAME);
+    for i in 0_u8..4 {
+        index.push(vec![i]);
+    }
+
+    let proof = index.get_range_proof(8..10_000_000);
+    assert!(proof
+        .check_against_hash(index.object_hash())
+        .unwrap()
+        .entries()
+        .is_empty());
+
+    let proof = index.get_range_proof(8..);
+    assert!(proof
+        .check_against_hash(index.object_hash())
         .unwrap()
         .entries()
         .is_empty());
@@ -739,6 +2703,39 @@ fn setting_elements_leads_to_correct_list_hash() {
     assert_eq!(hash1, hash2);
 }
 
+#[test]
+fn setting_elements_out_of_bounds() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+
+    let err = list.try_set(0, 1_u32).unwrap_err();
+    assert_eq!(err, ProofListError::IndexOutOfBounds { index: 0, len: 0 });
+    list.extend(vec![1, 2, 3]);
+    let list_hash = list.object_hash();
+    let err = list.try_set(5, 10).unwrap_err();
+    assert_eq!(err, ProofListError::IndexOutOfBounds { index: 5, len: 3 });
+    assert_eq!(
+        err.to_string(),
+        "Index out of bounds: the len is 3 but the index is 5"
+    );
+    // The list should not be modified.
+    assert_eq!(list.object_hash(), list_hash);
+
+    list.try_set(2, 10).unwrap();
+    assert!(list.iter().eq(vec![1, 2, 10]));
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds: the len is 3 but the index is 3")]
+fn setting_element_out_of_bounds_panics() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec![1_u32, 2, 3]);
+    list.set(3, 4);
+}
+
 #[test]
 fn setting_elements_leads_to_correct_list_hash_randomized() {
     const LIST_LEN: usize = 32;
@@ -792,6 +2789,50 @@ fn setting_elements_leads_to_correct_list_hash_randomized() {
     }
 }
 
+#[test]
+fn swapping_elements_leads_to_expected_hash() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    let mut reference = fork.get_proof_list("reference");
+
+    for len in 1..20 {
+        let mut values: Vec<_> = (0_u64..len).collect();
+        for first in 0..len {
+            for second in 0..len {
+                list.clear();
+                list.extend(values.iter().copied());
+                list.swap(first, second);
+
+                values.swap(first as usize, second as usize);
+                reference.clear();
+                reference.extend(values.iter().copied());
+                assert!(list.iter().eq(reference.iter()));
+                assert_eq!(
+                    list.object_hash(),
+                    reference.object_hash(),
+                    "Unexpected hash after swapping elements {} and {} in a list of length {}",
+                    first,
+                    second,
+                    len
+                );
+                assert_eq!(list.get_range_proof(..), reference.get_range_proof(..));
+                values.swap(first as usize, second as usize);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds: the len is 3 but the index is 3")]
+fn swapping_elements_out_of_bounds() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec![1_u32, 2, 3]);
+    list.swap(0, 3);
+}
+
 #[test]
 fn truncating_list() {
     let db = TemporaryDB::new();
@@ -807,7 +2848,7 @@ fn truncating_list() {
 
     // Check that the branches are removed.
     let level_lengths = vec![5, 5, 3, 2, 1];
-    for height in 1..tree_height_by_length(30) {
+    for height in 1..height_of_len(30) {
         let level_len = level_lengths
             .get(height as usize)
             .copied()
@@ -882,6 +2923,100 @@ fn truncating_list_leads_to_expected_hash() {
     }
 }
 
+#[test]
+fn truncating_list_across_power_of_two_boundaries() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    let mut reference = fork.get_proof_list("reference");
+
+    for &(old_len, new_len) in &[(9_u64, 8_u64), (17, 4), (33, 16), (64, 1), (65, 32), (5, 5)] {
+        list.clear();
+        list.extend(0..old_len);
+        list.truncate(new_len);
+        reference.clear();
+        reference.extend(0..new_len);
+        assert_eq!(list.height(), reference.height());
+        assert_eq!(list.object_hash(), reference.object_hash());
+
+        // Stale branches left after truncation would corrupt the tree
+        // once it grows back.
+        list.extend(new_len..old_len + 3);
+        reference.extend(new_len..old_len + 3);
+        assert_eq!(list.object_hash(), reference.object_hash());
+        for i in 0..old_len + 3 {
+            let proof = list.get_proof(i);
+            assert_eq!(proof, reference.get_proof(i));
+            proof.check_against_hash(list.object_hash()).unwrap();
+        }
+    }
+
+    list.truncate(0);
+    assert!(list.is_empty());
+    assert_eq!(list.object_hash(), HashTag::empty_list_hash());
+}
+
+#[test]
+fn retaining_elements_leads_to_expected_hash() {
+    let predicates: &[fn(&u64) -> bool] = &[
+        |_| true,
+        |_| false,
+        |&x| x % 2 == 0,
+        |&x| x % 3 != 1,
+        |&x| x < 17,
+        |&x| x >= 17,
+        |&x| x != 0,
+    ];
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    for len in 0_u64..40 {
+        for predicate in predicates {
+            let mut list = fork.get_proof_list(IDX_NAME);
+            list.clear();
+            list.extend(0..len);
+            list.retain(predicate);
+
+            let mut expected_list = fork.get_proof_list("expected");
+            expected_list.clear();
+            expected_list.extend((0..len).filter(predicate));
+            assert!(list.iter().eq(expected_list.iter()));
+            assert_eq!(list.object_hash(), expected_list.object_hash());
+            assert!(list.structurally_eq(&expected_list));
+        }
+    }
+}
+
+#[test]
+fn clearing_list_removes_all_branches() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    fork.get_proof_list(IDX_NAME).extend(0_u32..100);
+    db.merge(fork.into_patch()).unwrap();
+
+    let fork = db.fork();
+    {
+        let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+        list.clear();
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.object_hash(), HashTag::empty_list_hash());
+        assert_eq!(list.base.iter::<_, u64, Vec<u8>>(&()).count(), 0);
+
+        list.push(100);
+        assert_eq!(list.get(0), Some(100));
+        assert_eq!(list.object_hash(), HashTag::hash_list(&[100_u32]));
+        // Only the value and its hash should be present in the index.
+        assert_eq!(list.base.iter::<_, u64, Vec<u8>>(&()).count(), 2);
+    }
+
+    db.merge(fork.into_patch()).unwrap();
+    let snapshot = db.snapshot();
+    let list = snapshot.get_proof_list::<_, u32>(IDX_NAME);
+    assert!(list.iter().eq(vec![100]));
+    assert_eq!(list.object_hash(), HashTag::hash_list(&[100_u32]));
+    assert_eq!(list.base.iter::<_, u64, Vec<u8>>(&()).count(), 2);
+}
+
 #[test]
 fn popping_element_from_list() {
     let db = TemporaryDB::new();
@@ -899,9 +3034,41 @@ fn popping_element_from_list() {
     assert_eq!(list.object_hash(), HashTag::empty_list_hash());
 }
 
+#[test]
+fn popping_element_leads_to_expected_hash() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    let mut reference = fork.get_proof_list("reference");
+
+    // Lengths are chosen so that popping crosses power-of-two boundaries
+    // and decreases the tree height.
+    for &len in &[1_u64, 2, 3, 5, 9, 17, 33] {
+        list.clear();
+        list.extend(0..len);
+        let old_height = list.height();
+
+        assert_eq!(list.pop(), Some(len - 1));
+        reference.clear();
+        reference.extend(0..len - 1);
+        assert_eq!(list.len(), len - 1);
+        assert_eq!(list.height(), reference.height());
+        assert_eq!(list.object_hash(), reference.object_hash());
+        if (len - 1).is_power_of_two() {
+            assert_eq!(list.height(), old_height - 1);
+        }
+
+        // Proofs built for the popped list should coincide with the reference ones.
+        for i in 0..len {
+            assert_eq!(list.get_proof(i), reference.get_proof(i));
+        }
+    }
+}
+
 #[test]
 fn proof_json_serialization() {
-    let mut proof = ListProof::new(vec![(1, "foo".to_owned()), (2, "bar".to_owned())], 5);
+    let mut proof: ListProof<_> =
+        ListProof::new(vec![(1, "foo".to_owned()), (2, "bar".to_owned())], 5);
     proof.push_hash(1, 0, HashTag::hash_leaf(&[4]));
     proof.push_hash(2, 1, HashTag::hash_leaf(&[2]));
     proof.push_hash(3, 1, HashTag::hash_leaf(&[1]));
@@ -924,6 +3091,444 @@ fn proof_json_serialization() {
     assert_eq!(proof_from_json, proof);
 }
 
+#[test]
+fn validating_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec![1_u32, 2, 3, 4, 5]);
+    let list_hash = list.object_hash();
+
+    let proof = list.get_range_proof(1..3);
+    assert_eq!(
+        proof.validate(list_hash, 5).unwrap(),
+        vec![(1, &2), (2, &3)]
+    );
+    let proof = list.get_proof(4);
+    assert_eq!(proof.validate(list_hash, 5).unwrap(), vec![(4, &5)]);
+    let proof = list.get_proof(10);
+    assert!(proof.validate(list_hash, 5).unwrap().is_empty());
+
+    // Mismatched hash or length.
+    let err = proof.validate(Hash::zero(), 5).unwrap_err();
+    assert_matches!(
+        err,
+        ValidationError::UnmatchedRootHash { expected, actual }
+            if expected == Hash::zero() && actual == list_hash
+    );
+    let err = proof.validate(list_hash, 6).unwrap_err();
+    assert_matches!(
+        err,
+        ValidationError::Malformed(ListProofError::LengthMismatch {
+            expected: 6,
+            actual: 5,
+        })
+    );
+
+    // Malformed proof.
+    let mut proof: ListProof<_> = ListProof::new(vec![(1, 2_u32)], 5);
+    proof.push_hash(1, 0, Hash::zero());
+    let err = proof.validate(list_hash, 5).unwrap_err();
+    assert_matches!(
+        err,
+        ValidationError::Malformed(ListProofError::MissingHash { height: 2 })
+    );
+}
+
+#[test]
+fn validating_proofs_of_absence() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec![1_u32, 2, 3, 4, 5]);
+    let list_hash = list.object_hash();
+
+    let proof = list.get_range_proof(5..10);
+    proof.validate_absence(list_hash, 5..10).unwrap();
+    proof.validate_absence(list_hash, 5..).unwrap();
+    proof.validate_absence(list_hash, 100..=200).unwrap();
+    let err = proof.validate_absence(list_hash, 4..10).unwrap_err();
+    assert_matches!(err, ValidationError::Malformed(ListProofError::NotAbsent));
+    let err = proof.validate_absence(Hash::zero(), 5..10).unwrap_err();
+    assert_matches!(err, ValidationError::UnmatchedRootHash { .. });
+
+    // A proof of existence does not prove absence.
+    let proof = list.get_range_proof(3..);
+    let err = proof.validate_absence(list_hash, 3..).unwrap_err();
+    assert_matches!(err, ValidationError::Malformed(ListProofError::NotAbsent));
+
+    // A malicious prover cannot reduce the list length in order to claim absence
+    // of the existing elements.
+    let mut other = fork.get_proof_list("other");
+    other.extend(vec![1_u32, 2, 3]);
+    let forged_proof: ListProof<u32> = ListProof::empty(other.merkle_root(), 3);
+    let err = forged_proof.validate_absence(list_hash, 3..).unwrap_err();
+    assert_matches!(err, ValidationError::UnmatchedRootHash { .. });
+
+    // Empty list.
+    let empty_list = fork.get_proof_list::<_, u32>("empty");
+    let proof = empty_list.get_proof(0);
+    proof
+        .validate_absence(empty_list.object_hash(), ..)
+        .unwrap();
+}
+
+#[test]
+fn proof_display() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec![1_u32, 2, 3, 4, 5]);
+
+    let short = |hash: Hash| hash.to_hex()[..8].to_owned();
+    let h1 = HashTag::hash_leaf(&1_u32.to_bytes());
+    let h34 = HashTag::hash_node(
+        &HashTag::hash_leaf(&3_u32.to_bytes()),
+        &HashTag::hash_leaf(&4_u32.to_bytes()),
+    );
+    let h5 = HashTag::hash_single_node(&HashTag::hash_single_node(&HashTag::hash_leaf(
+        &5_u32.to_bytes(),
+    )));
+
+    let proof = list.get_proof(1);
+    let expected = format!(
+        "ListProof(length = 5)\n  \
+         Right(height = 3, index = 1): {}\n    \
+         Right(height = 2, index = 1): {}\n      \
+         Left(height = 1, index = 0): {}\n        \
+         Leaf(index = 1): 2",
+        short(h5),
+        short(h34),
+        short(h1)
+    );
+    assert_eq!(proof.to_string(), expected);
+
+    let proof = list.get_range_proof(..);
+    assert_eq!(
+        proof.to_string(),
+        "ListProof(length = 5)\n  Leaf(index = 0): 1\n  Leaf(index = 1): 2\n  \
+         Leaf(index = 2): 3\n  Leaf(index = 3): 4\n  Leaf(index = 4): 5"
+    );
+
+    let proof = list.get_proof(10);
+    assert_eq!(
+        proof.to_string(),
+        format!("Absent(5, {})", short(list.merkle_root()))
+    );
+}
+
+#[test]
+fn range_proof_json_roundtrip() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec!["foo".to_owned(), "bar".to_owned(), "baz".to_owned()]);
+    list.extend((0..10).map(|i| i.to_string()));
+
+    for range in &[0..1, 1..3, 2..13, 5..100, 20..30] {
+        let proof = list.get_range_proof(range.clone());
+        let json = serde_json::to_string(&proof).unwrap();
+        let restored: ListProof<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, proof);
+        assert_eq!(
+            restored.validate(list.object_hash(), list.len()).unwrap(),
+            proof.validate(list.object_hash(), list.len()).unwrap()
+        );
+    }
+
+    // Hashes are serialized in the hex form.
+    let proof = list.get_proof(0);
+    let json = serde_json::to_value(&proof).unwrap();
+    let sibling_hash = HashTag::hash_leaf(&"bar".to_owned().to_bytes());
+    assert_eq!(json["proof"][0]["hash"], json!(sibling_hash.to_hex()));
+}
+
+#[test]
+fn streamed_range_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+
+    let mut buffer = vec![];
+    list.write_range_proof(.., &mut buffer).unwrap();
+    let proof = ListProof::<String>::read_range_proof(&buffer[..]).unwrap();
+    assert_eq!(proof, list.get_range_proof(..));
+
+    list.extend((0..50).map(|i| i.to_string()));
+    let ranges = vec![
+        0..1,
+        0..50,
+        3..17,
+        16..33,
+        49..50,
+        40..100,
+        50..60,
+        100..200,
+        5..5,
+    ];
+    for range in ranges {
+        buffer.clear();
+        list.write_range_proof(range.clone(), &mut buffer).unwrap();
+        let proof = ListProof::<String>::read_range_proof(&buffer[..]).unwrap();
+        assert_eq!(proof, list.get_range_proof(range.clone()), "{:?}", range);
+        proof.check_against_hash(list.object_hash()).unwrap();
+    }
+
+    let err = list.write_range_proof(3..1, &mut buffer).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    // Truncated proof.
+    buffer.clear();
+    list.write_range_proof(1..3, &mut buffer).unwrap();
+    buffer.pop();
+    let err = ListProof::<String>::read_range_proof(&buffer[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn range_proof_size_hints() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+
+    let mut buffer = vec![];
+    list.write_range_proof(.., &mut buffer).unwrap();
+    assert_eq!(list.range_proof_size_hint(..), buffer.len());
+
+    for len in 1_u64..40 {
+        list.clear();
+        list.extend(0..len);
+        for from in 0..=len + 1 {
+            for to in from..=len + 2 {
+                buffer.clear();
+                list.write_range_proof(from..to, &mut buffer).unwrap();
+                assert_eq!(
+                    list.range_proof_size_hint(from..to),
+                    buffer.len(),
+                    "len = {}, range = {:?}",
+                    len,
+                    from..to
+                );
+            }
+        }
+    }
+}
+
+/// Hasher with domain separation prefixes different from `HashTag`.
+struct PrefixedHasher;
+
+const HASHER_PREFIX: &[u8] = b"test";
+
+/// Address of a list with `PrefixedHasher`. Such lists can only be created in groups.
+const CUSTOM_HASHER_ADDR: (&str, &u8) = ("custom", &1);
+
+impl Hasher for PrefixedHasher {
+    fn hash_leaf(value: &[u8]) -> Hash {
+        HashStream::new()
+            .update(HASHER_PREFIX)
+            .update(&[0])
+            .update(value)
+            .hash()
+    }
+
+    fn hash_node(left_hash: &Hash, right_hash: &Hash) -> Hash {
+        HashStream::new()
+            .update(HASHER_PREFIX)
+            .update(&[1])
+            .update(left_hash.as_ref())
+            .update(right_hash.as_ref())
+            .hash()
+    }
+
+    fn hash_single_node(hash: &Hash) -> Hash {
+        HashStream::new()
+            .update(HASHER_PREFIX)
+            .update(&[1])
+            .update(hash.as_ref())
+            .hash()
+    }
+
+    fn hash_list_node(len: u64, root: Hash) -> Hash {
+        HashStream::new()
+            .update(HASHER_PREFIX)
+            .update(&[2])
+            .update(&len.to_le_bytes())
+            .update(root.as_ref())
+            .hash()
+    }
+}
+
+#[test]
+fn default_hasher_is_hash_tag() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec![1_u32, 2, 3, 4, 5]);
+    let list_hash = list.object_hash();
+    drop(list);
+
+    let list = ProofListIndex::<_, u32, HashTag>::from_access(&fork, IDX_NAME.into()).unwrap();
+    assert_eq!(list.object_hash(), list_hash);
+    assert_eq!(list_hash, HashTag::hash_list(&[1_u32, 2, 3, 4, 5]));
+}
+
+#[test]
+fn custom_hasher_outside_of_group() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let err = ProofListIndex::<_, u32, PrefixedHasher>::from_access(&fork, IDX_NAME.into())
+        .map(drop)
+        .unwrap_err();
+    assert_matches!(err.kind, AccessErrorKind::AggregatedCustomHasher);
+    assert_eq!(fork.index_type(IDX_NAME), None);
+
+    // Lists in groups are not aggregated, so the state hash is not influenced by them.
+    let mut list =
+        ProofListIndex::<_, u32, PrefixedHasher>::from_access(&fork, CUSTOM_HASHER_ADDR.into())
+            .unwrap();
+    list.extend(vec![1, 2, 3]);
+    drop(list);
+    let patch = fork.into_patch();
+    assert!(SystemSchema::new(&patch)
+        .state_aggregator()
+        .keys()
+        .next()
+        .is_none());
+}
+
+#[test]
+fn proofs_with_custom_hasher() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut default_list = fork.get_proof_list(IDX_NAME);
+    let mut list =
+        ProofListIndex::<_, u32, PrefixedHasher>::from_access(&fork, CUSTOM_HASHER_ADDR.into())
+            .unwrap();
+
+    let values = vec![1_u32, 2, 3, 4, 5];
+    default_list.extend(values.clone());
+    for &value in &values {
+        list.push(value);
+    }
+    assert_eq!(list.height(), default_list.height());
+    assert_ne!(list.object_hash(), default_list.object_hash());
+
+    for proof in vec![
+        list.get_proof(1),
+        list.get_range_proof(1..4),
+        list.get_multiproof(vec![0, 4]),
+    ] {
+        proof.check_against_hash(list.object_hash()).unwrap();
+
+        // The proof should not validate under the default hasher.
+        let json = serde_json::to_value(&proof).unwrap();
+        let reinterpreted_proof: ListProof<u32> = serde_json::from_value(json).unwrap();
+        let checked_proof = reinterpreted_proof.check().unwrap();
+        assert_ne!(checked_proof.index_hash(), list.object_hash());
+        assert_ne!(checked_proof.index_hash(), default_list.object_hash());
+    }
+
+    // Proofs created for the default hasher should not validate under the custom one.
+    let json = serde_json::to_value(&default_list.get_proof(1)).unwrap();
+    let reinterpreted_proof: ListProof<u32, PrefixedHasher> = serde_json::from_value(json).unwrap();
+    let err = reinterpreted_proof
+        .check_against_hash(default_list.object_hash())
+        .unwrap_err();
+    assert_matches!(err, ValidationError::UnmatchedRootHash { .. });
+
+    // Mutations should use the custom hasher as well.
+    list.set(2, 10);
+    list.truncate(4);
+    default_list.set(2, 10);
+    default_list.truncate(4);
+    let proof = list.get_range_proof(..);
+    proof.check_against_hash(list.object_hash()).unwrap();
+    assert_ne!(list.object_hash(), default_list.object_hash());
+}
+
+#[test]
+#[should_panic(expected = "Multiple mutable borrows")]
+fn opening_list_twice_on_fork_panics() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.push(1_u32);
+    // The second instance would have a stale length, so the fork does not allow to create it.
+    let _other_list = fork.get_proof_list::<_, u32>(IDX_NAME);
+}
+
+#[test]
+fn reopened_list_has_actual_length() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    fork.get_proof_list(IDX_NAME).extend(vec![1_u32, 2, 3]);
+
+    let mut list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.len(), 3);
+    let list_hash = list.object_hash();
+    list.push(4);
+    drop(list);
+
+    let list = fork.get_proof_list::<_, u32>(IDX_NAME);
+    assert_eq!(list.len(), 4);
+    assert_ne!(list.object_hash(), list_hash);
+    assert_eq!(list.iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn mutations_invalidate_cached_root() {
+    fn expected_hash(values: &[u32]) -> Hash {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        let mut list = fork.get_proof_list(IDX_NAME);
+        list.extend(values.iter().copied());
+        list.object_hash()
+    }
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    assert_eq!(list.object_hash(), expected_hash(&[]));
+
+    list.push(1_u32);
+    assert_eq!(list.object_hash(), expected_hash(&[1]));
+    list.extend(vec![2, 3, 4, 5]);
+    assert_eq!(list.object_hash(), expected_hash(&[1, 2, 3, 4, 5]));
+    list.set(2, 10);
+    assert_eq!(list.object_hash(), expected_hash(&[1, 2, 10, 4, 5]));
+    list.swap(0, 4);
+    assert_eq!(list.object_hash(), expected_hash(&[5, 2, 10, 4, 1]));
+    list.truncate(3);
+    assert_eq!(list.object_hash(), expected_hash(&[5, 2, 10]));
+    assert_eq!(list.pop(), Some(10));
+    assert_eq!(list.object_hash(), expected_hash(&[5, 2]));
+    // Proofs must use the updated root as well.
+    let proof = list.get_range_proof(..);
+    assert!(proof.check_against_hash(list.object_hash()).is_ok());
+    list.clear();
+    assert_eq!(list.object_hash(), expected_hash(&[]));
+    list.push(7);
+    assert_eq!(list.object_hash(), expected_hash(&[7]));
+}
+
+#[test]
+fn read_only_list_is_sync() {
+    fn assert_sync<T: Sync>(_: &T) {}
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = fork.get_proof_list(IDX_NAME);
+    list.extend(vec![1_u32, 2, 3]);
+    let list_hash = list.object_hash();
+    db.merge(fork.into_patch()).unwrap();
+
+    let snapshot = db.snapshot();
+    let list = snapshot.get_proof_list::<_, u32>(IDX_NAME);
+    assert_sync(&list);
+    assert_eq!(list.object_hash(), list_hash);
+    assert_eq!(list.head(), (3, list_hash));
+}
+
 #[test]
 fn unordered_proofs() {
     let json = json!({
@@ -987,7 +3592,12 @@ fn proofs_with_unexpected_branches() {
         "length": 10,
     }))
     .unwrap();
-    assert_eq!(proof.check().unwrap_err(), ListProofError::UnexpectedBranch);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::UnexpectedBranch {
+            key: ProofListKey::new(10, 2)
+        }
+    );
 
     let proof: ListProof<u64> = serde_json::from_value(json!({
         "entries": [(2, 2)],
@@ -997,17 +3607,35 @@ fn proofs_with_unexpected_branches() {
         "length": 10,
     }))
     .unwrap();
-    assert_eq!(proof.check().unwrap_err(), ListProofError::UnexpectedBranch);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::UnexpectedBranch {
+            key: ProofListKey::new(5, 0)
+        }
+    );
 
-    let mut proof = ListProof::new(vec![(1, "foo".to_owned()), (2, "bar".to_owned())], 3);
+    let mut proof: ListProof<_> =
+        ListProof::new(vec![(1, "foo".to_owned()), (2, "bar".to_owned())], 3);
     proof.push_hash(2, 2, Hash::zero());
-    assert_eq!(proof.check().unwrap_err(), ListProofError::UnexpectedBranch);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::UnexpectedBranch {
+            key: ProofListKey::new(2, 2)
+        }
+    );
 
-    let mut proof = ListProof::new(vec![(1, "foo".to_owned()), (2, "bar".to_owned())], 3);
+    let mut proof: ListProof<_> =
+        ListProof::new(vec![(1, "foo".to_owned()), (2, "bar".to_owned())], 3);
     proof.push_hash(1, 4, Hash::zero());
-    assert_eq!(proof.check().unwrap_err(), ListProofError::UnexpectedBranch);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::UnexpectedBranch {
+            key: ProofListKey::new(1, 4)
+        }
+    );
 
-    let mut proof = ListProof::new(vec![(1, "foo".to_owned()), (2, "bar".to_owned())], 5);
+    let mut proof: ListProof<_> =
+        ListProof::new(vec![(1, "foo".to_owned()), (2, "bar".to_owned())], 5);
     proof.push_hash(1, 6, Hash::zero());
 }
 
@@ -1022,31 +3650,48 @@ fn proofs_with_unexpected_leaf() {
         "length": 5,
     }))
     .unwrap();
-    assert_eq!(proof.check().unwrap_err(), ListProofError::UnexpectedLeaf);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::UnexpectedLeaf {
+            key: ProofListKey::new(0, 1)
+        }
+    );
 }
 
 #[test]
 fn proofs_with_missing_entry() {
-    let proof = ListProof::new(vec![(1, 1_u64), (2, 2)], 3);
+    let proof: ListProof<_> = ListProof::new(vec![(1, 1_u64), (2, 2)], 3);
     // (1, 0) is missing
-    assert_eq!(proof.check().unwrap_err(), ListProofError::MissingHash);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::MissingHash { height: 1 }
+    );
 
-    let mut proof = ListProof::new(vec![(1, 1_u64)], 7);
+    let mut proof: ListProof<_> = ListProof::new(vec![(1, 1_u64)], 7);
     proof.push_hash(1, 0, Hash::zero());
     // (2, 1) is missing
-    assert_eq!(proof.check().unwrap_err(), ListProofError::MissingHash);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::MissingHash { height: 2 }
+    );
 
-    let mut proof = ListProof::new(vec![(1, 1_u64), (2, 2)], 9);
+    let mut proof: ListProof<_> = ListProof::new(vec![(1, 1_u64), (2, 2)], 9);
     proof.push_hash(1, 0, Hash::zero());
     proof.push_hash(1, 3, Hash::zero());
     // (3, 1) is missing
-    assert_eq!(proof.check().unwrap_err(), ListProofError::MissingHash);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::MissingHash { height: 3 }
+    );
 
-    let mut proof = ListProof::new(vec![(1, 1_u64), (2, 2), (4, 4)], 8);
+    let mut proof: ListProof<_> = ListProof::new(vec![(1, 1_u64), (2, 2), (4, 4)], 8);
     proof.push_hash(1, 0, Hash::zero());
     proof.push_hash(1, 3, Hash::zero());
     proof.push_hash(2, 3, Hash::zero());
-    assert_eq!(proof.check().unwrap_err(), ListProofError::MissingHash);
+    assert_eq!(
+        proof.check().unwrap_err(),
+        ListProofError::MissingHash { height: 1 }
+    );
 }
 
 #[test]
@@ -1059,7 +3704,7 @@ fn invalid_proofs_with_no_values() {
     .unwrap();
     assert_eq!(
         proof.check().unwrap_err(),
-        ListProofError::MissingHash // we expected 1 hash
+        ListProofError::MissingHash { height: 1 } // we expected 1 hash
     );
 
     let proof: ListProof<u64> = serde_json::from_value(json!({
@@ -1073,7 +3718,9 @@ fn invalid_proofs_with_no_values() {
     .unwrap();
     assert_eq!(
         proof.check().unwrap_err(),
-        ListProofError::UnexpectedBranch // we expected 1 hash, got 2
+        ListProofError::UnexpectedBranch {
+            key: ProofListKey::new(0, 1)
+        } // we expected 1 hash, got 2
     );
 
     let proof: ListProof<u64> = serde_json::from_value(json!({
@@ -1086,7 +3733,9 @@ fn invalid_proofs_with_no_values() {
     .unwrap();
     assert_eq!(
         proof.check().unwrap_err(),
-        ListProofError::UnexpectedBranch // the hash is at an incorrect position
+        ListProofError::UnexpectedBranch {
+            key: ProofListKey::new(0, 1)
+        } // the hash is at an incorrect position
     );
 }
 
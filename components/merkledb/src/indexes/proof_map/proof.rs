@@ -589,7 +589,10 @@ where
                 if checked.index_hash() == expected_map_hash {
                     Ok(checked)
                 } else {
-                    Err(ValidationError::UnmatchedRootHash)
+                    Err(ValidationError::UnmatchedRootHash {
+                        expected: expected_map_hash,
+                        actual: checked.index_hash(),
+                    })
                 }
             })
     }
@@ -270,8 +270,9 @@ mod tests {
     use std::str::FromStr;
 
     use chrono::Duration;
+    use exonum_crypto::hash;
 
-    use super::{BinaryValue, Decimal, Utc, Uuid, HASH_SIZE};
+    use super::{BinaryValue, Decimal, Hash, Utc, Uuid, HASH_SIZE};
 
     fn assert_round_trip_eq<T: BinaryValue + PartialEq + Debug>(values: &[T]) {
         for value in values {
@@ -389,4 +390,17 @@ mod tests {
         let values = [[1; HASH_SIZE]];
         assert_round_trip_eq(&values);
     }
+
+    #[test]
+    fn test_binary_form_hash() {
+        let values = [Hash::zero(), hash(&[1, 2, 3]), Hash::new([255; HASH_SIZE])];
+        assert_round_trip_eq(&values);
+
+        // Hashes are stored as is, without a length prefix or any other encoding.
+        for value in &values {
+            assert_eq!(value.to_bytes(), value.as_ref());
+        }
+        assert!(Hash::from_bytes(vec![0; HASH_SIZE - 1].into()).is_err());
+        assert!(Hash::from_bytes(vec![0; HASH_SIZE + 1].into()).is_err());
+    }
 }
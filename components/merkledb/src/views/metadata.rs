@@ -12,8 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use anyhow::{ensure, format_err};
-use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use anyhow::{bail, ensure, format_err};
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
 use enum_primitive_derive::Primitive;
 use exonum_crypto::Hash;
 use num_traits::FromPrimitive;
@@ -75,6 +75,8 @@ impl IndexType {
 
 /// Index state attribute tag.
 const INDEX_STATE_TAG: u32 = 0;
+/// Element fingerprint attribute tag.
+const FINGERPRINT_TAG: u32 = 1;
 
 /// A type that can be (de)serialized as a metadata value.
 pub trait BinaryAttribute: Sized {
@@ -171,6 +173,9 @@ pub struct IndexMetadata<V = Vec<u8>> {
     // calls `IndexState::unset()`. `None` option does not occupy space in the metadata
     // and can therefore be preferable to explicit "default" option.
     state: Option<V>,
+    // Optional fingerprint of the index element type. Like `state`, the fingerprint
+    // does not occupy space in the metadata if it is not set.
+    fingerprint: Option<u64>,
 }
 
 impl<V> BinaryValue for IndexMetadata<V>
@@ -182,6 +187,9 @@ where
         if let Some(ref state) = self.state {
             capacity += mem::size_of_val(&INDEX_STATE_TAG) + mem::size_of::<u32>() + state.size();
         }
+        if self.fingerprint.is_some() {
+            capacity += mem::size_of_val(&FINGERPRINT_TAG) + mem::size_of::<u32>() + 8;
+        }
         let mut buf = Vec::with_capacity(capacity);
 
         buf.write_u64::<LittleEndian>(self.identifier.get())
@@ -194,6 +202,11 @@ where
             buf.write_u32::<LittleEndian>(state.size() as u32).unwrap();
             state.write(&mut buf);
         }
+        if let Some(fingerprint) = self.fingerprint {
+            buf.write_u32::<LittleEndian>(FINGERPRINT_TAG).unwrap();
+            buf.write_u32::<LittleEndian>(8).unwrap();
+            buf.write_u64::<LittleEndian>(fingerprint).unwrap();
+        }
         buf
     }
 
@@ -206,31 +219,42 @@ where
         let index_type = IndexType::from_u32(index_type)
             .ok_or_else(|| format_err!("Unknown index type: {}", index_type))?;
 
-        if bytes.is_empty() {
-            // There are no tags in the metadata, correspondingly, no index state.
-            return Ok(Self {
-                identifier,
-                index_type,
-                state: None,
-            });
+        let mut state = None;
+        let mut fingerprint = None;
+        // Reads attributes in TLV (tag, length, value) form. The index state, if present,
+        // precedes the fingerprint.
+        while !bytes.is_empty() {
+            let tag = bytes.read_u32::<LittleEndian>()?;
+            let len = bytes.read_u32::<LittleEndian>()? as usize;
+            ensure!(
+                bytes.len() >= len,
+                "Attribute with tag {} is too short",
+                tag
+            );
+            let (value_bytes, rest) = bytes.split_at(len);
+
+            match tag {
+                INDEX_STATE_TAG if state.is_none() && fingerprint.is_none() => {
+                    state = Some(V::read(value_bytes)?);
+                }
+                FINGERPRINT_TAG if fingerprint.is_none() => {
+                    ensure!(
+                        len == 8,
+                        "Invalid length of the element fingerprint: {}",
+                        len
+                    );
+                    fingerprint = Some(LittleEndian::read_u64(value_bytes));
+                }
+                _ => bail!("Attribute with unknown tag: {}", tag),
+            }
+            bytes = rest;
         }
 
-        // Reads index state in TLV (tag, length, value) form.
-        let state_tag = bytes.read_u32::<LittleEndian>()?;
-        let state_len = bytes.read_u32::<LittleEndian>()? as usize;
-
-        ensure!(
-            state_tag == INDEX_STATE_TAG,
-            "Attribute with unknown tag: {}",
-            state_tag
-        );
-        ensure!(bytes.len() >= state_len, "Index state is too short");
-
-        let state_bytes = &bytes[0..state_len];
         Ok(Self {
             identifier,
             index_type,
-            state: Some(V::read(state_bytes)?),
+            state,
+            fingerprint,
         })
     }
 }
@@ -267,6 +291,7 @@ impl IndexMetadata {
                     );
                 })
             }),
+            fingerprint: self.fingerprint,
         }
     }
 }
@@ -290,6 +315,12 @@ where
     }
 }
 
+impl<T, V> IndexState<T, V> {
+    pub fn fingerprint(&self) -> Option<u64> {
+        self.metadata.fingerprint
+    }
+}
+
 impl<T, V> IndexState<T, V>
 where
     T: RawAccessMut,
@@ -311,6 +342,11 @@ where
         self.metadata.state = None;
         self.update_metadata_view();
     }
+
+    pub fn set_fingerprint(&mut self, fingerprint: u64) {
+        self.metadata.fingerprint = Some(fingerprint);
+        self.update_metadata_view();
+    }
 }
 
 /// Persistent pool used to store indexes metadata in the database.
@@ -352,6 +388,7 @@ impl<T: RawAccess> IndexesPool<T> {
             identifier: NonZeroU64::new(len + 1).unwrap(),
             index_type,
             state: None,
+            fingerprint: None,
         };
         let is_phantom = !self.0.put_or_forget(index_name, metadata.to_bytes());
         self.set_len(len + 1);
@@ -735,6 +772,27 @@ mod tests {
             identifier: NonZeroU64::new(12).unwrap(),
             index_type: IndexType::ProofList,
             state: Some(16_u64),
+            fingerprint: None,
+        };
+
+        let bytes = metadata.to_bytes();
+        assert_eq!(IndexMetadata::from_bytes(bytes.into()).unwrap(), metadata);
+
+        let metadata = IndexMetadata {
+            identifier: NonZeroU64::new(12).unwrap(),
+            index_type: IndexType::ProofList,
+            state: None::<u64>,
+            fingerprint: None,
+        };
+
+        let bytes = metadata.to_bytes();
+        assert_eq!(IndexMetadata::from_bytes(bytes.into()).unwrap(), metadata);
+
+        let metadata = IndexMetadata {
+            identifier: NonZeroU64::new(12).unwrap(),
+            index_type: IndexType::ProofList,
+            state: Some(16_u64),
+            fingerprint: Some(0xdead_beef),
         };
 
         let bytes = metadata.to_bytes();
@@ -744,6 +802,7 @@ mod tests {
             identifier: NonZeroU64::new(12).unwrap(),
             index_type: IndexType::ProofList,
             state: None::<u64>,
+            fingerprint: Some(1),
         };
 
         let bytes = metadata.to_bytes();
@@ -757,6 +816,7 @@ mod tests {
             identifier: NonZeroU64::new(12).unwrap(),
             index_type: IndexType::ProofList,
             state: Some(16_u64),
+            fingerprint: None,
         };
 
         let mut bytes = metadata.to_bytes();
@@ -549,6 +549,39 @@ where
             _v: PhantomData,
         }
     }
+
+    /// Advances the iterator to the next entry whose raw value bytes satisfy `predicate`.
+    /// Entries not satisfying the predicate are skipped without deserializing them.
+    pub(crate) fn next_matching<F>(&mut self, mut predicate: F) -> Option<(K::Owned, V)>
+    where
+        F: FnMut(&[u8]) -> bool,
+    {
+        if self.ended {
+            return None;
+        }
+
+        while let Some((key_slice, value_slice)) = self.base_iter.next() {
+            if !key_slice.starts_with(&self.prefix) {
+                break;
+            }
+            if !predicate(value_slice) {
+                continue;
+            }
+
+            let key = if self.detach_prefix {
+                // Since we've checked `start_with`, slicing here cannot panic.
+                K::read(&key_slice[self.prefix.len()..])
+            } else {
+                K::read(key_slice)
+            };
+            let value = V::from_bytes(Cow::Borrowed(value_slice))
+                .expect("Unable to decode value from bytes");
+            return Some((key, value));
+        }
+
+        self.ended = true;
+        None
+    }
 }
 
 impl<'a, K, V> Iterator for Iter<'a, K, V>
@@ -390,6 +390,47 @@ fn test_database_check_incorrect_version() {
     RocksDB::open(&dir, &opts).unwrap();
 }
 
+#[test]
+fn compacting_indexes() {
+    const GROUP_ADDR: (&str, &u8) = ("group", &1);
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let db = RocksDB::open(&dir, &DbOptions::default()).unwrap();
+    let fork = db.fork();
+    {
+        let mut map = fork.get_map(IDX_NAME);
+        let mut group_map = fork.get_map(GROUP_ADDR);
+        for i in 0_u8..100 {
+            map.put(&i, vec![i; 64]);
+            group_map.put(&i, vec![i; 64]);
+        }
+    }
+    db.merge(fork.into_patch()).unwrap();
+
+    let fork = db.fork();
+    {
+        let mut map = fork.get_map::<_, u8, Vec<u8>>(IDX_NAME);
+        let mut group_map = fork.get_map::<_, u8, Vec<u8>>(GROUP_ADDR);
+        for i in 0_u8..90 {
+            map.remove(&i);
+            group_map.remove(&i);
+        }
+    }
+    db.merge(fork.into_patch()).unwrap();
+
+    db.compact_index(IDX_NAME);
+    db.compact_index(GROUP_ADDR);
+    // Compacting a non-existing index does nothing.
+    db.compact_index("non_existing");
+
+    let snapshot = db.snapshot();
+    let map = snapshot.get_map::<_, u8, Vec<u8>>(IDX_NAME);
+    assert!(map.keys().eq(90..100));
+    let group_map = snapshot.get_map::<_, u8, Vec<u8>>(GROUP_ADDR);
+    assert!(group_map.iter().eq((90..100).map(|i| (i, vec![i; 64]))));
+    assert!(snapshot.index_type("non_existing").is_none());
+}
+
 #[test]
 fn fork_iter() {
     test_fork_iter(&TemporaryDB::new(), IDX_NAME);
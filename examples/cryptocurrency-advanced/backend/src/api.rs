@@ -20,10 +20,12 @@ use exonum::{
     messages::{AnyTx, Verified},
     runtime::CallerAddress as Address,
 };
-use exonum_merkledb::{proof_map::Raw, ListProof, MapProof};
+use exonum_merkledb::{proof_map::Raw, ListProof, MapProof, ObjectHash};
 use exonum_rust_runtime::api::{self, ServiceApiBuilder, ServiceApiState};
 
-use crate::{schema::SchemaImpl, wallet::Wallet};
+use std::ops::Bound;
+
+use crate::{event::TransferEvent, schema::SchemaImpl, wallet::Wallet};
 
 /// Describes the query parameters for the `get_wallet` endpoint.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -32,6 +34,57 @@ pub struct WalletQuery {
     pub pub_key: PublicKey,
 }
 
+/// Describes the query parameters for the `wallet_history_proof` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WalletHistoryQuery {
+    /// Public key of the queried wallet.
+    pub pub_key: PublicKey,
+    /// Inclusive start of the range of history entries. If not specified, the range starts
+    /// from the first entry.
+    #[serde(default)]
+    pub from: Option<u64>,
+    /// Exclusive end of the range of history entries. If not specified, the range ends
+    /// with the last entry.
+    #[serde(default)]
+    pub to: Option<u64>,
+}
+
+/// Describes the query parameters for the `event_log_proof` endpoint.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct EventLogQuery {
+    /// Inclusive start of the range of events. If not specified, the range starts
+    /// from the first event.
+    #[serde(default)]
+    pub from: Option<u64>,
+    /// Exclusive end of the range of events. If not specified, the range ends
+    /// with the last event.
+    #[serde(default)]
+    pub to: Option<u64>,
+}
+
+/// Describes the query parameters for the `wallet_history` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WalletHistoryPageQuery {
+    /// Public key of the queried wallet.
+    pub pub_key: PublicKey,
+    /// Index of the first history entry in the page.
+    #[serde(default)]
+    pub offset: u64,
+    /// Maximum number of history entries in the page. Must not exceed
+    /// `MAX_HISTORY_PAGE_LIMIT`.
+    #[serde(default = "default_history_page_limit")]
+    pub limit: usize,
+}
+
+/// Default maximum number of entries in a page of wallet history.
+pub const DEFAULT_HISTORY_PAGE_LIMIT: usize = 100;
+/// Largest allowed `limit` for a page of wallet history.
+pub const MAX_HISTORY_PAGE_LIMIT: usize = 1_000;
+
+fn default_history_page_limit() -> usize {
+    DEFAULT_HISTORY_PAGE_LIMIT
+}
+
 /// Proof of existence for specific wallet.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WalletProof {
@@ -50,6 +103,57 @@ pub struct WalletHistory {
     pub transactions: Vec<Verified<AnyTx>>,
 }
 
+/// Page of wallet history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalletHistoryPage {
+    /// Hashes of transactions in the page.
+    pub transactions: Vec<Hash>,
+    /// Total number of entries in the wallet history.
+    pub total: u64,
+}
+
+/// Proof for a range of wallet history.
+///
+/// The proof can be verified against `history_hash`, which should be equal to the
+/// `history_hash` field of the wallet record.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalletHistoryProof {
+    /// Hash of the wallet history.
+    pub history_hash: Hash,
+    /// Proof of transaction hashes in the requested range of the history.
+    pub proof: ListProof<Hash>,
+}
+
+/// Proof linking the state of the blockchain to the entire history of a specific wallet.
+///
+/// The proof is verified as a chain: `block_proof` and `wallet_proof.to_table` authenticate
+/// the hash of the wallets table, `wallet_proof.to_wallet` authenticates the wallet record
+/// against this hash, and `history_proof` authenticates transaction hashes in the wallet history
+/// against the `history_hash` field of the record.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalletHistoryFullProof {
+    /// Proof of the last block.
+    pub block_proof: BlockProof,
+    /// Proof of the appropriate wallet.
+    pub wallet_proof: WalletProof,
+    /// Proof of all transaction hashes in the wallet history.
+    pub history_proof: ListProof<Hash>,
+}
+
+/// Proof linking the state of the blockchain to a range of the event log.
+///
+/// The proof is verified as a chain: `block_proof` and `to_table` authenticate the hash
+/// of the event log, and `proof` authenticates events in the requested range against this hash.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventLogProof {
+    /// Proof of the last block.
+    pub block_proof: BlockProof,
+    /// Proof of the event log in the table of service indexes.
+    pub to_table: MapProof<String, Hash>,
+    /// Proof of events in the requested range of the log.
+    pub proof: ListProof<TransferEvent>,
+}
+
 /// Wallet information.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WalletInfo {
@@ -110,6 +214,122 @@ impl PublicApi {
         })
     }
 
+    /// Endpoint for getting a page of wallet history.
+    pub async fn wallet_history(
+        state: ServiceApiState,
+        query: WalletHistoryPageQuery,
+    ) -> api::Result<WalletHistoryPage> {
+        let currency_schema = SchemaImpl::new(state.service_data());
+        let address = Address::from_key(query.pub_key);
+        if currency_schema.wallet(address).is_none() {
+            return Err(api::Error::not_found().title("Wallet not found"));
+        }
+        if query.limit > MAX_HISTORY_PAGE_LIMIT {
+            return Err(api::Error::bad_request()
+                .title("Invalid history page limit")
+                .detail(format!(
+                    "Limit must not exceed {}, got {}",
+                    MAX_HISTORY_PAGE_LIMIT, query.limit
+                )));
+        }
+
+        let (transactions, total) =
+            currency_schema.wallet_history_page(address, query.offset, query.limit);
+        Ok(WalletHistoryPage {
+            transactions,
+            total,
+        })
+    }
+
+    /// Endpoint for getting the proof for a range of wallet history.
+    pub async fn wallet_history_proof(
+        state: ServiceApiState,
+        query: WalletHistoryQuery,
+    ) -> api::Result<WalletHistoryProof> {
+        let currency_schema = SchemaImpl::new(state.service_data());
+        let address = Address::from_key(query.pub_key);
+        if currency_schema.wallet(address).is_none() {
+            return Err(api::Error::not_found().title("Wallet not found"));
+        }
+
+        // `history` is always present for existing wallets.
+        let history = currency_schema.wallet_history.get(&address);
+        let from = Bound::Included(query.from.unwrap_or(0));
+        let to = query.to.map_or(Bound::Unbounded, Bound::Excluded);
+        let proof = history.try_get_range_proof((from, to)).map_err(|err| {
+            api::Error::bad_request()
+                .title("Invalid history range")
+                .detail(err.to_string())
+        })?;
+
+        Ok(WalletHistoryProof {
+            history_hash: history.object_hash(),
+            proof,
+        })
+    }
+
+    /// Endpoint for getting the proof of the entire wallet history tied to the latest block.
+    pub async fn wallet_history_full_proof(
+        state: ServiceApiState,
+        query: WalletQuery,
+    ) -> api::Result<WalletHistoryFullProof> {
+        let currency_schema = SchemaImpl::new(state.service_data());
+        let address = Address::from_key(query.pub_key);
+        if currency_schema.wallet(address).is_none() {
+            return Err(api::Error::not_found().title("Wallet not found"));
+        }
+
+        let IndexProof {
+            block_proof,
+            index_proof,
+            ..
+        } = state.data().proof_for_service_index("wallets").unwrap();
+        let (to_wallet, history_proof) = currency_schema.wallet_history_full_proof(address);
+
+        Ok(WalletHistoryFullProof {
+            block_proof,
+            wallet_proof: WalletProof {
+                to_table: index_proof,
+                to_wallet,
+            },
+            history_proof,
+        })
+    }
+
+    /// Endpoint for getting the proof for a range of the event log tied to the latest block.
+    pub async fn event_log_proof(
+        state: ServiceApiState,
+        query: EventLogQuery,
+    ) -> api::Result<EventLogProof> {
+        let IndexProof {
+            block_proof,
+            index_proof,
+            ..
+        } = state
+            .data()
+            .proof_for_service_index("events")
+            .ok_or_else(|| api::Error::not_found().title("Event log not found"))?;
+
+        let currency_schema = SchemaImpl::new(state.service_data());
+        let from = Bound::Included(query.from.unwrap_or(0));
+        let to = query.to.map_or(Bound::Unbounded, Bound::Excluded);
+        let proof = currency_schema
+            .public
+            .events
+            .try_get_range_proof((from, to))
+            .map_err(|err| {
+                api::Error::bad_request()
+                    .title("Invalid event range")
+                    .detail(err.to_string())
+            })?;
+
+        Ok(EventLogProof {
+            block_proof,
+            to_table: index_proof,
+            proof,
+        })
+    }
+
     /// Endpoint for getting approval transactions history for sender's wallet.
     pub async fn wallet_approval_transactions_history(
         state: ServiceApiState,
@@ -160,7 +380,14 @@ impl PublicApi {
     pub fn wire(builder: &mut ServiceApiBuilder) {
         builder
             .public_scope()
-            .endpoint("v1/wallets/info", Self::wallet_info);
+            .endpoint("v1/wallets/info", Self::wallet_info)
+            .endpoint("v1/wallets/history", Self::wallet_history)
+            .endpoint("v1/wallets/history_proof", Self::wallet_history_proof)
+            .endpoint(
+                "v1/wallets/history_full_proof",
+                Self::wallet_history_full_proof,
+            )
+            .endpoint("v1/events/proof", Self::event_log_proof);
     }
 
     /// Approve api
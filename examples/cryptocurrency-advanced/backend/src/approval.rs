@@ -0,0 +1,62 @@
+// Copyright 2020 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Transfers waiting for approval.
+
+use exonum::{helpers::Height, runtime::CallerAddress as Address};
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use crate::proto;
+
+/// Information about a transfer with approval stored in the database until the transfer
+/// is approved or expires.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::service::PendingApproval", serde_pb_convert)]
+pub struct PendingApproval {
+    /// Address of sender's wallet.
+    pub from: Address,
+    /// Address of receiver's wallet.
+    pub to: Address,
+    /// Amount of freezed currency.
+    pub amount: u64,
+    /// Address of approver's wallet.
+    pub approver: Address,
+    /// Height of the block starting from which the approval is expired.
+    pub expires_at: Height,
+}
+
+impl PendingApproval {
+    /// Creates a new pending approval.
+    pub fn new(
+        from: Address,
+        to: Address,
+        amount: u64,
+        approver: Address,
+        expires_at: Height,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            amount,
+            approver,
+            expires_at,
+        }
+    }
+
+    /// Checks whether the approval is expired at the specified height.
+    pub fn is_expired(&self, height: Height) -> bool {
+        height >= self.expires_at
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2020 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Events of the balance changes recorded in the event log.
+
+use exonum::{crypto::Hash, runtime::CallerAddress as Address};
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use crate::proto;
+
+/// Kind of the balance change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferEventKind {
+    /// Funds are credited to the `to` wallet.
+    Credit,
+    /// Funds are debited from the `from` wallet.
+    Debit,
+    /// Funds of the `from` wallet are freezed until the transfer to the `to` wallet
+    /// is approved.
+    Freeze,
+    /// Freezed funds of the `from` wallet are released because the transfer
+    /// to the `to` wallet is rejected or expired.
+    Release,
+}
+
+impl ProtobufConvert for TransferEventKind {
+    type ProtoStruct = proto::service::TransferEventKind;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        match self {
+            Self::Credit => proto::service::TransferEventKind::CREDIT,
+            Self::Debit => proto::service::TransferEventKind::DEBIT,
+            Self::Freeze => proto::service::TransferEventKind::FREEZE,
+            Self::Release => proto::service::TransferEventKind::RELEASE,
+        }
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+        let result = match pb {
+            proto::service::TransferEventKind::CREDIT => Self::Credit,
+            proto::service::TransferEventKind::DEBIT => Self::Debit,
+            proto::service::TransferEventKind::FREEZE => Self::Freeze,
+            proto::service::TransferEventKind::RELEASE => Self::Release,
+        };
+        Ok(result)
+    }
+}
+
+/// Change of a wallet balance stored in the event log.
+///
+/// The changed wallet is `to` for credits and `from` for other kinds of events.
+/// For issued funds, both `from` and `to` are the address of the receiving wallet.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::service::TransferEvent", serde_pb_convert)]
+pub struct TransferEvent {
+    /// Address of sender's wallet.
+    pub from: Address,
+    /// Address of receiver's wallet.
+    pub to: Address,
+    /// Amount of currency.
+    pub amount: u64,
+    /// Hash of the transaction which caused the change.
+    pub tx_hash: Hash,
+    /// Kind of the change.
+    pub kind: TransferEventKind,
+}
+
+impl TransferEvent {
+    /// Creates a new event.
+    pub fn new(
+        from: Address,
+        to: Address,
+        amount: u64,
+        tx_hash: Hash,
+        kind: TransferEventKind,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            amount,
+            tx_hash,
+            kind,
+        }
+    }
+}
@@ -23,14 +23,20 @@ extern crate serde_derive; // Required for Protobuf.
 pub use crate::{schema::Schema, transactions::CryptocurrencyInterface};
 
 pub mod api;
+pub mod approval;
+pub mod event;
 pub mod migrations;
 pub mod proto;
 pub mod schema;
 pub mod transactions;
 pub mod wallet;
 
-use exonum::runtime::{ExecutionContext, ExecutionError, InstanceId};
-use exonum_derive::{ServiceDispatcher, ServiceFactory};
+use exonum::{
+    merkledb::BinaryValue,
+    runtime::{CommonError, ExecutionContext, ExecutionError, InstanceId},
+};
+use exonum_derive::{BinaryValue, ObjectHash, ServiceDispatcher, ServiceFactory};
+use exonum_proto::ProtobufConvert;
 use exonum_rust_runtime::{api::ServiceApiBuilder, DefaultInstance, Service};
 
 use crate::{api::PublicApi as CryptocurrencyApi, schema::SchemaImpl};
@@ -38,6 +44,18 @@ use crate::{api::PublicApi as CryptocurrencyApi, schema::SchemaImpl};
 /// Initial balance of the wallet.
 pub const INITIAL_BALANCE: u64 = 100;
 
+/// Default number of blocks after which pending approvals expire.
+pub const DEFAULT_APPROVAL_EXPIRY: u64 = 100;
+
+/// Cryptocurrency service configuration passed to the service on its initialization.
+#[derive(Clone, Debug, Default, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::service::Config")]
+pub struct Config {
+    /// Number of blocks after which pending approvals expire and freezed funds
+    /// are released. If set to zero, `DEFAULT_APPROVAL_EXPIRY` is used.
+    pub approval_expiry: u64,
+}
+
 /// Cryptocurrency service implementation.
 #[derive(Debug, ServiceDispatcher, ServiceFactory)]
 #[service_dispatcher(implements("CryptocurrencyInterface"))]
@@ -48,11 +66,21 @@ impl Service for CryptocurrencyService {
     fn initialize(
         &self,
         context: ExecutionContext<'_>,
-        _params: Vec<u8>,
+        params: Vec<u8>,
     ) -> Result<(), ExecutionError> {
+        let config = Config::from_bytes(params.into()).map_err(CommonError::malformed_arguments)?;
         // Initialize indexes. Not doing this may lead to errors in HTTP API, since it relies on
         // `wallets` indexes being initialized for returning corresponding proofs.
-        SchemaImpl::new(context.service_data());
+        let mut schema = SchemaImpl::new(context.service_data());
+        schema.config.set(config);
+        Ok(())
+    }
+
+    /// Releases freezed funds of the expired approvals.
+    fn after_transactions(&self, context: ExecutionContext<'_>) -> Result<(), ExecutionError> {
+        let height = context.data().for_core().next_height();
+        let mut schema = SchemaImpl::new(context.service_data());
+        schema.release_expired_approvals(height)?;
         Ok(())
     }
 
@@ -16,17 +16,25 @@
 
 use exonum::{
     crypto::Hash,
+    helpers::Height,
     merkledb::{
         access::{Access, FromAccess, RawAccessMut},
-        Group, ObjectHash, ProofListIndex, RawProofMapIndex,
+        proof_map::Raw,
+        Entry, Group, ListProof, MapProof, ObjectHash, ProofListIndex, RawProofMapIndex,
     },
     runtime::CallerAddress as Address,
 };
 use exonum_derive::{FromAccess, RequireArtifact};
 
-use crate::{wallet::Wallet, INITIAL_BALANCE};
-use crate::{transactions::TxSendApprove};
-use crate::{transactions::TxApprove};
+use std::convert::TryFrom;
+
+use crate::{
+    approval::PendingApproval,
+    event::{TransferEvent, TransferEventKind},
+    transactions::{Error, TxApprove, TxReject},
+    wallet::Wallet,
+    Config, DEFAULT_APPROVAL_EXPIRY, INITIAL_BALANCE,
+};
 
 /// Database schema for the cryptocurrency.
 ///
@@ -37,7 +45,9 @@ pub(crate) struct SchemaImpl<T: Access> {
     #[from_access(flatten)]
     pub public: Schema<T>,
     /// History for specific wallets.
-    pub wallet_history: Group<T, Address, ProofListIndex<T::Base, Hash>>
+    pub wallet_history: Group<T, Address, ProofListIndex<T::Base, Hash>>,
+    /// Service configuration.
+    pub config: Entry<T::Base, Config>,
 }
 
 /// Public part of the cryptocurrency schema.
@@ -47,9 +57,13 @@ pub struct Schema<T: Access> {
     /// Map of wallet keys to information about the corresponding account.
     pub wallets: RawProofMapIndex<T::Base, Address, Wallet>,
     /// Map of approval transactions hash to infromation about the corresponding approval transaction
-    pub approval_transactions: RawProofMapIndex<T::Base, Hash, TxSendApprove>,
+    pub approval_transactions: RawProofMapIndex<T::Base, Hash, PendingApproval>,
     /// Map of approved tx_send_approved transactions
-    pub approved_transactions: RawProofMapIndex<T::Base, Hash, TxApprove>
+    pub approved_transactions: RawProofMapIndex<T::Base, Hash, TxApprove>,
+    /// Map of rejected tx_send_approved transactions
+    pub rejected_transactions: RawProofMapIndex<T::Base, Hash, TxReject>,
+    /// Log of balance changes of all wallets.
+    pub events: ProofListIndex<T::Base, TransferEvent>,
 }
 
 impl<T: Access> SchemaImpl<T> {
@@ -60,6 +74,45 @@ impl<T: Access> SchemaImpl<T> {
     pub fn wallet(&self, address: Address) -> Option<Wallet> {
         self.public.wallets.get(&address)
     }
+
+    /// Returns a page of the wallet history consisting of at most `limit` transaction hashes
+    /// starting from `offset`, together with the total length of the history.
+    ///
+    /// If `offset` is past the end of the history, the returned page is empty.
+    pub fn wallet_history_page(
+        &self,
+        address: Address,
+        offset: u64,
+        limit: usize,
+    ) -> (Vec<Hash>, u64) {
+        let history = self.wallet_history.get(&address);
+        let page = history.iter_from(offset).take(limit).collect();
+        (page, history.len())
+    }
+
+    /// Returns the proof of the wallet record in the wallets table together with the proof
+    /// of the entire wallet history.
+    ///
+    /// The history proof can be checked against the `history_hash` of the wallet record
+    /// obtained from the map proof. If the wallet does not exist, the map proof attests
+    /// to its absence, and the history proof is a proof for an empty list.
+    pub fn wallet_history_full_proof(
+        &self,
+        address: Address,
+    ) -> (MapProof<Address, Wallet, Raw>, ListProof<Hash>) {
+        let to_wallet = self.public.wallets.get_proof(address);
+        let history = self.wallet_history.get(&address);
+        (to_wallet, history.get_range_proof(..))
+    }
+
+    /// Returns the number of blocks after which pending approvals expire.
+    pub fn approval_expiry(&self) -> u64 {
+        match self.config.get() {
+            Some(Config { approval_expiry }) if approval_expiry > 0 => approval_expiry,
+            // The config is missing if the service data was migrated from the old version.
+            _ => DEFAULT_APPROVAL_EXPIRY,
+        }
+    }
 }
 
 impl<T> SchemaImpl<T>
@@ -69,71 +122,256 @@ where
 {
     /// Append new unapproved transaction record to db.
     /// 'wallet' - wallet of sender
-    pub fn create_send_approve_transaction(&mut self, wallet: Wallet, amount: u64, to: Address, approver: Address, tx_hash: Hash) {
+    /// 'height' - height of the block containing the transaction
+    pub fn create_send_approve_transaction(
+        &mut self,
+        wallet: Wallet,
+        amount: u64,
+        to: Address,
+        approver: Address,
+        height: Height,
+        tx_hash: Hash,
+    ) -> Result<(), Error> {
+        let from = wallet.owner;
         // Update freezed balance & save the history
-        self.change_wallet_balance(wallet, 0, amount as i64, tx_hash);
+        self.change_wallet_balance(wallet, to, 0, i128::from(amount), tx_hash)?;
 
         // Save transaction in schema.approval_transactions
-        let transaction = TxSendApprove::new(to, amount, approver);
-        self.public.approval_transactions.put(&tx_hash, transaction);
+        let expires_at = Height(height.0.saturating_add(self.approval_expiry()));
+        let approval = PendingApproval::new(from, to, amount, approver, expires_at);
+        self.public.approval_transactions.put(&tx_hash, approval);
+        Ok(())
     }
 
-    /// Append new unapproved transaction record to db.
-    /// 'wallet' - wallet of sender
-    pub fn create_approve_transaction(&mut self, sender_wallet: Wallet, receiver_wallet: Wallet, amount: u64, tx_approve: TxApprove, tx_hash: Hash) {
-        let neg_amount = (amount as i64) * -1;
-        let pos_amount = amount as i64;
-        
-        // Update sender_wallet & save the history
-        self.change_wallet_balance(sender_wallet, neg_amount, neg_amount, tx_hash);
+    /// Releases freezed funds of the approvals expired at the specified `height`
+    /// and removes these approvals.
+    pub fn release_expired_approvals(&mut self, height: Height) -> Result<(), Error> {
+        let expired_approvals: Vec<_> = self
+            .public
+            .approval_transactions
+            .iter()
+            .filter(|(_, approval)| approval.is_expired(height))
+            .collect();
+
+        for (tx_hash, approval) in expired_approvals {
+            if let Some(wallet) = self.wallet(approval.from) {
+                self.change_wallet_balance(
+                    wallet,
+                    approval.to,
+                    0,
+                    -i128::from(approval.amount),
+                    tx_hash,
+                )?;
+            }
+            self.public.approval_transactions.remove(&tx_hash);
+        }
+        Ok(())
+    }
+
+    /// Append new approval transaction record to db, transferring the approved part
+    /// of the freezed `amount` to the receiver and releasing the rest to the sender.
+    /// 'sender_wallet' - wallet of sender of the approved transaction
+    /// 'receiver_wallet' - wallet of receiver of the approved transaction
+    pub fn create_approve_transaction(
+        &mut self,
+        sender_wallet: Wallet,
+        receiver_wallet: Wallet,
+        amount: u64,
+        tx_approve: TxApprove,
+        tx_hash: Hash,
+    ) -> Result<(), Error> {
+        let amount = i128::from(amount);
+        let approved_amount = i128::from(tx_approve.approved_amount);
+        let from = sender_wallet.owner;
+        let to = receiver_wallet.owner;
+
+        // Update sender_wallet & save the history. All freezed funds are unfreezed,
+        // but only the approved part leaves the wallet.
+        self.change_wallet_balance(sender_wallet, to, -approved_amount, -amount, tx_hash)?;
         // Update receiver_wallet & save the history
-        self.change_wallet_balance(receiver_wallet, pos_amount, 0, tx_hash);
+        if approved_amount > 0 {
+            self.change_wallet_balance(receiver_wallet, from, approved_amount, 0, tx_hash)?;
+        }
 
+        // Remove transaction from schema.approval_transactions, so that its funds
+        // are not released on expiry
+        self.public
+            .approval_transactions
+            .remove(&tx_approve.approval_hash);
         // Save transaction in schema.approved_transactions
-        self.public.approved_transactions.put(&tx_hash, tx_approve.clone());
+        self.public.approved_transactions.put(&tx_hash, tx_approve);
+        Ok(())
+    }
+
+    /// Append new rejection transaction record to db and release freezed funds of the sender.
+    /// 'sender_wallet' - wallet of sender of the rejected transaction
+    /// 'to' - address of receiver of the rejected transaction
+    pub fn create_reject_transaction(
+        &mut self,
+        sender_wallet: Wallet,
+        to: Address,
+        amount: u64,
+        tx_reject: TxReject,
+        tx_hash: Hash,
+    ) -> Result<(), Error> {
+        // Update freezed balance & save the history
+        self.change_wallet_balance(sender_wallet, to, 0, -i128::from(amount), tx_hash)?;
+
+        // Remove transaction from schema.approval_transactions
+        self.public
+            .approval_transactions
+            .remove(&tx_reject.approval_hash);
+        // Save transaction in schema.rejected_transactions
+        self.public.rejected_transactions.put(&tx_hash, tx_reject);
+        Ok(())
     }
 
-    pub fn change_wallet_balance(&mut self, wallet: Wallet, balance_change: i64, freezed_balance_change: i64, transaction: Hash) {
+    /// Changes balance and freezed balance of the wallet and append new record to its history
+    /// and to the event log. `counterparty` is the address of the other wallet participating
+    /// in the transfer.
+    ///
+    /// Returns `Error::BalanceOverflow` and leaves the wallet unchanged if either balance
+    /// would become negative or exceed `u64::max_value()`.
+    pub fn change_wallet_balance(
+        &mut self,
+        wallet: Wallet,
+        counterparty: Address,
+        balance_change: i128,
+        freezed_balance_change: i128,
+        transaction: Hash,
+    ) -> Result<(), Error> {
+        let balance = apply_balance_change(wallet.balance, balance_change)?;
+        let freezed_balance = apply_balance_change(wallet.freezed_balance, freezed_balance_change)?;
+
+        // Save the changes in the event log. Approved transfers debit the freezed funds,
+        // so only the part of unfreezed funds exceeding the debited amount is released.
+        let debited = (-balance_change).max(0);
+        let changes = [
+            (TransferEventKind::Credit, balance_change.max(0)),
+            (TransferEventKind::Debit, debited),
+            (TransferEventKind::Freeze, freezed_balance_change.max(0)),
+            (
+                TransferEventKind::Release,
+                (-freezed_balance_change - debited).max(0),
+            ),
+        ];
+        for &(kind, change) in &changes {
+            if change > 0 {
+                let amount = u64::try_from(change).expect("Balance change was checked above");
+                self.push_event(wallet.owner, counterparty, amount, transaction, kind);
+            }
+        }
+
         // Save transaction in wallet's history
         let mut history = self.wallet_history.get(&wallet.owner);
         history.push(transaction);
         let history_hash = history.object_hash();
 
-        let wallet_freezed_balance = wallet.freezed_balance;
-        let wallet_balance = wallet.balance;
-
-        let wallet = wallet.set_balance(((wallet_balance as i64) + balance_change) as u64, &history_hash);
-        let wallet = wallet.set_freezed_balance(((wallet_freezed_balance as i64) + freezed_balance_change) as u64, &history_hash);
+        // Both balances are updated at once, so that the wallet accounts for a single
+        // history entry.
+        let wallet = Wallet::new(
+            wallet.owner,
+            &wallet.name,
+            balance,
+            freezed_balance,
+            history.len(),
+            &history_hash,
+        );
 
         // storing in wallets-db
         let wallet_key = wallet.owner;
         self.public.wallets.put(&wallet_key, wallet);
+        Ok(())
     }
 
-    /// Increases balance of the wallet and append new record to its history.
-    pub fn increase_wallet_balance(&mut self, wallet: Wallet, amount: u64, transaction: Hash) {
+    /// Increases balance of the wallet and append new record to its history and to the event log.
+    /// `from` is the address of the sender of the funds.
+    ///
+    /// Returns `Error::BalanceOverflow` and leaves the wallet unchanged if the balance
+    /// would exceed `u64::max_value()`.
+    pub fn increase_wallet_balance(
+        &mut self,
+        wallet: Wallet,
+        from: Address,
+        amount: u64,
+        transaction: Hash,
+    ) -> Result<(), Error> {
+        let balance = apply_balance_change(wallet.balance, i128::from(amount))?;
+        self.push_event(
+            wallet.owner,
+            from,
+            amount,
+            transaction,
+            TransferEventKind::Credit,
+        );
         let mut history = self.wallet_history.get(&wallet.owner);
         history.push(transaction);
         let history_hash = history.object_hash();
-        let balance = wallet.balance;
-        let wallet = wallet.set_balance(balance + amount, &history_hash);
+        let wallet = wallet.set_balance(balance, &history_hash);
         let wallet_key = wallet.owner;
         self.public.wallets.put(&wallet_key, wallet);
+        Ok(())
     }
 
-    /// Decreases balance of the wallet and append new record to its history.
-    pub fn decrease_wallet_balance(&mut self, wallet: Wallet, amount: u64, transaction: Hash) {
+    /// Decreases balance of the wallet and append new record to its history and to the event log.
+    /// `to` is the address of the receiver of the funds.
+    ///
+    /// Returns `Error::BalanceOverflow` and leaves the wallet unchanged if the balance
+    /// would become negative.
+    pub fn decrease_wallet_balance(
+        &mut self,
+        wallet: Wallet,
+        to: Address,
+        amount: u64,
+        transaction: Hash,
+    ) -> Result<(), Error> {
+        let balance = apply_balance_change(wallet.balance, -i128::from(amount))?;
+        self.push_event(
+            wallet.owner,
+            to,
+            amount,
+            transaction,
+            TransferEventKind::Debit,
+        );
         let mut history = self.wallet_history.get(&wallet.owner);
         history.push(transaction);
         let history_hash = history.object_hash();
-        let balance = wallet.balance;
-        let wallet = wallet.set_balance(balance - amount, &history_hash);
+        let wallet = wallet.set_balance(balance, &history_hash);
         let wallet_key = wallet.owner;
         self.public.wallets.put(&wallet_key, wallet);
+        Ok(())
+    }
+
+    /// Appends a change of the `wallet` balance to the event log.
+    fn push_event(
+        &mut self,
+        wallet: Address,
+        counterparty: Address,
+        amount: u64,
+        transaction: Hash,
+        kind: TransferEventKind,
+    ) {
+        let (from, to) = match kind {
+            TransferEventKind::Credit => (counterparty, wallet),
+            TransferEventKind::Debit | TransferEventKind::Freeze | TransferEventKind::Release => {
+                (wallet, counterparty)
+            }
+        };
+        self.public
+            .events
+            .push(TransferEvent::new(from, to, amount, transaction, kind));
     }
 
-    /// Creates a new wallet and append first record to its history.
+    /// Creates a new wallet and append first record to its history. The initial balance
+    /// of the wallet is recorded in the event log as credited by the wallet itself.
     pub fn create_wallet(&mut self, key: Address, name: &str, transaction: Hash) {
+        self.push_event(
+            key,
+            key,
+            INITIAL_BALANCE,
+            transaction,
+            TransferEventKind::Credit,
+        );
         let mut history = self.wallet_history.get(&key);
         history.push(transaction);
         let history_hash = history.object_hash();
@@ -141,3 +379,8 @@ where
         self.public.wallets.put(&key, wallet);
     }
 }
+
+/// Applies a signed change to the balance, checking that the result fits into `u64`.
+fn apply_balance_change(balance: u64, change: i128) -> Result<u64, Error> {
+    u64::try_from(i128::from(balance) + change).map_err(|_| Error::BalanceOverflow)
+}
@@ -42,12 +42,32 @@ pub enum Error {
     InsufficientCurrencyAmount = 3,
     /// Sender are same as receiver.
     ///
-    /// Can be emitted by 'Transfer`.
+    /// Can be emitted by `Transfer` or `TxSendApprove`.
     SenderSameAsReceiver = 4,
     /// Approver doesn't exist.
     ///
     /// Can be emitted by `TxSendApprove`.
-    ApproverNotFound = 5
+    ApproverNotFound = 5,
+    /// Wallet balance would become negative or exceed the maximum value.
+    ///
+    /// Can be emitted by `Transfer`, `Issue`, `TxSendApprove` or `TxApprove`.
+    BalanceOverflow = 6,
+    /// Transaction with approval doesn't exist or is already approved, rejected or expired.
+    ///
+    /// Can be emitted by `TxApprove` or `TxReject`.
+    ApprovalNotFound = 7,
+    /// Caller is not the approver of the transaction.
+    ///
+    /// Can be emitted by `TxApprove` or `TxReject`.
+    NotApprover = 8,
+    /// Transfer amount is zero.
+    ///
+    /// Can be emitted by `TxSendApprove`.
+    ZeroAmount = 9,
+    /// Approved amount exceeds the freezed amount of the transfer.
+    ///
+    /// Can be emitted by `TxApprove`.
+    ApprovedAmountTooLarge = 10,
 }
 
 /// Transfer `amount` of the currency from one wallet to another.
@@ -65,7 +85,9 @@ pub struct Transfer {
     pub seed: u64
 }
 
-/// Information about transaction with approval stored in the database.
+/// Transfer `amount` of the currency from one wallet to another with approval
+/// from the third person. The funds are freezed until the transfer is approved or
+/// the approval expires.
 #[derive(Clone, Debug)]
 #[derive(ProtobufConvert, BinaryValue, ObjectHash)]
 #[protobuf_convert(source = "proto::service::TxSendApprove", serde_pb_convert)]
@@ -82,17 +104,29 @@ pub struct TxSendApprove {
     pub approver: Address
 }
 
-/// Information about transaction with approval stored in the database.
+/// Approve the transaction with approval, transferring the freezed funds of the sender
+/// to the receiver.
 #[derive(Clone, Debug)]
 #[derive(ProtobufConvert, BinaryValue, ObjectHash)]
 #[protobuf_convert(source = "proto::service::TxApprove", serde_pb_convert)]
 pub struct TxApprove {
-    /// Address of sender person
-    pub from: Address,
-    /// Address of receiver's wallet.
-    pub to: Address,
-    /// Amount of currency to transfer.
-    pub amount: u64,
+    /// Hash of the transaction with approval to approve.
+    pub approval_hash: Hash,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+    /// Part of the freezed amount transferred to the receiver. The rest of the freezed funds
+    /// is released back to the sender.
+    pub approved_amount: u64,
+}
+
+/// Reject the transaction with approval, releasing the freezed funds of the sender.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::service::TxReject", serde_pb_convert)]
+pub struct TxReject {
+    /// Hash of the transaction with approval to reject.
+    pub approval_hash: Hash,
     /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
     ///
     /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
@@ -117,24 +151,32 @@ impl TxSendApprove {
     }
 }
 
-impl TxApprove {
-    /// Creates a new approval transaction.
-    pub fn new(
-        from: Address,
-        to: Address,
-        amount: u64
-    ) -> Self {
+impl TxReject {
+    /// Creates a new rejection transaction.
+    pub fn new(approval_hash: Hash) -> Self {
         let mut rng = rand::thread_rng();
 
         Self {
-            from: from,
-            to: to,
-            amount: amount,
+            approval_hash,
             seed: rng.gen::<u64>()
         }
     }
 }
 
+impl TxApprove {
+    /// Creates a new approval transaction, which transfers `approved_amount` out of
+    /// the freezed funds of the transfer and releases the rest to the sender.
+    pub fn new(approval_hash: Hash, approved_amount: u64) -> Self {
+        let mut rng = rand::thread_rng();
+
+        Self {
+            approval_hash,
+            seed: rng.gen::<u64>(),
+            approved_amount,
+        }
+    }
+}
+
 /// Issue `amount` of the currency to the `wallet`.
 #[derive(Clone, Debug)]
 #[derive(Serialize, Deserialize)]
@@ -187,6 +229,9 @@ pub trait CryptocurrencyInterface<Ctx> {
     /// Approve transaction tx_send_approve
     #[interface_method(id = 4)]
     fn tx_approve(&self, ctx: Ctx, arg: TxApprove) -> Self::Output;
+    /// Reject transaction tx_send_approve
+    #[interface_method(id = 5)]
+    fn tx_reject(&self, ctx: Ctx, arg: TxReject) -> Self::Output;
 }
 
 impl CryptocurrencyInterface<ExecutionContext<'_>> for CryptocurrencyService {
@@ -207,8 +252,8 @@ impl CryptocurrencyInterface<ExecutionContext<'_>> for CryptocurrencyService {
         if sender.balance - sender.freezed_balance  < amount {
             Err(Error::InsufficientCurrencyAmount.into())
         } else {
-            schema.decrease_wallet_balance(sender, amount, tx_hash);
-            schema.increase_wallet_balance(receiver, amount, tx_hash);
+            schema.decrease_wallet_balance(sender, to, amount, tx_hash)?;
+            schema.increase_wallet_balance(receiver, from, amount, tx_hash)?;
             Ok(())
         }
     }
@@ -223,6 +268,9 @@ impl CryptocurrencyInterface<ExecutionContext<'_>> for CryptocurrencyService {
         if from == to {
             return Err(Error::SenderSameAsReceiver.into());
         }
+        if amount == 0 {
+            return Err(Error::ZeroAmount.into());
+        }
 
         // Check sender's wallet exists
         let sender_wallet = schema.wallet(from).ok_or(Error::SenderNotFound)?;
@@ -235,35 +283,91 @@ impl CryptocurrencyInterface<ExecutionContext<'_>> for CryptocurrencyService {
         if sender_wallet.balance - sender_wallet.freezed_balance < amount {
             Err(Error::InsufficientCurrencyAmount.into())
         } else {
-            schema.create_send_approve_transaction(sender_wallet, amount, to, arg.approver, tx_hash);
+            let height = context.data().for_core().next_height();
+            schema.create_send_approve_transaction(
+                sender_wallet,
+                amount,
+                to,
+                arg.approver,
+                height,
+                tx_hash,
+            )?;
             Ok(())
         }
     }
 
     fn tx_approve(&self, context: ExecutionContext<'_>, arg: TxApprove) -> Self::Output {
         // Getting schema
-        let (_approver, tx_hash) = extract_info(&context)?;
+        let (approver, tx_hash) = extract_info(&context)?;
         let mut schema = SchemaImpl::new(context.service_data());
 
-        let from = arg.from;
-        let to = arg.to;
-        let amount = arg.amount;
+        // Check the approval is pending
+        let approval = schema
+            .public
+            .approval_transactions
+            .get(&arg.approval_hash)
+            .ok_or(Error::ApprovalNotFound)?;
+        // Check the approval is not expired. Expired approvals are released only after
+        // all transactions in the block are executed.
+        let height = context.data().for_core().next_height();
+        if approval.is_expired(height) {
+            return Err(Error::ApprovalNotFound.into());
+        }
+        // Check the caller is the designated approver
+        if approval.approver != approver {
+            return Err(Error::NotApprover.into());
+        }
+        if arg.approved_amount > approval.amount {
+            return Err(Error::ApprovedAmountTooLarge.into());
+        }
 
-        // Check sender's waller exists
-        let sender_wallet = schema.wallet(from).ok_or(Error::SenderNotFound)?;
-        // Check receiver's waller exists
-        let receiver_wallet = schema.wallet(to).ok_or(Error::ReceiverNotFound)?;
-        // Check approver's wallet exists
-        let _approver_wallet = schema.wallet(_approver).ok_or(Error::ApproverNotFound)?;
+        // Check sender's wallet exists
+        let sender_wallet = schema.wallet(approval.from).ok_or(Error::SenderNotFound)?;
+        // Check receiver's wallet exists
+        let receiver_wallet = schema.wallet(approval.to).ok_or(Error::ReceiverNotFound)?;
+
+        schema.create_approve_transaction(
+            sender_wallet,
+            receiver_wallet,
+            approval.amount,
+            arg,
+            tx_hash,
+        )?;
+        Ok(())
+    }
 
-        // both freezed_balance and balance
-        // have to be bigger than amount
-        if amount > sender_wallet.balance && amount > sender_wallet.freezed_balance {
-            Err(Error::InsufficientCurrencyAmount.into())
-        } else {
-            schema.create_approve_transaction(sender_wallet, receiver_wallet, amount, arg.clone(), tx_hash);
-            Ok(())
+    fn tx_reject(&self, context: ExecutionContext<'_>, arg: TxReject) -> Self::Output {
+        // Getting schema
+        let (approver, tx_hash) = extract_info(&context)?;
+        let mut schema = SchemaImpl::new(context.service_data());
+
+        // Check the approval is pending
+        let approval = schema
+            .public
+            .approval_transactions
+            .get(&arg.approval_hash)
+            .ok_or(Error::ApprovalNotFound)?;
+        // Check the approval is not expired. Expired approvals are released only after
+        // all transactions in the block are executed.
+        let height = context.data().for_core().next_height();
+        if approval.is_expired(height) {
+            return Err(Error::ApprovalNotFound.into());
+        }
+        // Check the caller is the designated approver
+        if approval.approver != approver {
+            return Err(Error::NotApprover.into());
         }
+        // Check sender's wallet exists
+        let sender_wallet = schema.wallet(approval.from).ok_or(Error::SenderNotFound)?;
+
+        schema.create_reject_transaction(
+            sender_wallet,
+            approval.to,
+            approval.amount,
+            arg,
+            tx_hash,
+        )?;
+        Ok(())
     }
 
     fn issue(&self, context: ExecutionContext<'_>, arg: Issue) -> Self::Output {
@@ -272,7 +376,7 @@ impl CryptocurrencyInterface<ExecutionContext<'_>> for CryptocurrencyService {
         let mut schema = SchemaImpl::new(context.service_data());
         if let Some(wallet) = schema.wallet(from) {
             let amount = arg.amount;
-            schema.increase_wallet_balance(wallet, amount, tx_hash);
+            schema.increase_wallet_balance(wallet, from, amount, tx_hash)?;
             Ok(())
         } else {
             Err(Error::ReceiverNotFound.into())
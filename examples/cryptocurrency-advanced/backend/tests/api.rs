@@ -20,11 +20,13 @@
 use exonum::{
     blockchain::IndexProof,
     crypto::{Hash, KeyPair, PublicKey},
+    helpers::Height,
     merkledb::ObjectHash,
     messages::{AnyTx, Verified},
     runtime::{Caller, CallerAddress, SnapshotExt},
 };
 use exonum_explorer_service::ExplorerFactory;
+use exonum_rust_runtime::api;
 use exonum_testkit::{
     explorer::api::{TransactionQuery, TransactionResponse},
     ApiKind, Spec, TestKit, TestKitApi, TestKitBuilder,
@@ -33,12 +35,16 @@ use serde_json::json;
 
 // Import data types used in tests from the crate where the service is defined.
 use exonum_cryptocurrency_advanced::{
-    api::{WalletInfo, WalletQuery},
+    api::{
+        EventLogProof, EventLogQuery, WalletHistoryFullProof, WalletHistoryPage,
+        WalletHistoryPageQuery, WalletHistoryProof, WalletHistoryQuery, WalletInfo, WalletQuery,
+        MAX_HISTORY_PAGE_LIMIT,
+    },
+    event::{TransferEvent, TransferEventKind},
     schema::Schema,
-    transactions::{CreateWallet, Transfer},
-    transactions::{TxSendApprove, TxApprove},
+    transactions::{CreateWallet, Issue, Transfer, TxApprove, TxReject, TxSendApprove},
     wallet::Wallet,
-    CryptocurrencyInterface, CryptocurrencyService,
+    Config, CryptocurrencyInterface, CryptocurrencyService,
 };
 
 /// Alice's wallets name.
@@ -81,7 +87,7 @@ async fn test_approve_after_tx_send() {
     api.assert_tx_status(tx_send_approve_result.object_hash(), &json!({ "type": "success" })).await;
 
     // Create approve transaction
-    let tx_approve = TxApprove::new(author_address(&tx_alice), author_address(&tx_bob), TRANSFER_AMOUNT);
+    let tx_approve = TxApprove::new(tx_send_approve_result.object_hash(), TRANSFER_AMOUNT);
     let tx_approve_result = _approver.tx_approve(SERVICE_ID, tx_approve);
 
     // Execute approve transaction
@@ -106,6 +112,195 @@ async fn test_approve_after_tx_send() {
     assert_eq!(approver_wallet.freezed_balance, 0);
 }
 
+/// Freezes 10$ of 'alice' and approves transferring 6$ of them to 'bob'.
+/// Checks that the rest is released to 'alice' and the changes are recorded in the history
+/// and the event log.
+#[tokio::test]
+async fn test_partial_approve() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const FREEZED_AMOUNT: u64 = 10;
+    const APPROVED_AMOUNT: u64 = 6;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+    let alice_address = author_address(&tx_alice);
+    let bob_address = author_address(&tx_bob);
+
+    let tx_send_approve =
+        TxSendApprove::new(bob_address, FREEZED_AMOUNT, author_address(&tx_approver));
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+
+    let tx_approve = TxApprove::new(tx_send_approve.object_hash(), APPROVED_AMOUNT);
+    let tx_approve = approver.tx_approve(SERVICE_ID, tx_approve);
+    api.transfer(&tx_approve).await;
+    testkit.create_block();
+    api.assert_tx_status(tx_approve.object_hash(), &json!({ "type": "success" }))
+        .await;
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_none());
+
+    let alice_wallet = schema.wallets.get(&alice_address).unwrap();
+    assert_eq!(
+        alice_wallet.balance,
+        INITIAL_WALLET_BALANCE - APPROVED_AMOUNT
+    );
+    assert_eq!(alice_wallet.freezed_balance, 0);
+    assert_eq!(alice_wallet.history_len, 3);
+
+    let bob_wallet = schema.wallets.get(&bob_address).unwrap();
+    assert_eq!(bob_wallet.balance, INITIAL_WALLET_BALANCE + APPROVED_AMOUNT);
+    assert_eq!(bob_wallet.freezed_balance, 0);
+    assert_eq!(bob_wallet.history_len, 2);
+
+    let events: Vec<_> = schema.events.iter_from(4).collect();
+    let tx_hash = tx_approve.object_hash();
+    let expected_events = vec![
+        TransferEvent::new(
+            alice_address,
+            bob_address,
+            APPROVED_AMOUNT,
+            tx_hash,
+            TransferEventKind::Debit,
+        ),
+        TransferEvent::new(
+            alice_address,
+            bob_address,
+            FREEZED_AMOUNT - APPROVED_AMOUNT,
+            tx_hash,
+            TransferEventKind::Release,
+        ),
+        TransferEvent::new(
+            alice_address,
+            bob_address,
+            APPROVED_AMOUNT,
+            tx_hash,
+            TransferEventKind::Credit,
+        ),
+    ];
+    assert_eq!(events, expected_events);
+}
+
+/// Freezes 10$ of 'alice' and approves transferring none of them to 'bob'.
+/// Checks that all freezed funds are released to 'alice' and 'bob' is not changed.
+#[tokio::test]
+async fn test_zero_approve() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const FREEZED_AMOUNT: u64 = 10;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+    let alice_address = author_address(&tx_alice);
+    let bob_address = author_address(&tx_bob);
+
+    let tx_send_approve =
+        TxSendApprove::new(bob_address, FREEZED_AMOUNT, author_address(&tx_approver));
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+
+    let tx_approve = TxApprove::new(tx_send_approve.object_hash(), 0);
+    let tx_approve = approver.tx_approve(SERVICE_ID, tx_approve);
+    api.transfer(&tx_approve).await;
+    testkit.create_block();
+    api.assert_tx_status(tx_approve.object_hash(), &json!({ "type": "success" }))
+        .await;
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_none());
+
+    let alice_wallet = schema.wallets.get(&alice_address).unwrap();
+    assert_eq!(alice_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(alice_wallet.freezed_balance, 0);
+    assert_eq!(alice_wallet.history_len, 3);
+
+    let bob_wallet = schema.wallets.get(&bob_address).unwrap();
+    assert_eq!(bob_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(bob_wallet.history_len, 1);
+
+    let events: Vec<_> = schema.events.iter_from(4).collect();
+    let expected_event = TransferEvent::new(
+        alice_address,
+        bob_address,
+        FREEZED_AMOUNT,
+        tx_approve.object_hash(),
+        TransferEventKind::Release,
+    );
+    assert_eq!(events, vec![expected_event]);
+}
+
+/// Freezes 10$ of 'alice' and approves transferring more than freezed.
+/// Checks that the approval is rejected and the transfer is still pending.
+#[tokio::test]
+async fn test_approve_more_than_freezed() {
+    const FREEZED_AMOUNT: u64 = 10;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_bob),
+        FREEZED_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    let wallets_hash = schema.wallets.object_hash();
+
+    let tx_approve = TxApprove::new(tx_send_approve.object_hash(), FREEZED_AMOUNT + 1);
+    let tx_approve = approver.tx_approve(SERVICE_ID, tx_approve);
+    api.transfer(&tx_approve).await;
+    testkit.create_block();
+    let expected_status = json!({
+        "type": "service_error",
+        "code": 10,
+        "description": "Approved amount exceeds the freezed amount of the transfer.\n\n\
+                        Can be emitted by `TxApprove`.",
+        "runtime_id": 0,
+        "call_site": {
+            "call_type": "method",
+            "instance_id": SERVICE_ID,
+            "method_id": 4,
+        },
+    });
+    api.assert_tx_status(tx_approve.object_hash(), &expected_status)
+        .await;
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert_eq!(schema.wallets.object_hash(), wallets_hash);
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_some());
+}
+
 /// $ = 'some money'
 /// Makes transfer (10$) from 'alice' (100$ init) to 'bob' (100$ init) with approver
 /// Checks that alice.freezed_balance has to change to transfer_amount
@@ -247,8 +442,8 @@ async fn test_approve_overcharge() {
     let (tx_approver, _approver) = api.create_wallet(APPROVER_NAME).await;
     testkit.create_block();
 
-    // Create transfer with approval transaction: 10$ from 'alice' to 'bob' with 'approver'
-    let tx_approve = TxApprove::new(author_address(&tx_alice), author_address(&tx_bob), TRANSFER_AMOUNT);
+    // Approve a transfer which was never sent
+    let tx_approve = TxApprove::new(tx_alice.object_hash(), TRANSFER_AMOUNT);
     let tx_approve_result = _approver.tx_approve(SERVICE_ID, tx_approve);
 
     // Execute approve transaction
@@ -271,6 +466,852 @@ async fn test_approve_overcharge() {
     assert_eq!(approver_wallet.freezed_balance, 0);
 }
 
+/// Checks that only the designated approver can approve the transfer.
+#[tokio::test]
+async fn test_approve_by_non_approver() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const TRANSFER_AMOUNT: u64 = 10;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, _approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_bob),
+        TRANSFER_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    let wallets_hash = schema.wallets.object_hash();
+
+    // 'bob' is not the approver of the transfer.
+    let tx_approve = bob.tx_approve(
+        SERVICE_ID,
+        TxApprove::new(tx_send_approve.object_hash(), TRANSFER_AMOUNT),
+    );
+    api.transfer(&tx_approve).await;
+    testkit.create_block();
+    let expected_status = json!({
+        "type": "service_error",
+        "code": 8,
+        "description": "Caller is not the approver of the transaction.\n\n\
+                        Can be emitted by `TxApprove` or `TxReject`.",
+        "runtime_id": 0,
+        "call_site": {
+            "call_type": "method",
+            "instance_id": SERVICE_ID,
+            "method_id": 4,
+        },
+    });
+    api.assert_tx_status(tx_approve.object_hash(), &expected_status)
+        .await;
+
+    // The transfer is still pending and wallets are not changed.
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_some());
+    assert_eq!(schema.wallets.object_hash(), wallets_hash);
+    let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+    assert_eq!(alice_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(alice_wallet.freezed_balance, TRANSFER_AMOUNT);
+}
+
+/// Freezes 10$ of 'alice', rejects the transfer and then tries to approve it.
+/// Checks that the approval fails and the released funds are not transferred.
+#[tokio::test]
+async fn test_approve_after_reject() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const TRANSFER_AMOUNT: u64 = 10;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_bob),
+        TRANSFER_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+    let tx_reject = approver.tx_reject(SERVICE_ID, TxReject::new(tx_send_approve.object_hash()));
+    api.transfer(&tx_reject).await;
+    testkit.create_block();
+    api.assert_tx_status(tx_reject.object_hash(), &json!({ "type": "success" }))
+        .await;
+
+    let tx_approve = TxApprove::new(tx_send_approve.object_hash(), TRANSFER_AMOUNT);
+    let tx_approve = approver.tx_approve(SERVICE_ID, tx_approve);
+    api.transfer(&tx_approve).await;
+    testkit.create_block();
+    api.assert_tx_status(tx_approve.object_hash(), &approval_not_found_status())
+        .await;
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approved_transactions
+        .get(&tx_approve.object_hash())
+        .is_none());
+    let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+    assert_eq!(alice_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(alice_wallet.freezed_balance, 0);
+    let bob_wallet = schema.wallets.get(&author_address(&tx_bob)).unwrap();
+    assert_eq!(bob_wallet.balance, INITIAL_WALLET_BALANCE);
+}
+
+/// Freezes 10$ of 'alice', lets the approval expire and then tries to approve it.
+/// Checks that the approval fails and the released funds are not transferred.
+#[tokio::test]
+async fn test_approve_after_expiry() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const TRANSFER_AMOUNT: u64 = 10;
+    const APPROVAL_EXPIRY: u64 = 3;
+
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        approval_expiry: APPROVAL_EXPIRY,
+    });
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_bob),
+        TRANSFER_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    let block = testkit.create_block();
+    let expires_at = Height(block.height().0 + APPROVAL_EXPIRY);
+
+    // Let the approval expire.
+    while testkit.height() < expires_at {
+        testkit.create_block();
+    }
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_none());
+
+    let tx_approve = TxApprove::new(tx_send_approve.object_hash(), TRANSFER_AMOUNT);
+    let tx_approve = approver.tx_approve(SERVICE_ID, tx_approve);
+    api.transfer(&tx_approve).await;
+    testkit.create_block();
+    api.assert_tx_status(tx_approve.object_hash(), &approval_not_found_status())
+        .await;
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+    assert_eq!(alice_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(alice_wallet.freezed_balance, 0);
+    let bob_wallet = schema.wallets.get(&author_address(&tx_bob)).unwrap();
+    assert_eq!(bob_wallet.balance, INITIAL_WALLET_BALANCE);
+}
+
+/// Freezes 10$ of 'alice' and tries to approve the transfer in the block at which
+/// the approval expires. Checks that the approval fails and the funds are released.
+#[tokio::test]
+async fn test_approve_at_expiry_height() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const TRANSFER_AMOUNT: u64 = 10;
+    const APPROVAL_EXPIRY: u64 = 3;
+
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        approval_expiry: APPROVAL_EXPIRY,
+    });
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_bob),
+        TRANSFER_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    let block = testkit.create_block();
+    let expires_at = Height(block.height().0 + APPROVAL_EXPIRY);
+
+    // Stop right before the block at which the approval expires.
+    while testkit.height().next() < expires_at {
+        testkit.create_block();
+    }
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_some());
+
+    let tx_approve = TxApprove::new(tx_send_approve.object_hash(), TRANSFER_AMOUNT);
+    let tx_approve = approver.tx_approve(SERVICE_ID, tx_approve);
+    api.transfer(&tx_approve).await;
+    let block = testkit.create_block();
+    assert_eq!(block.height(), expires_at);
+    api.assert_tx_status(tx_approve.object_hash(), &approval_not_found_status())
+        .await;
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_none());
+    let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+    assert_eq!(alice_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(alice_wallet.freezed_balance, 0);
+    let bob_wallet = schema.wallets.get(&author_address(&tx_bob)).unwrap();
+    assert_eq!(bob_wallet.balance, INITIAL_WALLET_BALANCE);
+}
+
+/// Returns the expected status of `TxApprove` for a transfer which is not pending.
+fn approval_not_found_status() -> serde_json::Value {
+    json!({
+        "type": "service_error",
+        "code": 7,
+        "description": "Transaction with approval doesn't exist or is already approved, \
+                        rejected or expired.\n\nCan be emitted by `TxApprove` or `TxReject`.",
+        "runtime_id": 0,
+        "call_site": {
+            "call_type": "method",
+            "instance_id": SERVICE_ID,
+            "method_id": 4,
+        },
+    })
+}
+
+/// Sends a transfer with approval of zero amount. Checks that `TxSendApprove`
+/// is rejected and no funds are freezed.
+#[tokio::test]
+async fn test_tx_send_approve_zero_amount() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, _approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve =
+        TxSendApprove::new(author_address(&tx_bob), 0, author_address(&tx_approver));
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+
+    let expected_status = json!({
+        "type": "service_error",
+        "code": 9,
+        "description": "Transfer amount is zero.\n\nCan be emitted by `TxSendApprove`.",
+        "runtime_id": 0,
+        "call_site": {
+            "call_type": "method",
+            "instance_id": SERVICE_ID,
+            "method_id": 3,
+        },
+    });
+    api.assert_tx_status(tx_send_approve.object_hash(), &expected_status)
+        .await;
+
+    // Check that no approvals are pending and wallets are not changed.
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert_eq!(schema.approval_transactions.iter().count(), 0);
+    for tx in &[&tx_alice, &tx_bob] {
+        let wallet = schema.wallets.get(&author_address(tx)).unwrap();
+        assert_eq!(wallet.balance, INITIAL_WALLET_BALANCE);
+        assert_eq!(wallet.freezed_balance, 0);
+    }
+}
+
+/// Sends a transfer with approval from 'alice' to herself. Checks that `TxSendApprove`
+/// is rejected and no funds are freezed.
+#[tokio::test]
+async fn test_tx_send_approve_to_self() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const TRANSFER_AMOUNT: u64 = 10;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_approver, _approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_alice),
+        TRANSFER_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+
+    let expected_status = json!({
+        "type": "service_error",
+        "code": 4,
+        "description": "Sender are same as receiver.\n\n\
+                        Can be emitted by `Transfer` or `TxSendApprove`.",
+        "runtime_id": 0,
+        "call_site": {
+            "call_type": "method",
+            "instance_id": SERVICE_ID,
+            "method_id": 3,
+        },
+    });
+    api.assert_tx_status(tx_send_approve.object_hash(), &expected_status)
+        .await;
+
+    // Check that no approvals are pending and the wallet is not changed.
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert_eq!(schema.approval_transactions.iter().count(), 0);
+    let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+    assert_eq!(alice_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(alice_wallet.freezed_balance, 0);
+}
+
+/// Freezes 10$ of 'alice' and lets the approval expire without the approver's decision.
+/// Checks that the freezed balance is released and the approval is removed.
+#[tokio::test]
+async fn test_approval_expiry() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const TRANSFER_AMOUNT: u64 = 10;
+    const APPROVAL_EXPIRY: u64 = 3;
+
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        approval_expiry: APPROVAL_EXPIRY,
+    });
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, _approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_bob),
+        TRANSFER_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx).await;
+    let block = testkit.create_block();
+    api.assert_tx_status(tx.object_hash(), &json!({ "type": "success" }))
+        .await;
+    let expires_at = Height(block.height().0 + APPROVAL_EXPIRY);
+
+    // The approval is pending until it expires.
+    while testkit.height().next() < expires_at {
+        let snapshot = testkit.snapshot();
+        let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+        let approval = schema.approval_transactions.get(&tx.object_hash()).unwrap();
+        assert_eq!(approval.expires_at, expires_at);
+        let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+        assert_eq!(alice_wallet.freezed_balance, TRANSFER_AMOUNT);
+        testkit.create_block();
+    }
+
+    // The freezed balance is released at the expiry height.
+    testkit.create_block();
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx.object_hash())
+        .is_none());
+
+    let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+    assert_eq!(alice_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(alice_wallet.freezed_balance, 0);
+
+    let bob_wallet = schema.wallets.get(&author_address(&tx_bob)).unwrap();
+    assert_eq!(bob_wallet.balance, INITIAL_WALLET_BALANCE);
+}
+
+/// Freezes 10$ of 'alice' and then rejects the transfer by the approver.
+/// Checks that the freezed balance is released and the rejection is recorded.
+#[tokio::test]
+async fn test_reject_after_tx_send() {
+    const INITIAL_WALLET_BALANCE: u64 = 100;
+    const TRANSFER_AMOUNT: u64 = 10;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    // Freeze 10$ of 'alice'.
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_bob),
+        TRANSFER_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+    api.assert_tx_status(tx_send_approve.object_hash(), &json!({ "type": "success" }))
+        .await;
+
+    let wallet = api.get_wallet(tx_alice.author()).await.unwrap();
+    assert_eq!(wallet.freezed_balance, TRANSFER_AMOUNT);
+
+    // Reject the transfer.
+    let tx_reject = approver.tx_reject(SERVICE_ID, TxReject::new(tx_send_approve.object_hash()));
+    api.transfer(&tx_reject).await;
+    testkit.create_block();
+    api.assert_tx_status(tx_reject.object_hash(), &json!({ "type": "success" }))
+        .await;
+
+    // Check the balances and the approval records via public schema.
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_none());
+    let rejection = schema
+        .rejected_transactions
+        .get(&tx_reject.object_hash())
+        .unwrap();
+    assert_eq!(rejection.approval_hash, tx_send_approve.object_hash());
+
+    let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+    assert_eq!(alice_wallet.balance, INITIAL_WALLET_BALANCE);
+    assert_eq!(alice_wallet.freezed_balance, 0);
+
+    let bob_wallet = schema.wallets.get(&author_address(&tx_bob)).unwrap();
+    assert_eq!(bob_wallet.balance, INITIAL_WALLET_BALANCE);
+
+    // The transfer cannot be rejected twice.
+    let tx_reject = approver.tx_reject(SERVICE_ID, TxReject::new(tx_send_approve.object_hash()));
+    api.transfer(&tx_reject).await;
+    testkit.create_block();
+    let expected_status = json!({
+        "type": "service_error",
+        "code": 7,
+        "description": "Transaction with approval doesn't exist or is already approved, \
+                        rejected or expired.\n\nCan be emitted by `TxApprove` or `TxReject`.",
+        "runtime_id": 0,
+        "call_site": {
+            "call_type": "method",
+            "instance_id": SERVICE_ID,
+            "method_id": 5,
+        },
+    });
+    api.assert_tx_status(tx_reject.object_hash(), &expected_status)
+        .await;
+}
+
+/// Checks that only the designated approver can reject the transfer.
+#[tokio::test]
+async fn test_reject_by_non_approver() {
+    const TRANSFER_AMOUNT: u64 = 10;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, bob) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, _approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+
+    let tx_send_approve = TxSendApprove::new(
+        author_address(&tx_bob),
+        TRANSFER_AMOUNT,
+        author_address(&tx_approver),
+    );
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+
+    // 'bob' is not the approver of the transfer.
+    let tx_reject = bob.tx_reject(SERVICE_ID, TxReject::new(tx_send_approve.object_hash()));
+    api.transfer(&tx_reject).await;
+    testkit.create_block();
+    let expected_status = json!({
+        "type": "service_error",
+        "code": 8,
+        "description": "Caller is not the approver of the transaction.\n\n\
+                        Can be emitted by `TxApprove` or `TxReject`.",
+        "runtime_id": 0,
+        "call_site": {
+            "call_type": "method",
+            "instance_id": SERVICE_ID,
+            "method_id": 5,
+        },
+    });
+    api.assert_tx_status(tx_reject.object_hash(), &expected_status)
+        .await;
+
+    // The transfer is still pending.
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert!(schema
+        .approval_transactions
+        .get(&tx_send_approve.object_hash())
+        .is_some());
+    let alice_wallet = schema.wallets.get(&author_address(&tx_alice)).unwrap();
+    assert_eq!(alice_wallet.freezed_balance, TRANSFER_AMOUNT);
+}
+
+/// Checks that the proof for a range of wallet history can be verified against the wallet record.
+#[tokio::test]
+async fn test_wallet_history_proof() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _) = api.create_wallet(BOB_NAME).await;
+    testkit.create_block();
+
+    let mut transfers = vec![];
+    for seed in 0..3 {
+        let tx = alice.transfer(
+            SERVICE_ID,
+            Transfer {
+                to: author_address(&tx_bob),
+                amount: 10,
+                seed,
+            },
+        );
+        api.transfer(&tx).await;
+        transfers.push(tx);
+    }
+    testkit.create_block();
+
+    let wallet = api.get_wallet(tx_alice.author()).await.unwrap();
+    assert_eq!(wallet.history_len, 4);
+
+    // Full history: the wallet creation and the transfers.
+    let history_proof = api.get_history_proof(tx_alice.author(), None, None).await;
+    assert_eq!(history_proof.history_hash, wallet.history_hash);
+    let checked_proof = history_proof
+        .proof
+        .check_against_hash(wallet.history_hash)
+        .unwrap();
+    assert!(checked_proof.indexes().eq(0..4));
+    assert_eq!(checked_proof.entries()[0].1, tx_alice.object_hash());
+
+    // Part of the history.
+    let history_proof = api
+        .get_history_proof(tx_alice.author(), Some(1), Some(3))
+        .await;
+    let checked_proof = history_proof
+        .proof
+        .check_against_hash(wallet.history_hash)
+        .unwrap();
+    assert_eq!(checked_proof.list_len(), 4);
+    assert!(checked_proof.indexes().eq(1..3));
+    // Transfers may be included into the block in any order.
+    let transfer_hashes: Vec<_> = transfers.iter().map(ObjectHash::object_hash).collect();
+    for (_, tx_hash) in checked_proof.entries() {
+        assert!(transfer_hashes.contains(tx_hash));
+    }
+
+    // Illegal range.
+    let err = api
+        .inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&WalletHistoryQuery {
+            pub_key: tx_alice.author(),
+            from: Some(3),
+            to: Some(1),
+        })
+        .get::<WalletHistoryProof>("v1/wallets/history_proof")
+        .await
+        .unwrap_err();
+    assert_eq!(err.http_code, api::HttpStatusCode::BAD_REQUEST);
+}
+
+/// Checks that the chain from the block header to transactions in the wallet history
+/// can be verified end to end.
+#[tokio::test]
+async fn test_wallet_history_full_proof() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _) = api.create_wallet(BOB_NAME).await;
+    testkit.create_block();
+
+    let tx_transfer = alice.transfer(
+        SERVICE_ID,
+        Transfer {
+            to: author_address(&tx_bob),
+            amount: 10,
+            seed: 0,
+        },
+    );
+    api.transfer(&tx_transfer).await;
+    testkit.create_block();
+
+    let full_proof: WalletHistoryFullProof = api
+        .inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&WalletQuery {
+            pub_key: tx_alice.author(),
+        })
+        .get("v1/wallets/history_full_proof")
+        .await
+        .unwrap();
+
+    // Block header -> wallets table.
+    let index_proof = IndexProof::new(full_proof.block_proof, full_proof.wallet_proof.to_table);
+    let (index_name, index_hash) = index_proof.verify(&api.validator_keys).unwrap();
+    assert_eq!(index_name, format!("{}.wallets", SERVICE_NAME));
+
+    // Wallets table -> wallet record.
+    let to_wallet = full_proof
+        .wallet_proof
+        .to_wallet
+        .check_against_hash(index_hash)
+        .unwrap();
+    let address = author_address(&tx_alice);
+    let (_, wallet) = to_wallet
+        .entries()
+        .find(|(&key, _)| key == address)
+        .unwrap();
+    assert_eq!(wallet.balance, 90);
+
+    // Wallet record -> wallet history.
+    let checked_history = full_proof
+        .history_proof
+        .check_against_hash(wallet.history_hash)
+        .unwrap();
+    assert_eq!(checked_history.list_len(), wallet.history_len);
+    let history: Vec<_> = checked_history
+        .entries()
+        .iter()
+        .map(|(_, hash)| *hash)
+        .collect();
+    assert_eq!(
+        history,
+        vec![tx_alice.object_hash(), tx_transfer.object_hash()]
+    );
+
+    // The proof is not provided for unknown wallets.
+    let err = api
+        .inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&WalletQuery {
+            pub_key: KeyPair::random().public_key(),
+        })
+        .get::<WalletHistoryFullProof>("v1/wallets/history_full_proof")
+        .await
+        .unwrap_err();
+    assert_eq!(err.http_code, api::HttpStatusCode::NOT_FOUND);
+}
+
+/// Checks that balance changes are recorded in the event log and the proof for a range
+/// of the log can be verified against the block header.
+#[tokio::test]
+async fn test_event_log_proof() {
+    const TRANSFER_AMOUNT: u64 = 10;
+    const FREEZED_AMOUNT: u64 = 5;
+
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _) = api.create_wallet(BOB_NAME).await;
+    let (tx_approver, approver) = api.create_wallet(APPROVER_NAME).await;
+    testkit.create_block();
+    let alice_address = author_address(&tx_alice);
+    let bob_address = author_address(&tx_bob);
+
+    let tx_transfer = alice.transfer(
+        SERVICE_ID,
+        Transfer {
+            to: bob_address,
+            amount: TRANSFER_AMOUNT,
+            seed: 0,
+        },
+    );
+    api.transfer(&tx_transfer).await;
+    testkit.create_block();
+    let tx_send_approve =
+        TxSendApprove::new(bob_address, FREEZED_AMOUNT, author_address(&tx_approver));
+    let tx_send_approve = alice.tx_send_approve(SERVICE_ID, tx_send_approve);
+    api.transfer(&tx_send_approve).await;
+    testkit.create_block();
+    let tx_reject = approver.tx_reject(SERVICE_ID, TxReject::new(tx_send_approve.object_hash()));
+    api.transfer(&tx_reject).await;
+    testkit.create_block();
+    api.assert_tx_status(tx_reject.object_hash(), &json!({ "type": "success" }))
+        .await;
+
+    // Skip the events of the wallet creation.
+    let event_log: EventLogProof = api
+        .inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&EventLogQuery {
+            from: Some(3),
+            to: None,
+        })
+        .get("v1/events/proof")
+        .await
+        .unwrap();
+
+    // Block header -> event log.
+    let index_proof = IndexProof::new(event_log.block_proof, event_log.to_table);
+    let (index_name, index_hash) = index_proof.verify(&api.validator_keys).unwrap();
+    assert_eq!(index_name, format!("{}.events", SERVICE_NAME));
+
+    // Event log -> events.
+    let checked_events = event_log.proof.check_against_hash(index_hash).unwrap();
+    assert_eq!(checked_events.list_len(), 7);
+    let events: Vec<_> = checked_events
+        .entries()
+        .iter()
+        .map(|(_, event)| event.clone())
+        .collect();
+    let expected_events = vec![
+        TransferEvent::new(
+            alice_address,
+            bob_address,
+            TRANSFER_AMOUNT,
+            tx_transfer.object_hash(),
+            TransferEventKind::Debit,
+        ),
+        TransferEvent::new(
+            alice_address,
+            bob_address,
+            TRANSFER_AMOUNT,
+            tx_transfer.object_hash(),
+            TransferEventKind::Credit,
+        ),
+        TransferEvent::new(
+            alice_address,
+            bob_address,
+            FREEZED_AMOUNT,
+            tx_send_approve.object_hash(),
+            TransferEventKind::Freeze,
+        ),
+        TransferEvent::new(
+            alice_address,
+            bob_address,
+            FREEZED_AMOUNT,
+            tx_reject.object_hash(),
+            TransferEventKind::Release,
+        ),
+    ];
+    assert_eq!(events, expected_events);
+
+    // The event log is also available via the public schema. The wallets are created
+    // in the same block, so the order of their events is not specified.
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    let wallet_creation = TransferEvent::new(
+        alice_address,
+        alice_address,
+        100,
+        tx_alice.object_hash(),
+        TransferEventKind::Credit,
+    );
+    assert!(schema
+        .events
+        .iter()
+        .take(3)
+        .any(|event| event == wallet_creation));
+
+    // Invalid ranges are rejected.
+    let err = api
+        .inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&EventLogQuery {
+            from: Some(5),
+            to: Some(3),
+        })
+        .get::<EventLogProof>("v1/events/proof")
+        .await
+        .unwrap_err();
+    assert_eq!(err.http_code, api::HttpStatusCode::BAD_REQUEST);
+}
+
+/// Checks that wallet history can be retrieved page by page.
+#[tokio::test]
+async fn test_wallet_history_pages() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, _) = api.create_wallet(BOB_NAME).await;
+    testkit.create_block();
+
+    for seed in 0..4 {
+        let tx = alice.transfer(
+            SERVICE_ID,
+            Transfer {
+                to: author_address(&tx_bob),
+                amount: 10,
+                seed,
+            },
+        );
+        api.transfer(&tx).await;
+    }
+    testkit.create_block();
+
+    let full_page = api.get_history_page(tx_alice.author(), 0, 100).await;
+    assert_eq!(full_page.total, 5);
+    assert_eq!(full_page.transactions.len(), 5);
+    assert_eq!(full_page.transactions[0], tx_alice.object_hash());
+
+    let page = api.get_history_page(tx_alice.author(), 1, 2).await;
+    assert_eq!(page.total, 5);
+    assert_eq!(page.transactions, full_page.transactions[1..3]);
+
+    // The last page may be incomplete.
+    let page = api.get_history_page(tx_alice.author(), 4, 2).await;
+    assert_eq!(page.transactions, full_page.transactions[4..]);
+
+    // Offset past the end of the history.
+    let page = api.get_history_page(tx_alice.author(), 10, 2).await;
+    assert_eq!(page.total, 5);
+    assert!(page.transactions.is_empty());
+
+    // The largest allowed limit.
+    let page = api
+        .get_history_page(tx_alice.author(), 0, MAX_HISTORY_PAGE_LIMIT)
+        .await;
+    assert_eq!(page.transactions, full_page.transactions);
+
+    // Limits exceeding the maximum are rejected.
+    for &limit in &[MAX_HISTORY_PAGE_LIMIT + 1, usize::max_value()] {
+        let err = api
+            .inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&WalletHistoryPageQuery {
+                pub_key: tx_alice.author(),
+                offset: 0,
+                limit,
+            })
+            .get::<WalletHistoryPage>("v1/wallets/history")
+            .await
+            .unwrap_err();
+        assert_eq!(err.http_code, api::HttpStatusCode::BAD_REQUEST);
+    }
+}
+
 /// Check that the wallet creation transaction works when invoked via API.
 #[tokio::main]
 #[test]
@@ -498,6 +1539,71 @@ async fn test_transfer_overcharge() {
     assert_eq!(wallet.balance, 100);
 }
 
+/// Checks that issuing or transferring funds fails if the balance of the receiver would exceed
+/// the maximum value, and that wallets are not changed in this case.
+#[tokio::test]
+async fn test_balance_overflow() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, alice) = api.create_wallet(ALICE_NAME).await;
+    let (tx_bob, bob) = api.create_wallet(BOB_NAME).await;
+    testkit.create_block();
+
+    // Make Bob's balance the maximum possible.
+    let tx = bob.issue(
+        SERVICE_ID,
+        Issue {
+            amount: u64::max_value() - 100,
+            seed: 0,
+        },
+    );
+    api.transfer(&tx).await;
+    testkit.create_block();
+    api.assert_tx_status(tx.object_hash(), &json!({ "type": "success" }))
+        .await;
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    let wallets_hash = schema.wallets.object_hash();
+
+    let tx_issue = bob.issue(SERVICE_ID, Issue { amount: 1, seed: 1 });
+    let tx_transfer = alice.transfer(
+        SERVICE_ID,
+        Transfer {
+            to: author_address(&tx_bob),
+            amount: 10,
+            seed: 0,
+        },
+    );
+    api.transfer(&tx_issue).await;
+    api.transfer(&tx_transfer).await;
+    testkit.create_block();
+
+    for (tx_hash, method_id) in vec![(tx_issue.object_hash(), 1), (tx_transfer.object_hash(), 0)] {
+        let expected_status = json!({
+            "type": "service_error",
+            "code": 6,
+            "description": "Wallet balance would become negative or exceed the maximum value.\n\n\
+                            Can be emitted by `Transfer`, `Issue`, `TxSendApprove` or `TxApprove`.",
+            "runtime_id": 0,
+            "call_site": {
+                "call_type": "method",
+                "instance_id": SERVICE_ID,
+                "method_id": method_id,
+            },
+        });
+        api.assert_tx_status(tx_hash, &expected_status).await;
+    }
+
+    let snapshot = testkit.snapshot();
+    let schema: Schema<_> = snapshot.service_schema(SERVICE_ID).unwrap();
+    assert_eq!(schema.wallets.object_hash(), wallets_hash);
+    let wallet = api.get_wallet(tx_alice.author()).await.unwrap();
+    assert_eq!(wallet.balance, 100);
+    let wallet = api.get_wallet(tx_bob.author()).await.unwrap();
+    assert_eq!(wallet.balance, u64::max_value());
+}
+
 #[tokio::test]
 async fn test_unknown_wallet_request() {
     let (_testkit, api) = create_testkit();
@@ -560,6 +1666,40 @@ impl CryptocurrencyApi {
         wallet.cloned()
     }
 
+    /// Gets a page of wallet history.
+    async fn get_history_page(
+        &self,
+        pub_key: PublicKey,
+        offset: u64,
+        limit: usize,
+    ) -> WalletHistoryPage {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&WalletHistoryPageQuery {
+                pub_key,
+                offset,
+                limit,
+            })
+            .get("v1/wallets/history")
+            .await
+            .unwrap()
+    }
+
+    /// Gets the proof for a range of wallet history.
+    async fn get_history_proof(
+        &self,
+        pub_key: PublicKey,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> WalletHistoryProof {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&WalletHistoryQuery { pub_key, from, to })
+            .get("v1/wallets/history_proof")
+            .await
+            .unwrap()
+    }
+
     /// Sends a transfer transaction over HTTP and checks the synchronous result.
     async fn transfer(&self, tx: &Verified<AnyTx>) {
         let tx_info: TransactionResponse = self
@@ -608,9 +1748,14 @@ impl CryptocurrencyApi {
 
 /// Creates a testkit together with the API wrapper defined above.
 fn create_testkit() -> (TestKit, CryptocurrencyApi) {
+    create_testkit_with_config(Config::default())
+}
+
+/// Creates a testkit with the specified service configuration.
+fn create_testkit_with_config(config: Config) -> (TestKit, CryptocurrencyApi) {
     let mut testkit = TestKitBuilder::validator()
         .with(Spec::new(ExplorerFactory).with_default_instance())
-        .with(Spec::new(CryptocurrencyService).with_instance(SERVICE_ID, SERVICE_NAME, ()))
+        .with(Spec::new(CryptocurrencyService).with_instance(SERVICE_ID, SERVICE_NAME, config))
         .build();
 
     let api = CryptocurrencyApi {
@@ -988,7 +988,7 @@ mod tests {
 
         assert_matches!(
             index_proof.verify(&public_keys).unwrap_err(),
-            ProofError::IncorrectEntryProof(ValidationError::UnmatchedRootHash)
+            ProofError::IncorrectEntryProof(ValidationError::UnmatchedRootHash { .. })
         );
     }
 
@@ -1048,7 +1048,7 @@ mod tests {
         let err = call_proof.verify(&public_keys).unwrap_err();
         assert_matches!(
             err,
-            ProofError::IncorrectEntryProof(ValidationError::UnmatchedRootHash)
+            ProofError::IncorrectEntryProof(ValidationError::UnmatchedRootHash { .. })
         );
     }
 